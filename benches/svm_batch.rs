@@ -0,0 +1,89 @@
+#![feature(test)]
+
+// This is a little weird, see
+// https://github.com/rust-lang/rust/pull/54116#issuecomment-422294107
+// for details
+extern crate test;
+
+mod util;
+
+mod svm_batch {
+    use crate::test::Bencher;
+    use ffsvm::{DenseSVM, FeatureVector, Label, Predict};
+    use simd_aligned::{arch::f32x8, MatSimd, Rows};
+    use std::convert::TryFrom;
+
+    /// Number of samples classified per iteration.
+    const SAMPLES: usize = 256;
+
+    /// Creates an SVM and `SAMPLES` rows of features for it.
+    fn produce_svm(kernel_type: &str, total_sv: u32, num_attributes: u32) -> (DenseSVM, MatSimd<f32x8, Rows>) {
+        let raw_model = super::util::random_dense("c_svc", kernel_type, total_sv, num_attributes);
+        let svm = DenseSVM::try_from(&raw_model).unwrap();
+        let mut features = MatSimd::with_dimension(SAMPLES, num_attributes as usize);
+        let mut flat = features.flat_mut();
+
+        for i in 0..SAMPLES {
+            for j in 0..num_attributes as usize {
+                flat[(i, j)] = (i + j) as f32;
+            }
+        }
+
+        (svm, features)
+    }
+
+    /// Produces a test case classifying all samples one by one via `predict_value`.
+    fn produce_loop(kernel_type: &str, total_sv: u32, num_attributes: u32) -> impl FnMut() {
+        let (svm, features) = produce_svm(kernel_type, total_sv, num_attributes);
+        let mut problem = FeatureVector::from(&svm);
+
+        move || {
+            for i in 0..SAMPLES {
+                problem.features().copy_from_slice(&features.row_as_flat(i)[..svm.attributes()]);
+                svm.predict_value(&mut problem).expect("This should work");
+            }
+        }
+    }
+
+    /// Produces a test case classifying all samples with `predict_values_batch`.
+    fn produce_batch(kernel_type: &str, total_sv: u32, num_attributes: u32) -> impl FnMut() {
+        let (svm, features) = produce_svm(kernel_type, total_sv, num_attributes);
+        let mut labels = vec![Label::None; SAMPLES];
+
+        move || svm.predict_values_batch(&features, &mut labels).expect("This should work")
+    }
+
+    // RBF
+
+    #[bench]
+    fn loop_rbf_sv1024_attr16(b: &mut Bencher) {
+        b.iter(produce_loop("rbf", 1024, 16));
+    }
+
+    #[bench]
+    fn batch_rbf_sv1024_attr16(b: &mut Bencher) {
+        b.iter(produce_batch("rbf", 1024, 16));
+    }
+
+    #[bench]
+    fn loop_rbf_sv1024_attr1024(b: &mut Bencher) {
+        b.iter(produce_loop("rbf", 1024, 1024));
+    }
+
+    #[bench]
+    fn batch_rbf_sv1024_attr1024(b: &mut Bencher) {
+        b.iter(produce_batch("rbf", 1024, 1024));
+    }
+
+    // Poly
+
+    #[bench]
+    fn loop_poly_sv1024_attr1024(b: &mut Bencher) {
+        b.iter(produce_loop("polynomial", 1024, 1024));
+    }
+
+    #[bench]
+    fn batch_poly_sv1024_attr1024(b: &mut Bencher) {
+        b.iter(produce_batch("polynomial", 1024, 1024));
+    }
+}
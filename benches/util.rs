@@ -14,8 +14,8 @@ pub fn random_dense<'b>(svm_type: &'b str, kernel_type: &'b str, total_sv: u32,
         nr_class: 2,
         rho: vec![rng.gen::<f64>()],
         label: vec![0, 1],
-        prob_a: Some(vec![rng.gen::<f64>(), rng.gen::<f64>()]),
-        prob_b: Some(vec![rng.gen::<f64>(), rng.gen::<f64>()]),
+        prob_a: Some(vec![rng.gen::<f64>()]),
+        prob_b: Some(vec![rng.gen::<f64>()]),
         nr_sv: vec![total_sv / 2, total_sv / 2],
     };
 
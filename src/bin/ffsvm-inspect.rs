@@ -0,0 +1,40 @@
+//! Prints statistics of a libSVM model, such as its support vectors per class and attribute ranges.
+#![warn(clippy::all)]
+#![warn(clippy::nursery)]
+#![warn(clippy::pedantic)]
+
+use ffsvm::{Error, ModelFile, ModelStats};
+use std::{
+    convert::TryFrom,
+    env, fs,
+    io::{self, Write},
+    process::ExitCode,
+};
+
+const USAGE: &str = "Usage: ffsvm-inspect model_file";
+
+fn run(model_file: &str) -> Result<(), Error> {
+    let text = fs::read_to_string(model_file)?;
+    let model = ModelFile::try_from(text.as_str())?;
+
+    write!(io::stdout().lock(), "{}", ModelStats::try_from(&model)?)?;
+
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+
+    let [model_file] = args.as_slice() else {
+        eprintln!("{USAGE}");
+        return ExitCode::FAILURE;
+    };
+
+    match run(model_file) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::FAILURE
+        }
+    }
+}
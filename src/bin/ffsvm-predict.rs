@@ -0,0 +1,261 @@
+//! Predicts a libSVM data file, writing the same output as libSVM's `svm-predict`.
+#![warn(clippy::all)]
+#![warn(clippy::nursery)]
+#![warn(clippy::pedantic)]
+#![allow(clippy::cast_precision_loss)]
+
+use ffsvm::{DataReader, DenseSVM, Error, FeatureVector, Label, ModelFile, PrecomputedSVM, Predict, SparseSVM};
+use std::{
+    convert::TryFrom,
+    env,
+    fs::{self, File},
+    io::{BufReader, BufWriter, Write},
+    process::ExitCode,
+};
+
+const USAGE: &str = "\
+Usage: ffsvm-predict [options] test_file model_file output_file
+options:
+-b probability_estimates: whether to predict probability estimates, 0 or 1 (default 0)
+-d representation: whether to load the model as a dense or sparse SVM, dense or sparse (default dense);
+   dense falls back to sparse if the test data has features no support vector uses,
+   models with a precomputed kernel are always loaded as such
+-q : quiet mode (no outputs)";
+
+/// Command line options, named like the ones of `svm-predict`.
+#[derive(Debug, Default)]
+struct Options {
+    probability: bool,
+    sparse: bool,
+    quiet: bool,
+    test_file: String,
+    model_file: String,
+    output_file: String,
+}
+
+impl Options {
+    /// Parses the arguments following the program name, returning `None` if they are invalid.
+    fn parse(mut args: impl Iterator<Item = String>) -> Option<Self> {
+        let mut options = Self::default();
+        let mut files = Vec::new();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "-b" => options.probability = matches!(args.next()?.as_str(), "1"),
+                "-d" => {
+                    options.sparse = match args.next()?.as_str() {
+                        "dense" => false,
+                        "sparse" => true,
+                        _ => return None,
+                    }
+                }
+                "-q" => options.quiet = true,
+                x if x.starts_with('-') => return None,
+                _ => files.push(arg),
+            }
+        }
+
+        let [test_file, model_file, output_file] = <[String; 3]>::try_from(files).ok()?;
+
+        Some(Self { test_file, model_file, output_file, ..options })
+    }
+
+    /// Prints a message unless we run in quiet mode, like `svm-predict`'s `info`.
+    fn info(&self, message: &str) {
+        if !self.quiet {
+            println!("{message}");
+        }
+    }
+}
+
+/// Formats a number like C's `printf("%g")`, which libSVM uses for all its output.
+fn format_g(value: f64) -> String {
+    const PRECISION: i32 = 6;
+
+    if value == 0.0 || !value.is_finite() {
+        return format!("{value}");
+    }
+
+    let scientific = format!("{:.*e}", PRECISION as usize - 1, value);
+    let (mantissa, exponent) = scientific.split_once('e').unwrap();
+    let exponent = exponent.parse::<i32>().unwrap();
+    let trim = |x: &str| if x.contains('.') { x.trim_end_matches('0').trim_end_matches('.').to_string() } else { x.to_string() };
+
+    if (-4..PRECISION).contains(&exponent) {
+        let decimals = usize::try_from(PRECISION - 1 - exponent).unwrap_or_default();
+        trim(&format!("{value:.decimals$}"))
+    } else {
+        format!("{}e{}{:02}", trim(mantissa), if exponent < 0 { '-' } else { '+' }, exponent.abs())
+    }
+}
+
+/// Returns the numeric value of a label, as `svm-predict` would print and compare it.
+fn label_value(label: Label) -> f64 {
+    match label {
+        Label::Class(x) => f64::from(x),
+        Label::Value(x) => f64::from(x),
+        Label::None => f64::NAN,
+    }
+}
+
+/// Accumulates the statistics `svm-predict` reports.
+#[derive(Debug, Default)]
+struct Statistics {
+    correct: usize,
+    total: usize,
+    error: f64,
+    sum_p: f64,
+    sum_t: f64,
+    sum_pp: f64,
+    sum_tt: f64,
+    sum_pt: f64,
+}
+
+impl Statistics {
+    #[allow(clippy::float_cmp)] // Labels are compared exactly, as in `svm-predict`.
+    fn add(&mut self, predicted: f64, target: f64) {
+        if predicted == target {
+            self.correct += 1;
+        }
+
+        self.error += (predicted - target) * (predicted - target);
+        self.sum_p += predicted;
+        self.sum_t += target;
+        self.sum_pp += predicted * predicted;
+        self.sum_tt += target * target;
+        self.sum_pt += predicted * target;
+        self.total += 1;
+    }
+
+    #[allow(clippy::suboptimal_flops)] // Same formulas as `svm-predict`.
+    fn report(&self, options: &Options, regression: bool) {
+        let total = self.total as f64;
+
+        if regression {
+            let covariance = total * self.sum_pt - self.sum_p * self.sum_t;
+            let correlation = covariance * covariance / ((total * self.sum_pp - self.sum_p * self.sum_p) * (total * self.sum_tt - self.sum_t * self.sum_t));
+
+            options.info(&format!("Mean squared error = {} (regression)", format_g(self.error / total)));
+            options.info(&format!("Squared correlation coefficient = {} (regression)", format_g(correlation)));
+        } else {
+            let accuracy = self.correct as f64 / total * 100.0;
+            options.info(&format!("Accuracy = {}% ({}/{}) (classification)", format_g(accuracy), self.correct, self.total));
+        }
+    }
+}
+
+/// Predicts all samples, writing one line per sample to `output`.
+fn predict<T>(
+    svm: &impl Predict<T>,
+    samples: impl Iterator<Item = Result<(f64, FeatureVector<T>), Error>>,
+    output: &mut impl Write,
+    probabilities: bool,
+) -> Result<Statistics, Error> {
+    let mut statistics = Statistics::default();
+
+    for sample in samples {
+        let (target, mut problem) = sample?;
+
+        if probabilities {
+            svm.predict_probability(&mut problem)?;
+        } else {
+            svm.predict_value(&mut problem)?;
+        }
+
+        let predicted = label_value(problem.label());
+        write!(output, "{}", format_g(predicted))?;
+
+        if probabilities {
+            for probability in problem.probabilities() {
+                write!(output, " {}", format_g(*probability))?;
+            }
+        }
+
+        writeln!(output)?;
+        statistics.add(predicted, target);
+    }
+
+    Ok(statistics)
+}
+
+fn run(options: &Options) -> Result<(), Error> {
+    let text = fs::read_to_string(&options.model_file)?;
+    let model = ModelFile::try_from(text.as_str())?;
+    let header = model.header();
+    let classification = matches!(header.svm_type, "c_svc" | "nu_svc");
+    let regression = matches!(header.svm_type, "epsilon_svr" | "nu_svr");
+
+    if options.probability {
+        if header.prob_a.is_none() || header.svm_type == "one_class" {
+            return Err(Error::NoProbabilities);
+        }
+
+        if regression {
+            let sigma = header.prob_a.as_ref().and_then(|x| x.first()).copied().unwrap_or_default();
+            options.info(&format!(
+                "Prob. model for test data: target value = predicted value + z,\nz: Laplace distribution e^(-|z|/sigma)/(2sigma),sigma={}",
+                format_g(sigma)
+            ));
+        }
+    } else if header.prob_a.is_some() {
+        options.info("Model supports probability estimates, but disabled in prediction.");
+    }
+
+    let probabilities = options.probability && classification;
+    let labels = probabilities.then(|| header.label.iter().map(ToString::to_string).collect::<Vec<_>>().join(" "));
+
+    // Creates the output file from scratch, so that a failed attempt to predict leaves nothing behind.
+    macro_rules! predict_file {
+        ($svm:expr) => {{
+            let svm = $svm;
+            let input = BufReader::new(File::open(&options.test_file)?);
+            let mut output = BufWriter::new(File::create(&options.output_file)?);
+
+            if let Some(labels) = &labels {
+                writeln!(output, "labels {labels}")?;
+            }
+
+            let statistics = predict(&svm, DataReader::new(input, &svm), &mut output, probabilities)?;
+            output.flush()?;
+            statistics
+        }};
+    }
+
+    let statistics = if header.kernel_type == "precomputed" {
+        predict_file!(PrecomputedSVM::try_from(&model)?)
+    } else if options.sparse {
+        predict_file!(SparseSVM::try_from(&model)?)
+    } else {
+        let dense = || -> Result<Statistics, Error> { Ok(predict_file!(DenseSVM::try_from(&model)?)) };
+
+        // `svm-train` omits attributes that are `0` in all support vectors, so test data often has features a dense
+        // SVM can't hold. `svm-predict` accepts them, and so does a sparse SVM.
+        match dense() {
+            Err(Error::FeatureIndexOutOfRange { .. } | Error::TooManyAttributes { .. }) => predict_file!(SparseSVM::try_from(&model)?),
+            statistics => statistics?,
+        }
+    };
+
+    statistics.report(options, regression);
+
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let Some(options) = Options::parse(env::args().skip(1)) else {
+        eprintln!("{USAGE}");
+        return ExitCode::FAILURE;
+    };
+
+    match run(&options) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(Error::NoProbabilities) => {
+            eprintln!("Model does not support probability estimates");
+            ExitCode::FAILURE
+        }
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::FAILURE
+        }
+    }
+}
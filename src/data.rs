@@ -0,0 +1,230 @@
+use crate::{
+    errors::{Error, ParseContext},
+    parser::Line,
+    sparse::SparseVector,
+    svm::features::FeatureVector,
+};
+use simd_aligned::{arch::f32x8, VecSimd};
+use std::io::BufRead;
+
+/// How feature indices in a data file map to the features of a [`FeatureVector`].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Indexing {
+    /// Index `i` in the file is feature `i`. Models trained with `svm-train` keep the indices of their training
+    /// data, so this is right whenever prediction data is numbered like the training data was.
+    #[default]
+    ZeroBased,
+
+    /// Index `i` in the file is feature `i - 1`, and index `0` is invalid. Use this for files numbered from `1`
+    /// when the model expects features numbered from `0`.
+    OneBased,
+}
+
+/// Implemented by the feature storage of all [`FeatureVector`]s, so a [`DataReader`] can fill them.
+#[doc(hidden)]
+pub trait DataFeatures {
+    /// Sets feature `index`, returning `false` if the feature vector can't hold such a feature.
+    fn set_feature(&mut self, num_attributes: usize, index: usize, value: f64) -> bool;
+}
+
+impl DataFeatures for VecSimd<f32x8> {
+    fn set_feature(&mut self, num_attributes: usize, index: usize, value: f64) -> bool {
+        if index >= num_attributes {
+            return false;
+        }
+
+        self.flat_mut()[index] = value as f32;
+        true
+    }
+}
+
+impl DataFeatures for SparseVector<f32> {
+    fn set_feature(&mut self, _num_attributes: usize, index: usize, value: f64) -> bool {
+        // Sparse vectors store indices as `u32`, setting a larger one would panic.
+        if u32::try_from(index).is_err() {
+            return false;
+        }
+
+        self[index] = value as f32;
+        true
+    }
+}
+
+impl DataFeatures for SparseVector<f64> {
+    fn set_feature(&mut self, _num_attributes: usize, index: usize, value: f64) -> bool {
+        // Sparse vectors store indices as `u32`, setting a larger one would panic.
+        if u32::try_from(index).is_err() {
+            return false;
+        }
+
+        self[index] = value;
+        true
+    }
+}
+
+impl DataFeatures for Vec<f64> {
+    fn set_feature(&mut self, _num_attributes: usize, index: usize, value: f64) -> bool {
+        // Rows hold kernel values of all training instances, but only those up to the largest serial number of a
+        // support vector are ever read.
+        if let Some(feature) = self.get_mut(index) {
+            *feature = value;
+        }
+
+        true
+    }
+}
+
+/// Reads samples in libSVM's `label index:value index:value ...` data format.
+///
+/// Each sample becomes a [`FeatureVector`] sized for the SVM the reader was created for, together with its label
+/// as written in the file:
+///
+/// ```
+/// use ffsvm::{DataReader, DenseSVM, Error, Predict};
+/// # use ffsvm::SAMPLE_MODEL;
+///
+/// # fn main() -> Result<(), Error> {
+/// let svm = DenseSVM::try_from(SAMPLE_MODEL)?;
+/// let data = "42 0:0.55 1:-0.15 2:0.58 3:-0.22 # first sample\n21 1:0.1\n";
+///
+/// for sample in DataReader::new(data.as_bytes(), &svm) {
+///     let (label, mut fv) = sample?;
+///     svm.predict_value(&mut fv)?;
+///
+///     println!("Expected {label}, predicted {:?}", fv.label());
+/// }
+/// # Ok(())
+/// # }
+/// ```
+///
+/// As in svmlight files, everything after a `#` is a comment, and `qid:` tokens are ignored. Indices must be
+/// strictly increasing. Lines that can't be read produce an [`Error::ParsingToken`] with their line number.
+///
+/// [`DenseFeatures`](crate::DenseFeatures) only hold the attributes of their SVM, i.e., up to the largest index any
+/// support vector uses, so features beyond that produce an [`Error::FeatureIndexOutOfRange`], even though such
+/// features don't change predictions. libSVM's `svm-train` omits attributes that are `0` from models, so this also
+/// happens for features that were `0` in all support vectors. Read such data with a
+/// [`SparseSVM`](crate::SparseSVM), or load the [`DenseSVM`](crate::DenseSVM) with
+/// [`AttributePolicy::Fixed`](crate::AttributePolicy::Fixed).
+///
+/// Data files for a [`PrecomputedSVM`](crate::PrecomputedSVM) contain kernel values `0:serial 1:k_1 2:k_2 ...`,
+/// which are read into the [`PrecomputedFeatures`](crate::PrecomputedFeatures) as they are. Kernel values of training
+/// instances beyond the largest serial number of any support vector are never used and skipped.
+#[derive(Debug)]
+pub struct DataReader<R, T> {
+    reader: R,
+    prototype: FeatureVector<T>,
+    indexing: Indexing,
+    line: String,
+    number: usize,
+}
+
+impl<R, T> DataReader<R, T>
+where
+    R: BufRead,
+    T: DataFeatures + Clone,
+{
+    /// Reads samples from `reader`, producing feature vectors for `svm`.
+    pub fn new<S>(reader: R, svm: &S) -> Self
+    where
+        FeatureVector<T>: for<'s> From<&'s S>,
+    {
+        Self::with_prototype(reader, FeatureVector::from(svm))
+    }
+
+    /// Reads samples from `reader`, producing clones of `prototype`.
+    pub(crate) fn with_prototype(reader: R, prototype: FeatureVector<T>) -> Self {
+        Self {
+            reader,
+            prototype,
+            indexing: Indexing::default(),
+            line: String::new(),
+            number: 0,
+        }
+    }
+
+    /// Sets how indices in the file are numbered.
+    #[must_use]
+    pub const fn with_indexing(mut self, indexing: Indexing) -> Self {
+        self.indexing = indexing;
+        self
+    }
+
+    /// Parses a single line, returning `None` if it contained no sample.
+    fn parse(&self, text: &str) -> Result<Option<(f64, FeatureVector<T>)>, Error> {
+        let line = Line { number: self.number, text };
+        let data = text.split('#').next().unwrap_or_default();
+        let mut tokens = data.split_whitespace();
+
+        let Some(label) = tokens.next() else {
+            return Ok(None);
+        };
+
+        let label = line.parse(label, ParseContext::DataFile)?;
+        let mut problem = self.prototype.clone();
+        let mut last = None;
+
+        for token in tokens {
+            let Some((index, value)) = token.split_once(':') else {
+                return Err(line.error(token, ParseContext::DataFile));
+            };
+
+            if index == "qid" {
+                continue;
+            }
+
+            let parsed = line.parse::<usize>(index, ParseContext::DataFile)?;
+            let value = line.parse(value, ParseContext::DataFile)?;
+            let feature = match self.indexing {
+                Indexing::ZeroBased => Some(parsed),
+                Indexing::OneBased => parsed.checked_sub(1),
+            };
+
+            let feature = match feature {
+                Some(feature) if last < Some(feature) => feature,
+                _ => return Err(line.error(index, ParseContext::DataFile)),
+            };
+
+            if !problem.features.set_feature(problem.num_attributes, feature, value) {
+                return Err(Error::FeatureIndexOutOfRange {
+                    line: self.number,
+                    index: parsed,
+                    num_attributes: problem.num_attributes,
+                });
+            }
+
+            last = Some(feature);
+        }
+
+        Ok(Some((label, problem)))
+    }
+}
+
+impl<R, T> Iterator for DataReader<R, T>
+where
+    R: BufRead,
+    T: DataFeatures + Clone,
+{
+    type Item = Result<(f64, FeatureVector<T>), Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let mut text = std::mem::take(&mut self.line);
+            text.clear();
+
+            match self.reader.read_line(&mut text) {
+                Ok(0) => return None,
+                Ok(_) => self.number += 1,
+                Err(e) => return Some(Err(e.into())),
+            }
+
+            let result = self.parse(text.trim_end_matches(['\r', '\n']));
+            self.line = text;
+
+            // Empty and comment lines contain no sample, so we just read the next one.
+            if let Some(result) = result.transpose() {
+                return Some(result);
+            }
+        }
+    }
+}
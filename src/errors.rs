@@ -1,11 +1,17 @@
-use std::num::{ParseFloatError, ParseIntError};
+use std::{
+    fmt::{self, Display},
+    num::{ParseFloatError, ParseIntError},
+};
 
 /// Possible error types when classifying with one of the SVMs.
+///
+/// New variants might be added in future versions, so matches need a wildcard arm.
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum Error {
-    /// This can be emitted when creating an SVM from a [`ModelFile`](crate::ModelFile). For models generated by
-    /// libSVM's `svm-train`, the most common reason this occurs is skipping attributes.
-    /// All attributes must be in sequential order 0, 1, 2, ..., n. If they are not, this
+    /// This can be emitted when creating an SVM from a [`ModelFile`](crate::ModelFile). Attributes of each
+    /// support vector must be strictly increasing, and for a [`DenseSVM`](crate::DenseSVM) loaded with
+    /// [`AttributePolicy::Strict`](crate::AttributePolicy::Strict) in sequential order 0, 1, 2, ..., n. If they are not, this
     /// error will be emitted. For more details see the documentation provided in [`ModelFile`](crate::ModelFile).
     AttributesUnordered {
         /// The index process that was not a direct successor of the previous index. Can be used for
@@ -21,7 +27,8 @@ pub enum Error {
     },
 
     /// This error can be emitted by [`Predict::predict_probability`](crate::Predict::predict_probability) in case the model loaded by
-    ///  [`ModelFile`](crate::ModelFile) was not trained with probability estimates (`svm-train -b 1`).
+    ///  [`ModelFile`](crate::ModelFile) was not trained with probability estimates (`svm-train -b 1`), and its probabilities
+    /// were not calibrated with, e.g., [`DenseSVM::calibrate_probabilities`](crate::DenseSVM::calibrate_probabilities).
     NoProbabilities,
 
     /// Can be emitted by [`Predict::predict_probability`](crate::Predict::predict_probability) when predicting probabilities
@@ -40,24 +47,350 @@ pub enum Error {
     /// Wrapper for internal parsing error when unifiying error handling.
     Parsing(String),
 
+    /// A floating point number could not be parsed, the original error is available via [`std::error::Error::source`].
+    ParsingFloat(ParseFloatError),
+
+    /// An integer could not be parsed, the original error is available via [`std::error::Error::source`].
+    ParsingInt(ParseIntError),
+
+    /// A token of a model file could not be parsed.
+    ParsingToken {
+        /// The line of the model file, starting at `1`.
+        line: usize,
+
+        /// The byte column the token starts at, starting at `1`.
+        column: usize,
+
+        /// The offending token. Empty if a header was missing its value.
+        token: String,
+
+        /// The part of the model the token belongs to.
+        context: ParseContext,
+    },
+
     /// A required attribute was not found.
     MissingRequiredAttribute,
+
+    /// The model's `svm_type` is not known to us.
+    UnsupportedSvmType(String),
+
+    /// The model's `kernel_type` is not known to us, or can't be used with the chosen SVM. Models with a
+    /// `precomputed` kernel can only be loaded into a [`PrecomputedSVM`](crate::PrecomputedSVM), all other
+    /// kernels only into a [`DenseSVM`](crate::DenseSVM) or [`SparseSVM`](crate::SparseSVM).
+    UnsupportedKernel(String),
+
+    /// The model did not contain any support vectors past its `SV` line.
+    NoSupportVectors,
+
+    /// The model's `nr_class` is too small for its `svm_type`, classifiers need at least two classes.
+    InvalidClassCount(u32),
+
+    /// The number of support vectors given by `total_sv` doesn't match the sum of `nr_sv`, or the
+    /// number of support vectors actually present in the model.
+    InconsistentSupportVectorCount {
+        /// The number of support vectors announced by `total_sv`.
+        total_sv: usize,
+
+        /// The number of support vectors found instead.
+        found: usize,
+    },
+
+    /// The model's `rho` doesn't have one entry per class pair (or exactly one entry for regression and one-class SVMs).
+    RhoLengthMismatch {
+        /// The number of entries required.
+        expected: usize,
+
+        /// The number of entries found.
+        found: usize,
+    },
+
+    /// A multi value header such as `label`, `nr_sv`, `probA` or `probB` has the wrong number of entries, or a
+    /// classifier has only one of `probA` and `probB`.
+    HeaderLengthMismatch {
+        /// The name of the header line.
+        key: &'static str,
+
+        /// The number of entries required.
+        expected: usize,
+
+        /// The number of entries found.
+        found: usize,
+    },
+
+    /// A support vector doesn't have one coefficient per other class (or exactly one coefficient for
+    /// regression and one-class SVMs).
+    CoefficientCountMismatch {
+        /// The position of the support vector in the model, starting at `0`.
+        sv_index: usize,
+
+        /// The number of coefficients required.
+        expected: usize,
+
+        /// The number of coefficients found.
+        found: usize,
+    },
+
+    /// A support vector of a [`DenseSVM`](crate::DenseSVM) has more attributes than the SVM, as set by
+    /// [`AttributePolicy::Fixed`](crate::AttributePolicy::Fixed) or, for
+    /// [`AttributePolicy::Strict`](crate::AttributePolicy::Strict), the first support vector.
+    AttributeCountMismatch {
+        /// The position of the support vector in the model, starting at `0`.
+        sv_index: usize,
+
+        /// The maximum number of attributes allowed.
+        expected: usize,
+
+        /// The number of attributes found.
+        found: usize,
+    },
+
+    /// The number of attributes inferred from the largest attribute index of a model, or the largest serial number
+    /// of a precomputed kernel model, is larger than the model justifies, which usually means the model is corrupt.
+    /// The limit grows with the number of attributes the model stores. Models that really need more attributes can be
+    /// loaded into a [`SparseSVM`](crate::SparseSVM), or into a [`DenseSVM`](crate::DenseSVM) with
+    /// [`AttributePolicy::Fixed`](crate::AttributePolicy::Fixed). [`ModelStats`](crate::ModelStats) of such models
+    /// fail the same way.
+    TooManyAttributes {
+        /// The largest number of attributes allowed.
+        limit: usize,
+
+        /// The number of attributes found.
+        found: usize,
+    },
+
+    /// A support vector of a precomputed kernel model doesn't reference its training instance by
+    /// exactly one `0:serial_number` entry, with `serial_number` being a whole number from `1` to `2^24 - 1`. Larger
+    /// serial numbers can't be told apart from their neighbors once read.
+    InvalidSerialNumber {
+        /// The position of the support vector in the model, starting at `0`.
+        sv_index: usize,
+    },
+
+    /// The data given to [`DenseSVM::try_from`](crate::DenseSVM::try_from) is not a binary model, it doesn't
+    /// start with the expected magic number.
+    BinaryInvalidMagic,
+
+    /// The binary model was written in a format version we can't read.
+    BinaryUnsupportedVersion(u32),
+
+    /// The binary model ended before all data announced by its header was read.
+    BinaryTruncated {
+        /// The number of bytes required.
+        expected: usize,
+
+        /// The number of bytes found.
+        found: usize,
+    },
+
+    /// The payload of the binary model doesn't match its checksum, i.e., the data was corrupted.
+    BinaryChecksumMismatch {
+        /// The checksum stored in the header.
+        expected: u64,
+
+        /// The checksum computed from the payload.
+        found: u64,
+    },
+
+    /// The binary model given to [`DenseSVMView::try_from`](crate::DenseSVMView::try_from) doesn't start at a
+    /// suitably aligned address.
+    BinaryUnaligned {
+        /// The alignment required, in bytes.
+        alignment: usize,
+    },
+
+    /// A field of the binary model's header has an invalid value, or disagrees with the rest of the model.
+    BinaryInvalidHeader(&'static str),
+
+    /// A [`FeatureVector`](crate::FeatureVector) was used with an SVM of a different shape than the one it was created
+    /// for. Holds the name of the first property that differs: `num_attributes`, `total_sv` or `classes`, the latter if
+    /// the SVMs have different labels, or a different number of support vectors per class.
+    ///
+    /// Feature vectors can be shared between SVMs of the same shape, e.g., between two versions of a model trained on
+    /// the same data, but should otherwise be created with [`FeatureVector::from`](crate::FeatureVector::from) for each SVM.
+    FeatureVectorMismatch(&'static str),
+
+    /// A [`Guarded`](crate::Guarded) SVM found a feature that is `NaN` or infinite.
+    NonFiniteFeature {
+        /// The index of the feature.
+        index: usize,
+
+        /// The value of the feature.
+        value: f64,
+    },
+
+    /// A [`Guarded`](crate::Guarded) SVM found a feature outside of its training range.
+    FeatureOutOfRange {
+        /// The index of the feature.
+        index: usize,
+
+        /// The value of the feature.
+        value: f64,
+
+        /// The smallest value allowed.
+        min: f64,
+
+        /// The largest value allowed.
+        max: f64,
+    },
+
+    /// A matrix given to [`DenseSVM::predict_values_batch`](crate::DenseSVM::predict_values_batch) or
+    /// [`DenseSVM::predict_probabilities_batch`](crate::DenseSVM::predict_probabilities_batch) doesn't have
+    /// one row per sample, or the number of columns the SVM requires. Dimensions are given as `(rows, columns)`.
+    BatchDimensionMismatch {
+        /// The dimension required.
+        expected: (usize, usize),
+
+        /// The dimension found.
+        found: (usize, usize),
+    },
+
+    /// A [`DataReader`](crate::DataReader) found a feature index beyond the attributes of the feature vectors it
+    /// produces, e.g., a feature no support vector of a [`DenseSVM`](crate::DenseSVM) uses, or an index above
+    /// `u32::MAX` for a [`SparseSVM`](crate::SparseSVM).
+    FeatureIndexOutOfRange {
+        /// The line of the data file, starting at `1`.
+        line: usize,
+
+        /// The index as written in the data file.
+        index: usize,
+
+        /// The number of attributes of the feature vectors.
+        num_attributes: usize,
+    },
+
+    /// Reading or writing failed, e.g., when reading from a [`DataReader`](crate::DataReader).
+    Io(std::io::Error),
+
+    /// A training parameter is out of range, or infeasible for the training data, e.g., a `nu` too large for
+    /// the number of samples of some class. Holds the name of the parameter as used by libSVM, e.g., `C`.
+    InvalidParameter(&'static str),
+
+    /// The training data can't be trained on. Holds the reason, e.g., that classification data has a single class.
+    InvalidTrainingData(&'static str),
+
+    /// Probabilities can't be calibrated on the given samples, e.g., because a sample's label is not a class of the SVM,
+    /// or the SVM is a one-class SVM. Holds the reason.
+    InvalidCalibrationData(&'static str),
+}
+
+/// Where in a model, range or data file parsing failed, see [`Error::ParsingToken`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ParseContext {
+    /// A value of the header line with the given key, e.g., `rho`.
+    Header(&'static str),
+
+    /// A line before the `SV` section that doesn't start with a known header key.
+    UnknownHeader,
+
+    /// The support vector with the given index, starting at `0`.
+    SupportVector(usize),
+
+    /// A line of an `svm-scale` range file read by a [`Scaler`](crate::Scaler).
+    RangeFile,
+
+    /// A line of a data file read by a [`DataReader`](crate::DataReader).
+    DataFile,
+}
+
+impl Display for ParseContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Header(key) => write!(f, "header `{key}`"),
+            Self::UnknownHeader => write!(f, "unknown header line"),
+            Self::SupportVector(index) => write!(f, "support vector {index}"),
+            Self::RangeFile => write!(f, "range file"),
+            Self::DataFile => write!(f, "data file"),
+        }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::AttributesUnordered { index, value, last_index } => {
+                write!(f, "attribute {index} (value {value}) does not follow attribute {last_index}")
+            }
+            Self::NoProbabilities => write!(f, "model was not trained with probability estimates"),
+            Self::IterationsExceeded => write!(f, "iteration limit exceeded while estimating probabilities"),
+            Self::NoGamma => write!(f, "model has no `gamma`"),
+            Self::NoCoef0 => write!(f, "model has no `coef0`"),
+            Self::NoDegree => write!(f, "model has no `degree`"),
+            Self::Parsing(message) => write!(f, "parsing failed: {message}"),
+            Self::ParsingFloat(e) => write!(f, "invalid floating point number: {e}"),
+            Self::ParsingInt(e) => write!(f, "invalid integer: {e}"),
+            Self::ParsingToken { line, column, token, context } => {
+                write!(f, "invalid token `{token}` in {context} at line {line}, column {column}")
+            }
+            Self::MissingRequiredAttribute => write!(f, "model is missing a required header"),
+            Self::UnsupportedSvmType(svm_type) => write!(f, "unsupported `svm_type` {svm_type}"),
+            Self::UnsupportedKernel(kernel_type) => write!(f, "unsupported `kernel_type` {kernel_type}"),
+            Self::NoSupportVectors => write!(f, "model has no support vectors"),
+            Self::InvalidClassCount(nr_class) => write!(f, "invalid `nr_class` {nr_class}"),
+            Self::InconsistentSupportVectorCount { total_sv, found } => {
+                write!(f, "`total_sv` is {total_sv}, but found {found} support vectors")
+            }
+            Self::RhoLengthMismatch { expected, found } => write!(f, "expected {expected} `rho` values, found {found}"),
+            Self::HeaderLengthMismatch { key, expected, found } => write!(f, "expected {expected} `{key}` values, found {found}"),
+            Self::CoefficientCountMismatch { sv_index, expected, found } => {
+                write!(f, "support vector {sv_index} has {found} coefficients, expected {expected}")
+            }
+            Self::AttributeCountMismatch { sv_index, expected, found } => {
+                write!(f, "support vector {sv_index} has {found} attributes, expected at most {expected}")
+            }
+            Self::TooManyAttributes { limit, found } => write!(f, "model needs {found} attributes, at most {limit} are allowed"),
+            Self::InvalidSerialNumber { sv_index } => write!(f, "support vector {sv_index} has no valid serial number"),
+            Self::BinaryInvalidMagic => write!(f, "not a binary model"),
+            Self::BinaryUnsupportedVersion(version) => write!(f, "unsupported binary model version {version}"),
+            Self::BinaryTruncated { expected, found } => write!(f, "binary model truncated, expected {expected} bytes, found {found}"),
+            Self::BinaryChecksumMismatch { expected, found } => {
+                write!(f, "binary model corrupted, checksum is {found:#018x}, expected {expected:#018x}")
+            }
+            Self::BinaryUnaligned { alignment } => write!(f, "binary model is not aligned to {alignment} bytes"),
+            Self::BinaryInvalidHeader(field) => write!(f, "binary model has an invalid `{field}`"),
+            Self::FeatureVectorMismatch(property) => write!(f, "feature vector was created for an SVM with a different `{property}`"),
+            Self::NonFiniteFeature { index, value } => write!(f, "feature {index} is {value}"),
+            Self::FeatureOutOfRange { index, value, min, max } => {
+                write!(f, "feature {index} is {value}, outside of its training range [{min}, {max}]")
+            }
+            Self::BatchDimensionMismatch { expected, found } => {
+                write!(f, "batch has dimension {found:?}, expected {expected:?}")
+            }
+            Self::FeatureIndexOutOfRange { line, index, num_attributes } => {
+                write!(f, "feature index {index} in line {line} is out of range, the model has {num_attributes} attributes")
+            }
+            Self::Io(e) => write!(f, "I/O error: {e}"),
+            Self::InvalidParameter(name) => write!(f, "invalid training parameter `{name}`"),
+            Self::InvalidTrainingData(reason) => write!(f, "cannot train on data with {reason}"),
+            Self::InvalidCalibrationData(reason) => write!(f, "cannot calibrate probabilities with {reason}"),
+        }
+    }
 }
 
-// impl<'a, T> From<Error<'a, T>> for Error {
-//     fn from(_: Error<'a, T>) -> Self {
-//         Error::ParsingError
-//     }
-// }
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::ParsingFloat(e) => Some(e),
+            Self::ParsingInt(e) => Some(e),
+            Self::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
 
 impl From<ParseFloatError> for Error {
-    fn from(_e: ParseFloatError) -> Self {
-        Self::Parsing("ParseFloatError".to_owned())
+    fn from(e: ParseFloatError) -> Self {
+        Self::ParsingFloat(e)
     }
 }
 
 impl From<ParseIntError> for Error {
-    fn from(_: ParseIntError) -> Self {
-        Self::Parsing("ParseIntError".to_owned())
+    fn from(e: ParseIntError) -> Self {
+        Self::ParsingInt(e)
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
     }
 }
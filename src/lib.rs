@@ -10,7 +10,7 @@
 //!
 //! # Highlights
 //!
-//! * loads almost all [libSVM](https://github.com/cjlin1/libsvm) types (C-SVC, ν-SVC, ε-SVR,  ν-SVR) and kernels (linear, poly, RBF and sigmoid)
+//! * loads almost all [libSVM](https://github.com/cjlin1/libsvm) types (C-SVC, ν-SVC, one-class, ε-SVR,  ν-SVR) and kernels (linear, poly, RBF, sigmoid and precomputed)
 //! * produces practically same classification results as libSVM
 //! * optimized for [SIMD](https://github.com/rust-lang/rfcs/pull/2366) and can be mixed seamlessly with [Rayon](https://github.com/rayon-rs/rayon)
 //! * written in 100% safe Rust
@@ -74,7 +74,17 @@
 //! * Compile your project with `target-cpu=native` for a massive speed boost (e.g., check our `.cargo/config.toml` how
 //!   you can easily do that for your project). Note, due to how Rust works, this is only used for application
 //!   (or dynamic FFI libraries), not library crates wrapping us.
-//! * For an x-fold performance increase, create a number of `Problem` structures, and process them with [Rayon's](https://docs.rs/rayon/1.0.3/rayon/) `par_iter`.
+//! * For an x-fold performance increase, create a number of `Problem` structures, and process them with [Rayon's](https://docs.rs/rayon/1.0.3/rayon/) `par_iter`, or enable the `rayon` feature
+//!   and use `par_predict_values`. With that feature, kernel values of classes with many support vectors are computed in parallel as well.
+//! * When classifying many samples with a `DenseSVM` at once, `predict_values_batch` reuses support vectors while they are in cache, which is considerably faster for larger models.
+//! * If you don't know whether a model is dense or sparse, load it into an `Svm`, which picks a `DenseSVM` or `SparseSVM` for you.
+//! * If you trained on data scaled by `svm-scale -s range_file`, load that range file into a `Scaler` and apply it to your features before predicting.
+//! * To compare a model against libSVM, `cargo install ffsvm --features cli` and run `ffsvm-predict`, which takes the same arguments and writes the same output as `svm-predict`.
+//!   `ffsvm-inspect` prints statistics of a model and whether it's best loaded into a `DenseSVM` or `SparseSVM`.
+//! * If a model was trained without `-b 1`, but you have labeled validation data, `calibrate_probabilities` fits the probability estimates `predict_probability` needs.
+//! * For regression models with probability estimates, `svr_distribution` turns a predicted value into a `Laplace` distribution, e.g., for 95% prediction intervals.
+//! * If you'd rather not install libSVM, enable the `train` feature and train models with a `Trainer`, which uses the same solver as `svm-train` and produces practically the same models.
+//! * To pick `C`, `gamma` and other parameters without `grid.py`, run a `GridSearch`, which cross-validates all combinations in parallel and picks the best one.
 //!
 //! # FAQ
 //!
@@ -99,10 +109,15 @@
 #![allow(clippy::module_name_repetitions)] // We do that way too often
 #![allow(clippy::doc_markdown)] // Mainly for `libSVM` in the docs.
 
+mod data;
 mod errors;
 mod parser;
+mod scaler;
 mod sparse;
+mod stats;
 mod svm;
+#[cfg(feature = "train")]
+mod train;
 mod util;
 mod vectors;
 
@@ -110,12 +125,27 @@ mod vectors;
 pub static SAMPLE_MODEL: &str = include_str!("sample.model");
 
 pub use crate::{
-    errors::Error,
+    data::{DataReader, Indexing},
+    errors::{Error, ParseContext},
     parser::{Attribute, Header, ModelFile, SupportVector},
+    scaler::Scaler,
+    sparse::{SparseVector, SparseVectorIter},
+    stats::{ModelStats, Representation, ValueStats},
     svm::{
-        features::{DenseFeatures, FeatureVector, Label, SparseFeatures},
+        features::{DenseFeatures, FeatureVector, Label, PrecomputedFeatures, SparseFeatures},
+        guard::{Guarded, InvalidFeaturePolicy},
         kernel::{KernelDense, KernelSparse, Linear, Poly, Rbf, Sigmoid},
+        laplace::Laplace,
         predict::Predict,
-        DenseSVM, SVMType, SparseSVM,
+        AttributePolicy, BackendPolicy, DenseSVM, Features, PrecomputedSVM, SVMType, SparseSVM, Svm, SvmFeatures,
     },
 };
+
+#[cfg(target_endian = "little")]
+pub use crate::svm::DenseSVMView;
+
+#[cfg(feature = "rayon")]
+pub use crate::svm::parallel::ParallelPredict;
+
+#[cfg(feature = "train")]
+pub use crate::train::{CrossValidation, Dataset, Folds, GridPoint, GridSearch, GridSearchResult, KernelType, Score, Trainer};
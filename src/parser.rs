@@ -1,12 +1,18 @@
-use crate::errors::Error;
-use std::{convert::TryFrom, str};
+use crate::errors::{Error, ParseContext};
+use std::{
+    convert::TryFrom,
+    fmt::{self, Display},
+    str,
+    str::FromStr,
+};
 
-/// Parsing result of a model file used to instantiate a [`DenseSVM`](`crate::DenseSVM`) or [`SparseSVM`](`crate::SparseSVM`).
+/// Parsing result of a model file used to instantiate a [`DenseSVM`](`crate::DenseSVM`), [`SparseSVM`](`crate::SparseSVM`)
+/// or [`PrecomputedSVM`](`crate::PrecomputedSVM`).
 ///
 /// # Obtaining Models
 /// A model file is produced by [libSVM](https://github.com/cjlin1/libsvm). For details
 /// how to produce a model see the top-level [FFSVM](index.html#creating-a-libsvm-model)
-/// documentation.
+/// documentation. With the `train` feature, a `Trainer` produces models as well.
 ///
 /// # Loading Models
 ///
@@ -19,7 +25,8 @@ use std::{convert::TryFrom, str};
 /// let model_result = ModelFile::try_from(SAMPLE_MODEL);
 /// ```
 ///
-/// Should anything be wrong with the model format, an [`Error`] will be returned. Once you have
+/// Should anything be wrong with the model format, an [`Error`] will be returned. Tokens that can't be
+/// parsed produce an [`Error::ParsingToken`] with their line and column. Once you have
 /// your model, you can use it to create an SVM, for example by invoking `DenseSVM::try_from(model)`.
 ///
 /// # Model Format
@@ -45,13 +52,31 @@ use std::{convert::TryFrom, str};
 /// 256 0:0.4933203 1:0.1098869 2:0.1048947 3:0.1069601 4:0.2152338 5:0 6:0 7:0 8:1 9:1
 /// ```
 ///
-/// Apart from "one-class SVM" (`-s 2` in libSVM) and "precomputed kernel" (`-t 4`) all
-/// generated libSVM models should be supported.
+/// All generated libSVM models should be supported. Models with a "precomputed kernel" (`-t 4`) must be
+/// loaded into a [`PrecomputedSVM`](`crate::PrecomputedSVM`). One-class SVMs (`-s 2`) trained with probability
+/// estimates can be loaded, but will predict without them.
 ///
-/// However, note that for the [`DenseSVM`](`crate::DenseSVM`) to work, all support vectors
-/// (past the `SV` line) must have **strictly** increasing attribute identifiers starting at `0`,
-/// without skipping an attribute. In other words, your attributes have to be named `0:`, `1:`,
-/// `2:`, ... `n:` and not, say, `0:`, `1:`, `4:`, ... `n:`.
+/// # Saving Models
+///
+/// Models can be written back in libSVM's format through [`Display`], e.g., via [`ToString::to_string`]. A
+/// model can also be obtained from an existing SVM, for example by invoking `ModelFile::from(&svm)`:
+///
+/// ```rust
+/// use ffsvm::{DenseSVM, ModelFile};
+/// # use ffsvm::SAMPLE_MODEL;
+///
+/// let svm = DenseSVM::try_from(SAMPLE_MODEL)?;
+/// let text = ModelFile::from(&svm).to_string();
+/// # Ok::<(), ffsvm::Error>(())
+/// ```
+///
+/// Numbers are written in their shortest form that parses back into the same value, so
+/// the written model predicts exactly like the one it came from.
+///
+/// However, note that all support vectors (past the `SV` line) must have **strictly** increasing
+/// attribute identifiers. Attributes omitted from a support vector, e.g., `0:`, `1:`, `4:`, are `0`,
+/// like libSVM's `svm-train` omits them. The [`DenseSVM`](`crate::DenseSVM`) can also insist on
+/// attributes named `0:`, `1:`, `2:`, ... `n:`, see [`AttributePolicy`](crate::AttributePolicy).
 #[derive(Clone, Debug, Default)]
 pub struct ModelFile<'a> {
     header: Header<'a>,
@@ -67,7 +92,7 @@ impl<'a> ModelFile<'a> {
 
     #[doc(hidden)]
     #[must_use]
-    pub const fn header(&self) -> &Header {
+    pub const fn header(&self) -> &Header<'_> {
         &self.header
     }
 
@@ -109,6 +134,118 @@ pub struct SupportVector {
     pub features: Vec<Attribute>,
 }
 
+/// A single line of a model or range file, used to report where parsing failed.
+pub struct Line<'a> {
+    pub number: usize,
+    pub text: &'a str,
+}
+
+impl Line<'_> {
+    /// Parses a token of this line, producing an [`Error::ParsingToken`] with the token's position if that fails.
+    pub fn parse<T: FromStr>(&self, token: &str, context: ParseContext) -> Result<T, Error> {
+        token.parse::<T>().map_err(|_| self.error(token, context))
+    }
+
+    /// Parses all values of a multi value header such as `rho 0.1 0.2`.
+    fn parse_all<T: FromStr>(&self, tokens: &[&str], key: &'static str) -> Result<Vec<T>, Error> {
+        tokens.iter().skip(1).map(|x| self.parse(x, ParseContext::Header(key))).collect()
+    }
+
+    /// Returns the value of a single value header such as `svm_type c_svc`, borrowed from the input.
+    fn value<'t>(&self, tokens: &[&'t str], key: &'static str) -> Result<&'t str, Error> {
+        tokens
+            .get(1)
+            .copied()
+            .ok_or_else(|| self.error(&self.text[self.text.len()..], ParseContext::Header(key)))
+    }
+
+    /// Parses the value of a single value header such as `gamma 0.5`.
+    fn parse_single<T: FromStr>(&self, tokens: &[&str], key: &'static str) -> Result<T, Error> {
+        self.parse(self.value(tokens, key)?, ParseContext::Header(key))
+    }
+
+    /// Creates an error pointing at `token`, which must be a slice of this line.
+    pub fn error(&self, token: &str, context: ParseContext) -> Error {
+        Error::ParsingToken {
+            line: self.number,
+            column: token.as_ptr() as usize - self.text.as_ptr() as usize + 1,
+            token: token.to_owned(),
+            context,
+        }
+    }
+}
+
+/// Writes a multi value header such as `rho 0.1 0.2`.
+fn write_all<T: Display>(f: &mut fmt::Formatter<'_>, key: &str, values: &[T]) -> fmt::Result {
+    write!(f, "{key}")?;
+
+    for value in values {
+        write!(f, " {value}")?;
+    }
+
+    writeln!(f)
+}
+
+impl Display for ModelFile<'_> {
+    /// Writes the model in the same format and order as libSVM's `svm_save_model`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let header = &self.header;
+
+        writeln!(f, "svm_type {}", header.svm_type)?;
+        writeln!(f, "kernel_type {}", header.kernel_type)?;
+
+        if let Some(degree) = header.degree {
+            writeln!(f, "degree {degree}")?;
+        }
+
+        if let Some(gamma) = header.gamma {
+            writeln!(f, "gamma {gamma}")?;
+        }
+
+        if let Some(coef0) = header.coef0 {
+            writeln!(f, "coef0 {coef0}")?;
+        }
+
+        writeln!(f, "nr_class {}", header.nr_class)?;
+        writeln!(f, "total_sv {}", header.total_sv)?;
+        write_all(f, "rho", &header.rho)?;
+
+        // Regression and one-class models don't have labels
+        if !header.label.is_empty() {
+            write_all(f, "label", &header.label)?;
+        }
+
+        if let Some(prob_a) = &header.prob_a {
+            write_all(f, "probA", prob_a)?;
+        }
+
+        if let Some(prob_b) = &header.prob_b {
+            write_all(f, "probB", prob_b)?;
+        }
+
+        if !header.nr_sv.is_empty() {
+            write_all(f, "nr_sv", &header.nr_sv)?;
+        }
+
+        writeln!(f, "SV")?;
+
+        // Like libSVM, every coefficient and attribute is followed by a space.
+        for vector in &self.vectors {
+            for coef in &vector.coefs {
+                write!(f, "{coef} ")?;
+            }
+
+            for attribute in &vector.features {
+                write!(f, "{}:{} ", attribute.index, attribute.value)?;
+            }
+
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
 impl<'a> TryFrom<&'a str> for ModelFile<'a> {
     type Error = Error;
 
@@ -129,11 +266,16 @@ impl<'a> TryFrom<&'a str> for ModelFile<'a> {
         let mut nr_sv = Vec::new();
 
         let mut vectors = Vec::new();
+        let mut in_header = true;
 
-        for line in input.lines() {
-            let tokens = line.split_whitespace().collect::<Vec<_>>();
+        for (number, text) in input.lines().enumerate() {
+            let line = Line { number: number + 1, text };
+            let tokens = text.split_whitespace().collect::<Vec<_>>();
 
             match tokens.first() {
+                // Empty end of file
+                None => break,
+
                 // Single value headers
                 //
                 // svm_type c_svc
@@ -147,66 +289,51 @@ impl<'a> TryFrom<&'a str> for ModelFile<'a> {
                 // probB 0.135634 0.570051 -0.114691 -0.397667 0.0687938 0.839527 -0.310816 -0.787629 0.0335196 0.15079 -0.389211 0.288416 0.186429 0.46585 0.547398
                 // nr_sv 50 56 17 11 7 12
                 // SV
-                Some(x) if *x == "svm_type" => {
-                    svm_type = Some(tokens[1]);
-                }
-                Some(x) if *x == "kernel_type" => {
-                    kernel_type = Some(tokens[1]);
-                }
-                Some(x) if *x == "gamma" => {
-                    gamma = tokens[1].parse::<f32>().ok();
-                }
-                Some(x) if *x == "coef0" => {
-                    coef0 = tokens[1].parse::<f32>().ok();
-                }
-                Some(x) if *x == "degree" => {
-                    degree = tokens[1].parse::<u32>().ok();
-                }
-                Some(x) if *x == "nr_class" => {
-                    nr_class = tokens[1].parse::<u32>().ok();
-                }
-                Some(x) if *x == "total_sv" => {
-                    total_sv = tokens[1].parse::<u32>().ok();
-                }
-                // Multi value headers
-                Some(x) if *x == "rho" => rho = tokens.iter().skip(1).filter_map(|x| x.parse::<f64>().ok()).collect(),
-                Some(x) if *x == "label" => label = tokens.iter().skip(1).filter_map(|x| x.parse::<i32>().ok()).collect(),
-                Some(x) if *x == "nr_sv" => nr_sv = tokens.iter().skip(1).filter_map(|x| x.parse::<u32>().ok()).collect(),
-                Some(x) if *x == "probA" => prob_a = Some(tokens.iter().skip(1).filter_map(|x| x.parse::<f64>().ok()).collect()),
-                Some(x) if *x == "probB" => prob_b = Some(tokens.iter().skip(1).filter_map(|x| x.parse::<f64>().ok()).collect()),
-                // Header separator
-                Some(x) if *x == "SV" => {}
+                Some(x) if in_header => match *x {
+                    "svm_type" => svm_type = Some(line.value(&tokens, "svm_type")?),
+                    "kernel_type" => kernel_type = Some(line.value(&tokens, "kernel_type")?),
+                    "gamma" => gamma = Some(line.parse_single(&tokens, "gamma")?),
+                    "coef0" => coef0 = Some(line.parse_single(&tokens, "coef0")?),
+                    "degree" => degree = Some(line.parse_single(&tokens, "degree")?),
+                    "nr_class" => nr_class = Some(line.parse_single(&tokens, "nr_class")?),
+                    "total_sv" => total_sv = Some(line.parse_single(&tokens, "total_sv")?),
+                    // Multi value headers
+                    "rho" => rho = line.parse_all(&tokens, "rho")?,
+                    "label" => label = line.parse_all(&tokens, "label")?,
+                    "nr_sv" => nr_sv = line.parse_all(&tokens, "nr_sv")?,
+                    "probA" => prob_a = Some(line.parse_all(&tokens, "probA")?),
+                    "probB" => prob_b = Some(line.parse_all(&tokens, "probB")?),
+                    // Density marks of one-class probability estimates, which we don't support yet.
+                    "prob_density_marks" => {}
+                    // Header separator
+                    "SV" => in_header = false,
+                    _ => return Err(line.error(x, ParseContext::UnknownHeader)),
+                },
+
                 // These are all regular lines without a clear header (after SV) ...
                 //
                 // 0.0625 0:0.6619648 1:0.8464851 2:0.4801146 3:0 4:0 5:0.02131653 6:0 7:0 8:0 9:0 10:0 11:0 12:0 13:0 14:0 15:0.5579834 16:0.1106567 17:0 18:0 19:0 20:0
                 // 0.0625 0:0.5861949 1:0.5556895 2:0.619291 3:0 4:0 5:0 6:0 7:0 8:0 9:0 10:0 11:0.5977631 12:0 13:0 14:0 15:0.6203156 16:0 17:0 18:0 19:0.1964417 20:0
                 // 0.0625 0:0.44675 1:0.4914977 2:0.4227562 3:0.2904663 4:0.2904663 5:0.268158 6:0 7:0 8:0 9:0 10:0 11:0.6202393 12:0.0224762 13:0 14:0 15:0.6427917 16:0.0224762 17:0 18:0 19:0.1739655 20:0
                 Some(_) => {
+                    let context = ParseContext::SupportVector(vectors.len());
                     let mut sv = SupportVector {
                         coefs: Vec::new(),
                         features: Vec::new(),
                     };
 
-                    let (features, coefs): (Vec<&str>, Vec<&str>) = tokens.iter().partition(|x| x.contains(':'));
-
-                    sv.coefs = coefs.iter().filter_map(|x| x.parse::<f32>().ok()).collect();
-                    sv.features = features
-                        .iter()
-                        .filter_map(|x| {
-                            let split = x.split(':').collect::<Vec<&str>>();
-
-                            Some(Attribute {
-                                index: split.first()?.parse::<u32>().ok()?,
-                                value: split.get(1)?.parse::<f32>().ok()?,
-                            })
-                        })
-                        .collect();
+                    for token in &tokens {
+                        match token.split_once(':') {
+                            Some((index, value)) => sv.features.push(Attribute {
+                                index: index.parse::<u32>().map_err(|_| line.error(token, context))?,
+                                value: value.parse::<f32>().map_err(|_| line.error(token, context))?,
+                            }),
+                            None => sv.coefs.push(line.parse::<f32>(token, context)?),
+                        }
+                    }
 
                     vectors.push(sv);
                 }
-
-                // Empty end of file
-                None => break,
             }
         }
 
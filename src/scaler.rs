@@ -0,0 +1,253 @@
+use crate::{
+    errors::{Error, ParseContext},
+    parser::Line,
+    sparse::SparseVector,
+    svm::features::FeatureVector,
+};
+use simd_aligned::{arch::f32x8, VecSimd};
+use std::{
+    collections::BTreeMap,
+    convert::TryFrom,
+    fmt::{self, Display},
+};
+
+/// Implemented by the feature storage of [`DenseFeatures`](crate::DenseFeatures) and [`SparseFeatures`](crate::SparseFeatures),
+/// so a [`Scaler`] can scale them.
+#[doc(hidden)]
+pub trait ScaledFeatures {
+    /// Replaces the first `num_attributes` features with their scaled values.
+    fn scale_features(&mut self, num_attributes: usize, scaler: &Scaler);
+}
+
+impl ScaledFeatures for VecSimd<f32x8> {
+    fn scale_features(&mut self, num_attributes: usize, scaler: &Scaler) {
+        for (index, value) in self.flat_mut()[..num_attributes].iter_mut().enumerate() {
+            *value = scaler.scale_feature(index, f64::from(*value)) as f32;
+        }
+    }
+}
+
+impl ScaledFeatures for SparseVector<f32> {
+    fn scale_features(&mut self, _num_attributes: usize, scaler: &Scaler) {
+        let original = std::mem::take(self);
+
+        // Like `svm-scale`, features without range become zero, and zeros after scaling are dropped, so only features
+        // with a range remain. Sparse vectors can't hold features beyond `u32::MAX`, these stay unset.
+        for (index, range) in scaler.features.range(..=u32::MAX as usize) {
+            let value = original.get(*index).map_or(0.0, |value| f64::from(*value));
+            let result = scaler.scale_in_range(*range, value) as f32;
+
+            if result != 0.0 {
+                self[*index] = result;
+            }
+        }
+    }
+}
+
+/// The `y` section of a range file.
+#[derive(Copy, Clone, Debug, PartialEq)]
+struct Target {
+    lower: f64,
+    upper: f64,
+    min: f64,
+    max: f64,
+}
+
+/// Scales features the same way libSVM's `svm-scale` does.
+///
+/// Models should be trained on scaled features, and the same scaling must then be applied before predicting.
+/// When `svm-scale` was used with `-s range_file`, the range file can be loaded and applied to [`DenseFeatures`](crate::DenseFeatures)
+/// and [`SparseFeatures`](crate::SparseFeatures) in place:
+///
+/// ```
+/// use ffsvm::{DenseSVM, Error, FeatureVector, Predict, Scaler};
+/// # use ffsvm::SAMPLE_MODEL;
+///
+/// # fn main() -> Result<(), Error> {
+/// let svm = DenseSVM::try_from(SAMPLE_MODEL)?;
+/// let scaler = Scaler::try_from("x\n-1 1\n0 0 10\n1 -5 5\n")?;
+///
+/// let mut fv = FeatureVector::from(&svm);
+/// fv.features()[0] = 5.0;
+/// fv.features()[1] = 5.0;
+///
+/// scaler.scale(&mut fv);
+///
+/// assert_eq!(&fv.features()[..4], &[0.0, 1.0, 0.0, 0.0]);
+/// # Ok(())
+/// # }
+/// ```
+///
+/// As in `svm-scale`, features without a range in the file, or with a constant value in the training data, become `0`.
+/// For [`SparseFeatures`](crate::SparseFeatures) unset features count as `0` and are scaled as well, while features
+/// that are `0` after scaling are removed.
+///
+/// Writing a [`Scaler`] with [`Display`] produces a range file again.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Scaler {
+    lower: f64,
+    upper: f64,
+    features: BTreeMap<usize, (f64, f64)>,
+    target: Option<Target>,
+}
+
+impl Scaler {
+    /// Creates a scaler mapping features into `[lower, upper]`, without any feature ranges yet.
+    #[must_use]
+    pub const fn new(lower: f64, upper: f64) -> Self {
+        Self { lower, upper, features: BTreeMap::new(), target: None }
+    }
+
+    /// Adds the `(min, max)` range feature `index` had in the training data.
+    #[must_use]
+    pub fn with_feature(mut self, index: usize, min: f64, max: f64) -> Self {
+        self.features.insert(index, (min, max));
+        self
+    }
+
+    /// Also scales targets with range `[min, max]` into `[lower, upper]`, like `svm-scale -y lower upper`.
+    #[must_use]
+    pub const fn with_target(mut self, lower: f64, upper: f64, min: f64, max: f64) -> Self {
+        self.target = Some(Target { lower, upper, min, max });
+        self
+    }
+
+    /// Returns the `(lower, upper)` bounds features are scaled into.
+    #[must_use]
+    pub const fn bounds(&self) -> (f64, f64) {
+        (self.lower, self.upper)
+    }
+
+    /// Returns the `(min, max)` range of feature `index` in the training data, if known.
+    #[must_use]
+    pub fn feature_range(&self, index: usize) -> Option<(f64, f64)> {
+        self.features.get(&index).copied()
+    }
+
+    /// Scales all features of the problem in place.
+    pub fn scale<T: ScaledFeatures>(&self, problem: &mut FeatureVector<T>) {
+        problem.features.scale_features(problem.num_attributes, self);
+    }
+
+    /// Scales a single value of feature `index`.
+    #[must_use]
+    pub fn scale_feature(&self, index: usize, value: f64) -> f64 {
+        self.feature_range(index).map_or(0.0, |range| self.scale_in_range(range, value))
+    }
+
+    /// Scales a single value of a feature with the `(min, max)` range, `0` if the feature was constant.
+    fn scale_in_range(&self, (min, max): (f64, f64), value: f64) -> f64 {
+        if min < max {
+            scale(value, (min, max), (self.lower, self.upper))
+        } else {
+            0.0
+        }
+    }
+
+    /// Scales a target value, e.g., a regression label. Targets are left unchanged if the file had no `y` section.
+    #[must_use]
+    pub fn scale_target(&self, value: f64) -> f64 {
+        self.target.map_or(value, |t| scale(value, (t.min, t.max), (t.lower, t.upper)))
+    }
+
+    /// Maps a scaled target, e.g., the [`Label::Value`](crate::Label::Value) of a regression, back into its original range.
+    #[must_use]
+    pub fn unscale_target(&self, value: f64) -> f64 {
+        self.target.map_or(value, |t| scale(value, (t.lower, t.upper), (t.min, t.max)))
+    }
+}
+
+/// Linearly maps `value` from range `from` into range `to`, like `svm-scale` returning the bounds for the range's ends.
+#[allow(clippy::float_cmp)] // We want the same results as `svm-scale`.
+fn scale(value: f64, from: (f64, f64), to: (f64, f64)) -> f64 {
+    if value == from.0 {
+        to.0
+    } else if value == from.1 {
+        to.1
+    } else {
+        to.0 + (to.1 - to.0) * (value - from.0) / (from.1 - from.0)
+    }
+}
+
+/// Splits a line into exactly `N` tokens.
+fn tokens<'t, const N: usize>(line: &Line<'t>) -> Result<[&'t str; N], Error> {
+    let mut tokens = line.text.split_whitespace();
+    let mut result = [""; N];
+
+    for token in &mut result {
+        *token = tokens.next().ok_or_else(|| line.error(&line.text[line.text.len()..], ParseContext::RangeFile))?;
+    }
+
+    tokens.next().map_or(Ok(result), |extra| Err(line.error(extra, ParseContext::RangeFile)))
+}
+
+/// Parses a line of two numbers, such as the bounds `-1 1`.
+fn pair(line: &Line<'_>) -> Result<(f64, f64), Error> {
+    let [a, b] = tokens(line)?;
+    Ok((line.parse(a, ParseContext::RangeFile)?, line.parse(b, ParseContext::RangeFile)?))
+}
+
+impl TryFrom<&str> for Scaler {
+    type Error = Error;
+
+    /// Parses a range file as written by `svm-scale -s range_file`.
+    fn try_from(input: &str) -> Result<Self, Error> {
+        let end = Line { number: input.lines().count() + 1, text: "" };
+        let lines = input
+            .lines()
+            .enumerate()
+            .map(|(number, text)| Line { number: number + 1, text })
+            .filter(|line| !line.text.trim().is_empty())
+            .collect::<Vec<_>>();
+        let line = |i: usize| lines.get(i).unwrap_or(&end);
+
+        let mut next = 0;
+        let mut target = None;
+
+        if lines.first().is_some_and(|line| line.text.trim() == "y") {
+            let (lower, upper) = pair(line(1))?;
+            let (min, max) = pair(line(2))?;
+
+            target = Some(Target { lower, upper, min, max });
+            next = 3;
+        }
+
+        if let Some(token) = line(next).text.split_whitespace().next().filter(|token| *token != "x") {
+            return Err(line(next).error(token, ParseContext::RangeFile));
+        }
+
+        let [_] = tokens(line(next))?;
+
+        let (lower, upper) = pair(line(next + 1))?;
+        let mut scaler = Self { lower, upper, features: BTreeMap::new(), target };
+
+        for line in lines.iter().skip(next + 2) {
+            let [index, min, max] = tokens(line)?;
+            let index = line.parse(index, ParseContext::RangeFile)?;
+
+            scaler = scaler.with_feature(index, line.parse(min, ParseContext::RangeFile)?, line.parse(max, ParseContext::RangeFile)?);
+        }
+
+        Ok(scaler)
+    }
+}
+
+impl Display for Scaler {
+    /// Writes a range file that can be read by `svm-scale -r range_file`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(t) = self.target {
+            writeln!(f, "y")?;
+            writeln!(f, "{} {}", t.lower, t.upper)?;
+            writeln!(f, "{} {}", t.min, t.max)?;
+        }
+
+        writeln!(f, "x")?;
+        writeln!(f, "{} {}", self.lower, self.upper)?;
+
+        for (index, (min, max)) in self.features.iter().filter(|(_, (min, max))| min < max) {
+            writeln!(f, "{index} {min} {max}")?;
+        }
+
+        Ok(())
+    }
+}
@@ -9,6 +9,27 @@ where
     value: T,
 }
 
+/// A sparse vector, holding the values of all set indices ordered by index.
+///
+/// Values can be set in any order, e.g., from a hash map, and are moved into place as they are set, so
+/// kernels can always merge support vectors and features by walking both in ascending order:
+///
+/// ```
+/// use ffsvm::SparseVector;
+///
+/// let mut vector = [(7, 0.5), (2, 1.0)].into_iter().collect::<SparseVector<f32>>();
+/// vector[4] = -1.0;
+///
+/// assert_eq!(vector.iter().collect::<Vec<_>>(), vec![(2, 1.0), (4, -1.0), (7, 0.5)]);
+/// assert_eq!(vector.get(3), None);
+/// assert_eq!(vector.remove(7), Some(0.5));
+/// ```
+///
+/// Setting a value with [`IndexMut`] inserts it if needed, while reading a missing index with [`Index`] panics;
+/// use [`SparseVector::get`] instead if an index might not be set.
+///
+/// Indices are stored as `u32`. Indices above `u32::MAX` are never set, so [`SparseVector::get`] and
+/// [`SparseVector::remove`] return `None` for them, while setting them panics.
 #[derive(Clone, Debug, Default)]
 pub struct SparseVector<T>
 where
@@ -21,20 +42,53 @@ impl<T> SparseVector<T>
 where
     T: Clone + Copy + Default,
 {
+    /// Creates an empty vector.
+    #[must_use]
     pub const fn new() -> Self {
         Self { entries: Vec::new() }
     }
 
+    /// Removes all values.
     pub fn clear(&mut self) {
         self.entries.clear();
     }
 
+    /// Returns the value at `index`, or `None` if it was never set.
+    #[must_use]
+    pub fn get(&self, index: usize) -> Option<&T> {
+        let position = self.position(u32::try_from(index).ok()?).ok()?;
+        Some(&self.entries[position].value)
+    }
+
+    /// Removes the value at `index`, returning it if it was set.
+    pub fn remove(&mut self, index: usize) -> Option<T> {
+        let position = self.position(u32::try_from(index).ok()?).ok()?;
+        Some(self.entries.remove(position).value)
+    }
+
+    /// Iterates over the index and value of all set entries, ordered by index.
+    #[must_use]
     pub const fn iter(&self) -> SparseVectorIter<'_, T> {
         SparseVectorIter { vector: self, index: 0 }
     }
+
+    /// Iterates over the index and a mutable reference to the value of all set entries.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (u32, &mut T)> {
+        self.entries.iter_mut().map(|entry| (entry.index, &mut entry.value))
+    }
+
+    /// Returns the position of the entry for `index`, or the position it must be inserted at to keep the entries ordered.
+    fn position(&self, index: u32) -> Result<usize, usize> {
+        match self.entries.last() {
+            // Most vectors are filled in ascending order, so we check for appending before searching.
+            Some(last) if last.index < index => Err(self.entries.len()),
+            None => Err(0),
+            Some(_) => self.entries.binary_search_by_key(&index, |entry| entry.index),
+        }
+    }
 }
 
-/// Basic iterator struct to go over matrix
+/// Iterator over the index and value of all set entries of a [`SparseVector`].
 #[derive(Clone, Debug)]
 pub struct SparseVectorIter<'a, T>
 where
@@ -65,6 +119,18 @@ where
     }
 }
 
+impl<'a, T> IntoIterator for &'a SparseVector<T>
+where
+    T: Clone + Copy + Default,
+{
+    type Item = (u32, T);
+    type IntoIter = SparseVectorIter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
 impl<T> Index<usize> for SparseVector<T>
 where
     T: Copy + Sized + Default,
@@ -72,16 +138,8 @@ where
     type Output = T;
 
     fn index(&self, index: usize) -> &T {
-        // TODO: Beautify me
-
-        for e in &self.entries {
-            if e.index == index as u32 {
-                return &e.value;
-            }
-        }
-
         // We can panic here since a regular index out of bounds would also panic.
-        panic!("Index out of bounds.");
+        self.get(index).expect("Index out of bounds.")
     }
 }
 
@@ -90,24 +148,61 @@ where
     T: Copy + Sized + Default,
 {
     fn index_mut(&mut self, index: usize) -> &mut T {
-        // TODO: Beautify me
+        let index = stored_index(index);
+        let position = self.position(index).unwrap_or_else(|position| {
+            self.entries.insert(position, Entry { index, value: T::default() });
+            position
+        });
 
-        let highest_so_far: i32 = self.entries.last().map_or(-1, |x| x.index as i32);
+        &mut self.entries[position].value
+    }
+}
 
-        if index as i32 <= highest_so_far {
-            unimplemented!("We still need to implement unsorted insertion. As of today, you need to insert element in strictly ascending order.");
-        }
+impl<T> Extend<(usize, T)> for SparseVector<T>
+where
+    T: Copy + Sized + Default,
+{
+    /// Sets all values, later values replacing earlier ones with the same index.
+    fn extend<I: IntoIterator<Item = (usize, T)>>(&mut self, iter: I) {
+        self.entries.extend(iter.into_iter().map(|(index, value)| Entry { index: stored_index(index), value }));
 
-        self.entries.push(Entry {
-            index: index as u32,
-            value: T::default(),
-        });
+        // Sorting is stable, so of all entries with the same index the one set last comes last, and is moved
+        // into the one kept by `dedup_by`.
+        if !self.entries.is_sorted_by(|a, b| a.index < b.index) {
+            self.entries.sort_by_key(|entry| entry.index);
+            self.entries.dedup_by(|later, kept| {
+                let duplicate = later.index == kept.index;
+
+                if duplicate {
+                    kept.value = later.value;
+                }
 
-        // `unwrap` should be safe since we just inserted that value.
-        &mut self.entries.last_mut().unwrap().value
+                duplicate
+            });
+        }
     }
 }
 
+impl<T> FromIterator<(usize, T)> for SparseVector<T>
+where
+    T: Copy + Sized + Default,
+{
+    fn from_iter<I: IntoIterator<Item = (usize, T)>>(iter: I) -> Self {
+        let mut vector = Self::new();
+        vector.extend(iter);
+        vector
+    }
+}
+
+/// Returns `index` as stored in an [`Entry`].
+///
+/// # Panics
+///
+/// Panics if `index` is above `u32::MAX`, since such an index is out of bounds of any vector.
+fn stored_index(index: usize) -> u32 {
+    u32::try_from(index).expect("Index out of bounds.")
+}
+
 #[derive(Clone, Debug)]
 pub struct SparseMatrix<T>
 where
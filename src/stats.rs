@@ -0,0 +1,297 @@
+use crate::{
+    errors::Error,
+    parser::ModelFile,
+    svm::{core::validate::attribute_limit, DenseSVM, PrecomputedSVM, SparseSVM},
+};
+use std::{
+    convert::TryFrom,
+    fmt::{self, Display},
+    mem::size_of,
+};
+
+/// Size of a single stored value of a [`SparseSVM`] support vector, its `u32` index and `f32` value.
+const SPARSE_ENTRY_BYTES: usize = 8;
+
+/// Size of the `Vec` holding the values of a [`SparseSVM`] support vector.
+const SPARSE_VECTOR_BYTES: usize = size_of::<Vec<u64>>();
+
+/// The SVM type a model should be loaded into, see [`ModelStats::recommended`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Representation {
+    /// The model is best loaded into a [`DenseSVM`].
+    Dense,
+
+    /// The model is best loaded into a [`SparseSVM`].
+    Sparse,
+
+    /// The model has a precomputed kernel and must be loaded into a [`PrecomputedSVM`].
+    Precomputed,
+}
+
+impl Display for Representation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Dense => write!(f, "DenseSVM"),
+            Self::Sparse => write!(f, "SparseSVM"),
+            Self::Precomputed => write!(f, "PrecomputedSVM"),
+        }
+    }
+}
+
+/// Minimum, maximum and mean of a set of values.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct ValueStats {
+    /// The smallest value.
+    pub min: f64,
+
+    /// The largest value.
+    pub max: f64,
+
+    /// The mean of all values.
+    pub mean: f64,
+}
+
+impl ValueStats {
+    /// Computes the statistics of `values`, all of them `0` if there are none.
+    fn from_values(values: impl Iterator<Item = f64>) -> Self {
+        let (mut min, mut max, mut sum, mut count) = (f64::INFINITY, f64::NEG_INFINITY, 0.0, 0);
+
+        for value in values {
+            min = min.min(value);
+            max = max.max(value);
+            sum += value;
+            count += 1;
+        }
+
+        if count == 0 {
+            Self::default()
+        } else {
+            Self { min, max, mean: sum / f64::from(count) }
+        }
+    }
+}
+
+/// Statistics of a model, e.g., to decide how to load a model nothing else is known about.
+///
+/// Statistics can be computed from a [`ModelFile`] or any SVM:
+///
+/// ```
+/// use ffsvm::{DenseSVM, ModelFile, ModelStats, Representation};
+/// # use ffsvm::SAMPLE_MODEL;
+///
+/// let stats = ModelStats::try_from(&ModelFile::try_from(SAMPLE_MODEL)?)?;
+///
+/// assert_eq!(stats.num_attributes, 4);
+/// assert_eq!(stats.recommended(), Representation::Dense);
+///
+/// let svm = DenseSVM::try_from(SAMPLE_MODEL)?;
+/// assert_eq!(ModelStats::try_from(&svm)?.classes, vec![(21, 1), (42, 1)]);
+/// # Ok::<(), ffsvm::Error>(())
+/// ```
+///
+/// Statistics of an SVM describe the model as the SVM stores it, e.g., a [`DenseSVM`] stores omitted attributes as `0`.
+///
+/// Statistics are kept for every attribute, so like a [`DenseSVM`] they fail with [`Error::TooManyAttributes`] for
+/// models whose largest attribute index is far beyond what they store.
+///
+/// Writing [`ModelStats`] with [`Display`] produces a human readable report, as printed by the `ffsvm-inspect` tool.
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub struct ModelStats {
+    /// The model's `svm_type`, e.g., `c_svc`.
+    pub svm_type: String,
+
+    /// The model's `kernel_type`, e.g., `rbf`.
+    pub kernel_type: String,
+
+    /// The total number of support vectors.
+    pub total_sv: usize,
+
+    /// Label and number of support vectors of each class. Empty for regression and one-class SVMs.
+    pub classes: Vec<(i32, usize)>,
+
+    /// The number of attributes, i.e., the largest attribute index of any support vector plus one.
+    pub num_attributes: usize,
+
+    /// The fraction of attributes over all support vectors that are `0`, whether omitted from the model or not.
+    pub sparsity: f64,
+
+    /// Statistics of each attribute over all support vectors, counting omitted attributes as `0`.
+    pub features: Vec<ValueStats>,
+
+    /// Statistics of the absolute values of all support vector coefficients.
+    pub coefficients: ValueStats,
+
+    /// Estimated memory in bytes a [`DenseSVM`] needs for support vectors and coefficients.
+    pub dense_bytes: usize,
+
+    /// Estimated memory in bytes a [`SparseSVM`] needs for support vectors and coefficients.
+    pub sparse_bytes: usize,
+
+    /// The fraction of attributes over all support vectors stored in the model.
+    stored_ratio: f64,
+}
+
+impl ModelStats {
+    /// Recommends which SVM to load the model into.
+    ///
+    /// Models are recommended for a [`DenseSVM`] unless fewer than 1 in 8 of their attributes are stored. The dense
+    /// kernels process 8 attributes at once, so they are usually faster for anything denser than that.
+    #[must_use]
+    pub fn recommended(&self) -> Representation {
+        representation(&self.kernel_type, self.stored_ratio)
+    }
+}
+
+/// Recommends which SVM to load a model into like [`ModelStats::recommended`], without computing all statistics.
+///
+/// This only counts attributes, so unlike [`ModelStats`] it doesn't allocate memory per attribute, and works on models
+/// with arbitrarily large attribute indices.
+pub fn recommend(model: &ModelFile<'_>) -> Representation {
+    let vectors = model.vectors();
+    let num_attributes = vectors.iter().flat_map(|x| x.features.iter()).map(|x| x.index as usize + 1).max().unwrap_or_default();
+    let num_stored = vectors.iter().map(|x| x.features.len()).sum::<usize>();
+    let num_cells = vectors.len().saturating_mul(num_attributes).max(1);
+
+    representation(model.header().kernel_type, num_stored as f64 / num_cells as f64)
+}
+
+/// Recommends a [`DenseSVM`] unless fewer than 1 in 8 attributes are stored, see [`ModelStats::recommended`].
+fn representation(kernel_type: &str, stored_ratio: f64) -> Representation {
+    if kernel_type == "precomputed" {
+        Representation::Precomputed
+    } else if stored_ratio >= 1.0 / 8.0 {
+        Representation::Dense
+    } else {
+        Representation::Sparse
+    }
+}
+
+/// Rounds `n` up to the next multiple of `lanes`, like the rows of a `MatSimd`.
+const fn padded(n: usize, lanes: usize) -> usize {
+    n.div_ceil(lanes) * lanes
+}
+
+impl TryFrom<&ModelFile<'_>> for ModelStats {
+    type Error = Error;
+
+    fn try_from(model: &ModelFile<'_>) -> Result<Self, Error> {
+        let header = model.header();
+        let vectors = model.vectors();
+        let total_sv = vectors.len();
+        let classification = matches!(header.svm_type, "c_svc" | "nu_svc");
+
+        let num_attributes = vectors.iter().flat_map(|x| x.features.iter()).map(|x| x.index as usize + 1).max().unwrap_or_default();
+        let num_stored = vectors.iter().map(|x| x.features.len()).sum::<usize>();
+        let num_cells = total_sv.saturating_mul(num_attributes).max(1);
+        let limit = attribute_limit(model);
+
+        if num_attributes > limit {
+            return Err(Error::TooManyAttributes { limit, found: num_attributes });
+        }
+
+        let mut sums = vec![0.0; num_attributes];
+        let mut counts = vec![0; num_attributes];
+        let mut non_zero = 0;
+        let mut features = vec![ValueStats { min: f64::INFINITY, max: f64::NEG_INFINITY, mean: 0.0 }; num_attributes];
+
+        for attribute in vectors.iter().flat_map(|x| x.features.iter()) {
+            let (index, value) = (attribute.index as usize, f64::from(attribute.value));
+            let stats = &mut features[index];
+
+            stats.min = stats.min.min(value);
+            stats.max = stats.max.max(value);
+            sums[index] += value;
+            counts[index] += 1;
+            non_zero += usize::from(value != 0.0);
+        }
+
+        // Attributes omitted from some support vector are `0` there.
+        for (stats, _) in features.iter_mut().zip(&counts).filter(|(_, count)| **count < total_sv) {
+            stats.min = stats.min.min(0.0);
+            stats.max = stats.max.max(0.0);
+        }
+
+        for (stats, sum) in features.iter_mut().zip(&sums) {
+            stats.mean = sum / total_sv as f64;
+        }
+
+        let classes = if classification {
+            header.label.iter().zip(&header.nr_sv).map(|(label, n)| (*label, *n as usize)).collect()
+        } else {
+            Vec::new()
+        };
+
+        // Support vectors and coefficients are stored per class, regression and one-class SVMs have a single one.
+        let class_sizes = if classification { classes.iter().map(|(_, n)| *n).collect() } else { vec![total_sv] };
+        let num_coefficients = vectors.first().map_or(0, |x| x.coefs.len());
+        let coefficient_bytes = class_sizes.iter().map(|n| num_coefficients * padded(*n, 4) * size_of::<f64>()).sum::<usize>();
+        let dense_bytes = total_sv * padded(num_attributes, 8) * size_of::<f32>() + coefficient_bytes;
+        let sparse_bytes = num_stored * SPARSE_ENTRY_BYTES + total_sv * SPARSE_VECTOR_BYTES + coefficient_bytes;
+
+        Ok(Self {
+            svm_type: header.svm_type.to_string(),
+            kernel_type: header.kernel_type.to_string(),
+            total_sv,
+            classes,
+            num_attributes,
+            sparsity: 1.0 - non_zero as f64 / num_cells as f64,
+            features,
+            coefficients: ValueStats::from_values(vectors.iter().flat_map(|x| x.coefs.iter()).map(|x| f64::from(x.abs()))),
+            dense_bytes,
+            sparse_bytes,
+            stored_ratio: num_stored as f64 / num_cells as f64,
+        })
+    }
+}
+
+impl TryFrom<&DenseSVM> for ModelStats {
+    type Error = Error;
+
+    fn try_from(svm: &DenseSVM) -> Result<Self, Error> {
+        Self::try_from(&ModelFile::from(svm))
+    }
+}
+
+impl TryFrom<&SparseSVM> for ModelStats {
+    type Error = Error;
+
+    fn try_from(svm: &SparseSVM) -> Result<Self, Error> {
+        Self::try_from(&ModelFile::from(svm))
+    }
+}
+
+impl TryFrom<&PrecomputedSVM> for ModelStats {
+    type Error = Error;
+
+    fn try_from(svm: &PrecomputedSVM) -> Result<Self, Error> {
+        Self::try_from(&ModelFile::from(svm))
+    }
+}
+
+impl Display for ModelStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "svm_type        {}", self.svm_type)?;
+        writeln!(f, "kernel_type     {}", self.kernel_type)?;
+        writeln!(f, "total_sv        {}", self.total_sv)?;
+
+        if !self.classes.is_empty() {
+            let classes = self.classes.iter().map(|(label, n)| format!("{label}: {n}")).collect::<Vec<_>>();
+            writeln!(f, "classes         {}", classes.join(", "))?;
+        }
+
+        writeln!(f, "attributes      {}", self.num_attributes)?;
+        writeln!(f, "sparsity        {:.1}%", self.sparsity * 100.0)?;
+        writeln!(f, "|coefficients|  min {:.6}, max {:.6}, mean {:.6}", self.coefficients.min, self.coefficients.max, self.coefficients.mean)?;
+        writeln!(f, "memory          {} bytes dense, {} bytes sparse", self.dense_bytes, self.sparse_bytes)?;
+        writeln!(f, "recommended     {}", self.recommended())?;
+        writeln!(f)?;
+        writeln!(f, "{:>9} {:>14} {:>14} {:>14}", "attribute", "min", "max", "mean")?;
+
+        for (index, stats) in self.features.iter().enumerate() {
+            writeln!(f, "{index:>9} {:>14.6} {:>14.6} {:>14.6}", stats.min, stats.max, stats.mean)?;
+        }
+
+        Ok(())
+    }
+}
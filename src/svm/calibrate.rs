@@ -0,0 +1,86 @@
+use crate::{
+    errors::Error,
+    svm::{features::FeatureVector, predict::Predict, Probabilities, SVMType},
+    util::sigmoid_train,
+    vectors::Triangular,
+};
+
+/// Fits the probability estimates of an SVM to labeled samples.
+///
+/// For classifiers, a sigmoid is fitted for each class pair to the decision values of the samples of these two
+/// classes, like libSVM's `svm_binary_svc_probability` does to cross-validation decision values during training.
+/// For regression SVMs, the scale of the Laplace distribution of the prediction errors is estimated like in
+/// libSVM's `svm_svr_probability`.
+pub fn fit_probabilities<T>(svm: &impl Predict<T>, svm_type: SVMType, samples: &mut [(f64, FeatureVector<T>)]) -> Result<Probabilities, Error> {
+    if samples.is_empty() {
+        return Err(Error::InvalidCalibrationData("no samples"));
+    }
+
+    match svm_type {
+        SVMType::CSvc | SVMType::NuSvc => fit_sigmoids(svm, samples),
+        SVMType::ESvr | SVMType::NuSvr => fit_laplace(svm, samples),
+        SVMType::OneClass => Err(Error::InvalidCalibrationData("a one-class SVM")),
+    }
+}
+
+/// Fits a sigmoid to the decision values of each class pair.
+fn fit_sigmoids<T>(svm: &impl Predict<T>, samples: &mut [(f64, FeatureVector<T>)]) -> Result<Probabilities, Error> {
+    let num_classes = samples[0].1.classes.len();
+    let mut classes = Vec::with_capacity(samples.len());
+
+    for (label, problem) in samples.iter_mut() {
+        svm.predict_value(problem)?;
+
+        // Labels are truncated like `svm-train` does, and the feature vector knows all labels of the SVM.
+        let class = problem.classes.iter().position(|(x, _)| *x == *label as i32);
+        classes.push(class.ok_or(Error::InvalidCalibrationData("labels not in the model"))?);
+    }
+
+    let mut a = Vec::with_capacity(num_classes * (num_classes - 1) / 2);
+    let mut b = Vec::with_capacity(a.capacity());
+
+    for i in 0..num_classes {
+        for j in i + 1..num_classes {
+            let (decision_values, labels): (Vec<f64>, Vec<bool>) = samples
+                .iter()
+                .zip(&classes)
+                .filter(|(_, class)| **class == i || **class == j)
+                .map(|((_, problem), class)| (problem.decision_value(i, j), *class == i))
+                .unzip();
+
+            // Without samples of both classes, the sigmoid would be flat, and predict `0.5` for either class.
+            if !labels.contains(&true) || !labels.contains(&false) {
+                return Err(Error::InvalidCalibrationData("a class without samples"));
+            }
+
+            let (a_ij, b_ij) = sigmoid_train(&decision_values, &labels);
+            a.push(a_ij);
+            b.push(b_ij);
+        }
+    }
+
+    Ok(Probabilities { a: Triangular::from(&a), b: Triangular::from(&b) })
+}
+
+/// Estimates the scale of the Laplace distribution of the prediction errors as their mean absolute value, ignoring
+/// outliers beyond five standard deviations.
+fn fit_laplace<T>(svm: &impl Predict<T>, samples: &mut [(f64, FeatureVector<T>)]) -> Result<Probabilities, Error> {
+    let mut errors = Vec::with_capacity(samples.len());
+
+    for (label, problem) in samples.iter_mut() {
+        svm.predict_value(problem)?;
+        errors.push((*label - problem.raw_value()).abs());
+    }
+
+    let mae = errors.iter().sum::<f64>() / errors.len() as f64;
+    let std = (2.0 * mae * mae).sqrt();
+    let inliers = errors.iter().filter(|x| **x <= 5.0 * std).collect::<Vec<_>>();
+    let sigma = inliers.iter().copied().sum::<f64>() / inliers.len() as f64;
+
+    // A Laplace distribution of scale `0` has no density, so perfect predictions can't be calibrated on.
+    if !sigma.is_normal() {
+        return Err(Error::InvalidCalibrationData("no prediction errors"));
+    }
+
+    Ok(Probabilities { a: Triangular::from(&vec![sigma]), b: Triangular::with_dimension(0, 0.0) })
+}
@@ -4,6 +4,8 @@ use simd_aligned::{
     MatSimd, Rows,
 };
 
+use std::collections::BTreeMap;
+
 /// Represents one class of the SVM model.
 #[derive(Clone, Debug)]
 #[doc(hidden)]
@@ -16,10 +18,17 @@ pub struct Class<M32> {
     /// Coefficients between this class and n-1 other classes.
     pub(crate) coefficients: MatSimd<f64x4, Rows>,
 
-    /// All support vectors in this class.
+    /// All support vectors in this class. For precomputed kernels these are the training serial numbers.
     pub(crate) support_vectors: M32,
 }
 
+impl<M32> Class<M32> {
+    /// Returns the number of support vectors in this class.
+    pub fn num_support_vectors(&self) -> usize {
+        self.coefficients.dimension().1
+    }
+}
+
 impl Class<MatSimd<f32x8, Rows>> {
     /// Creates a new class with the given parameters.
     pub fn with_parameters(classes: usize, support_vectors: usize, attributes: usize, label: i32) -> Self {
@@ -29,6 +38,19 @@ impl Class<MatSimd<f32x8, Rows>> {
             support_vectors: MatSimd::with_dimension(support_vectors, attributes),
         }
     }
+
+    /// Adds all support vectors, weighted by their coefficients in the given row, to `weights`.
+    pub fn add_weighted(&self, weights: &mut [f64], row: usize) {
+        let coefficients = self.coefficients.flat();
+
+        for i_vector in 0..self.coefficients.dimension().1 {
+            let coefficient = coefficients[(row, i_vector)];
+
+            for (weight, value) in weights.iter_mut().zip(self.support_vectors.row_as_flat(i_vector)) {
+                *weight += coefficient * f64::from(*value);
+            }
+        }
+    }
 }
 
 impl Class<SparseMatrix<f32>> {
@@ -40,4 +62,28 @@ impl Class<SparseMatrix<f32>> {
             support_vectors: SparseMatrix::with(support_vectors),
         }
     }
+
+    /// Adds all support vectors, weighted by their coefficients in the given row, to `weights`.
+    pub fn add_weighted(&self, weights: &mut BTreeMap<u32, f64>, row: usize) {
+        let coefficients = self.coefficients.flat();
+
+        for (i_vector, sv) in self.support_vectors.row_iter().enumerate() {
+            let coefficient = coefficients[(row, i_vector)];
+
+            for (index, value) in sv {
+                *weights.entry(index).or_default() += coefficient * f64::from(value);
+            }
+        }
+    }
+}
+
+impl Class<Vec<u32>> {
+    /// Creates a new class with the given parameters.
+    pub fn with_parameters(classes: usize, support_vectors: usize, _attributes: usize, label: i32) -> Self {
+        Self {
+            label,
+            coefficients: MatSimd::with_dimension(classes - 1, support_vectors),
+            support_vectors: vec![0; support_vectors],
+        }
+    }
 }
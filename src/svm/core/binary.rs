@@ -0,0 +1,491 @@
+//! A compact binary format for [`DenseSVM`], which loads without parsing text.
+//!
+//! All numbers are little endian. The file starts with a fixed size header:
+//!
+//! ```text
+//! offset  type     content
+//!      0  [u8; 8]  magic number `FFSVMBIN`
+//!      8  u32      format version
+//!     12  u32      svm type, index into `SVM_TYPES`
+//!     16  u32      kernel type, index into `KERNELS`
+//!     20  u32      degree
+//!     24  f32      gamma
+//!     28  f32      coef0
+//!     32  u32      number of classes, `1` for regression and one-class SVMs
+//!     36  u32      total number of support vectors
+//!     40  u32      number of attributes
+//!     44  u32      flags, see `FLAG_PROB_A` and `FLAG_PROB_B`
+//!     48  u64      payload length in bytes
+//!     56  u64      FNV-1a checksum of the payload
+//! ```
+//!
+//! The payload follows right after. It consists of blocks, each starting at a multiple of [`ALIGNMENT`]
+//! and padded with zeros to the next one:
+//!
+//! - `rho`, one `f64` per class pair,
+//! - `probA` and `probB`, one `f64` per class pair each, if flagged,
+//! - one `(label: i32, num_sv: u32)` pair per class,
+//! - per class, its coefficients as laid out in a `MatSimd<f64x4, Rows>`, followed by its support
+//!   vectors as laid out in a `MatSimd<f32x8, Rows>`. Each row is padded to whole SIMD vectors.
+use crate::{
+    errors::Error,
+    parser::{Header, ModelFile},
+    svm::{
+        class::Class,
+        kernel::{KernelDense, Linear, Poly, Rbf, Sigmoid},
+        DenseSVM, Probabilities, SVMType,
+    },
+    vectors::Triangular,
+};
+use simd_aligned::{
+    arch::{f32x8, f64x4},
+    traits::Simd,
+    MatSimd, Rows,
+};
+use std::convert::TryFrom;
+
+/// Magic number every binary model starts with.
+pub const MAGIC: [u8; 8] = *b"FFSVMBIN";
+
+/// Version of the format written by [`DenseSVM::to_bytes`].
+pub const VERSION: u32 = 1;
+
+/// Size of the header in bytes.
+pub const HEADER_SIZE: usize = 64;
+
+/// All payload blocks start at multiples of this, the size of a `f32x8` and `f64x4`.
+pub const ALIGNMENT: usize = 32;
+
+/// Set if the payload contains `probA`.
+const FLAG_PROB_A: u32 = 1;
+
+/// Set if the payload contains `probB`.
+const FLAG_PROB_B: u32 = 2;
+
+/// SVM types in the order of their binary identifiers.
+const SVM_TYPES: [SVMType; 5] = [SVMType::CSvc, SVMType::NuSvc, SVMType::ESvr, SVMType::NuSvr, SVMType::OneClass];
+
+/// Kernel types in the order of their binary identifiers.
+const KERNELS: [&str; 4] = ["linear", "polynomial", "rbf", "sigmoid"];
+
+/// Computes the 64 bit FNV-1a hash of the given data.
+pub fn checksum(data: &[u8]) -> u64 {
+    data.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3))
+}
+
+/// Rounds the given length up to the next multiple of [`ALIGNMENT`].
+const fn aligned(length: usize) -> usize {
+    length.div_ceil(ALIGNMENT) * ALIGNMENT
+}
+
+/// Number of bytes a `MatSimd` with the given dimension occupies.
+pub const fn matrix_size<T: Simd>(rows: usize, columns: usize) -> usize {
+    rows * columns.div_ceil(T::LANES) * ALIGNMENT
+}
+
+/// Appends the given values to `bytes`, followed by padding up to the next block.
+fn write_block<const N: usize>(bytes: &mut Vec<u8>, values: impl Iterator<Item = [u8; N]>) {
+    for value in values {
+        bytes.extend_from_slice(&value);
+    }
+
+    bytes.resize(aligned(bytes.len()), 0);
+}
+
+/// The decoded fixed size header.
+#[derive(Copy, Clone, Debug)]
+pub struct BinaryHeader {
+    pub svm_type: SVMType,
+    pub kernel_type: &'static str,
+    pub degree: u32,
+    pub gamma: f32,
+    pub coef0: f32,
+    pub num_classes: usize,
+    pub num_total_sv: usize,
+    pub num_attributes: usize,
+    pub flags: u32,
+    pub payload_length: usize,
+    pub checksum: u64,
+}
+
+impl BinaryHeader {
+    /// Number of entries in `rho`, `probA` and `probB`.
+    pub const fn num_pairs(&self) -> usize {
+        match self.svm_type {
+            SVMType::CSvc | SVMType::NuSvc => self.num_classes * (self.num_classes - 1) / 2,
+            SVMType::ESvr | SVMType::NuSvr | SVMType::OneClass => 1,
+        }
+    }
+
+    /// Number of coefficient rows per class.
+    pub const fn num_coefficients(&self) -> usize {
+        match self.svm_type {
+            SVMType::CSvc | SVMType::NuSvc => self.num_classes - 1,
+            SVMType::ESvr | SVMType::NuSvr | SVMType::OneClass => 1,
+        }
+    }
+
+    /// Size of the payload up to and including the class table.
+    pub fn table_size(&self) -> usize {
+        let pairs = aligned(self.num_pairs() * 8);
+        let probabilities = [FLAG_PROB_A, FLAG_PROB_B].iter().filter(|flag| self.flags & **flag != 0).count();
+
+        pairs * (1 + probabilities) + aligned(self.num_classes * 8)
+    }
+
+    /// Size of the coefficients and support vectors of a class with the given number of support vectors.
+    pub const fn class_size(&self, num_sv: usize) -> usize {
+        matrix_size::<f64x4>(self.num_coefficients(), num_sv) + matrix_size::<f32x8>(num_sv, self.num_attributes)
+    }
+
+    /// Reads the header, checking magic number, version, length and checksum.
+    pub fn read(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() < MAGIC.len() || bytes[..MAGIC.len()] != MAGIC {
+            return Err(Error::BinaryInvalidMagic);
+        }
+
+        if bytes.len() < HEADER_SIZE {
+            return Err(Error::BinaryTruncated {
+                expected: HEADER_SIZE,
+                found: bytes.len(),
+            });
+        }
+
+        let mut reader = Reader { bytes, offset: MAGIC.len() };
+        let version = reader.u32();
+
+        if version != VERSION {
+            return Err(Error::BinaryUnsupportedVersion(version));
+        }
+
+        let svm_type = *SVM_TYPES.get(reader.u32() as usize).ok_or(Error::BinaryInvalidHeader("svm_type"))?;
+        let kernel_type = *KERNELS.get(reader.u32() as usize).ok_or(Error::BinaryInvalidHeader("kernel_type"))?;
+
+        let header = Self {
+            svm_type,
+            kernel_type,
+            degree: reader.u32(),
+            gamma: reader.f32(),
+            coef0: reader.f32(),
+            num_classes: reader.u32() as usize,
+            num_total_sv: reader.u32() as usize,
+            num_attributes: reader.u32() as usize,
+            flags: reader.u32(),
+            payload_length: usize::try_from(reader.u64()).map_err(|_| Error::BinaryInvalidHeader("payload_length"))?,
+            checksum: reader.u64(),
+        };
+
+        let expected = HEADER_SIZE.saturating_add(header.payload_length);
+
+        if bytes.len() < expected {
+            return Err(Error::BinaryTruncated { expected, found: bytes.len() });
+        }
+
+        if bytes.len() > expected {
+            return Err(Error::BinaryInvalidHeader("payload_length"));
+        }
+
+        let found = checksum(&bytes[HEADER_SIZE..]);
+
+        if found != header.checksum {
+            return Err(Error::BinaryChecksumMismatch {
+                expected: header.checksum,
+                found,
+            });
+        }
+
+        let num_classes_valid = match svm_type {
+            SVMType::CSvc | SVMType::NuSvc => header.num_classes >= 2,
+            SVMType::ESvr | SVMType::NuSvr | SVMType::OneClass => header.num_classes == 1,
+        };
+
+        if !num_classes_valid {
+            return Err(Error::BinaryInvalidHeader("num_classes"));
+        }
+
+        if header.num_total_sv == 0 {
+            return Err(Error::NoSupportVectors);
+        }
+
+        if header.flags & !(FLAG_PROB_A | FLAG_PROB_B) != 0 {
+            return Err(Error::BinaryInvalidHeader("flags"));
+        }
+
+        // Guards the size computations below against absurd, but checksummed, headers.
+        if header.num_classes > header.payload_length || header.num_total_sv > header.payload_length || header.num_attributes > header.payload_length {
+            return Err(Error::BinaryInvalidHeader("payload_length"));
+        }
+
+        Ok(header)
+    }
+
+    /// Reads `rho`, probabilities and the class table, and makes sure the payload has exactly the size they require.
+    pub fn read_tables(&self, bytes: &[u8]) -> Result<BinaryTables, Error> {
+        let num_pairs = self.num_pairs();
+
+        // Make sure the class table can be read before we look at it
+        if self.table_size() > self.payload_length {
+            return Err(Error::BinaryInvalidHeader("payload_length"));
+        }
+
+        let mut reader = Reader { bytes, offset: HEADER_SIZE };
+
+        let rho = reader.block(num_pairs, Reader::f64);
+
+        let probabilities = match (self.flags & FLAG_PROB_A != 0, self.flags & FLAG_PROB_B != 0) {
+            (true, true) => Some(Probabilities {
+                a: Triangular::from(&reader.block(num_pairs, Reader::f64)),
+                b: Triangular::from(&reader.block(num_pairs, Reader::f64)),
+            }),
+            (true, false) => Some(Probabilities {
+                a: Triangular::from(&reader.block(num_pairs, Reader::f64)),
+                b: Triangular::with_dimension(0, 0.0),
+            }),
+            (false, false) => None,
+            (false, true) => return Err(Error::BinaryInvalidHeader("flags")),
+        };
+
+        let classes = reader.block(self.num_classes, |reader| (reader.i32(), reader.u32() as usize));
+
+        // With the class table known the payload length must match exactly
+        let found = classes.iter().map(|(_, num_sv)| *num_sv).sum::<usize>();
+
+        if found != self.num_total_sv {
+            return Err(Error::InconsistentSupportVectorCount {
+                total_sv: self.num_total_sv,
+                found,
+            });
+        }
+
+        let payload_length = classes.iter().fold(self.table_size(), |size, (_, num_sv)| size + self.class_size(*num_sv));
+
+        if payload_length != self.payload_length {
+            return Err(Error::BinaryInvalidHeader("payload_length"));
+        }
+
+        Ok(BinaryTables {
+            rho: Triangular::from(&rho),
+            probabilities,
+            classes,
+            offset: reader.offset,
+        })
+    }
+
+    /// Returns the `ModelFile` header the kernel of this model can be created from.
+    pub fn kernel_header(&self) -> Header<'static> {
+        Header {
+            kernel_type: self.kernel_type,
+            gamma: Some(self.gamma),
+            coef0: Some(self.coef0),
+            degree: Some(self.degree),
+            ..Header::default()
+        }
+    }
+
+    /// Creates the kernel of this model.
+    pub fn kernel(&self) -> Result<Box<dyn KernelDense>, Error> {
+        let raw_model = ModelFile::new(self.kernel_header(), Vec::new());
+
+        Ok(match self.kernel_type {
+            "rbf" => Box::new(Rbf::try_from(&raw_model)?),
+            "polynomial" => Box::new(Poly::try_from(&raw_model)?),
+            "sigmoid" => Box::new(Sigmoid::try_from(&raw_model)?),
+            _ => Box::new(Linear::from(&raw_model)),
+        })
+    }
+}
+
+/// Everything of a binary model but the coefficients and support vectors of its classes.
+pub struct BinaryTables {
+    pub rho: Triangular<f64>,
+    pub probabilities: Option<Probabilities>,
+
+    /// Label and number of support vectors of each class.
+    pub classes: Vec<(i32, usize)>,
+
+    /// Offset of the first class' coefficients.
+    pub offset: usize,
+}
+
+/// Reads consecutive little endian numbers. Callers must make sure enough bytes are available.
+pub struct Reader<'a> {
+    pub bytes: &'a [u8],
+    pub offset: usize,
+}
+
+impl Reader<'_> {
+    fn take<const N: usize>(&mut self) -> [u8; N] {
+        let mut value = [0; N];
+        value.copy_from_slice(&self.bytes[self.offset..self.offset + N]);
+        self.offset += N;
+        value
+    }
+
+    pub fn u32(&mut self) -> u32 {
+        u32::from_le_bytes(self.take())
+    }
+
+    pub fn i32(&mut self) -> i32 {
+        i32::from_le_bytes(self.take())
+    }
+
+    pub fn u64(&mut self) -> u64 {
+        u64::from_le_bytes(self.take())
+    }
+
+    pub fn f32(&mut self) -> f32 {
+        f32::from_le_bytes(self.take())
+    }
+
+    pub fn f64(&mut self) -> f64 {
+        f64::from_le_bytes(self.take())
+    }
+
+    /// Reads `count` values of a block and skips its padding.
+    pub fn block<T>(&mut self, count: usize, read: impl Fn(&mut Self) -> T) -> Vec<T> {
+        let start = self.offset;
+        let values = (0..count).map(|_| read(self)).collect();
+        self.offset = start + aligned(self.offset - start);
+        values
+    }
+
+    /// Fills a `MatSimd` from its binary layout, including the padding of each row.
+    pub fn matrix<T>(&mut self, matrix: &mut MatSimd<T, Rows>, read: impl Fn(&mut Self) -> T::Element)
+    where
+        T: Simd + Default + Clone,
+    {
+        let (rows, columns) = matrix.dimension();
+        let padded = columns.div_ceil(T::LANES) * ALIGNMENT;
+
+        for row in 0..rows {
+            let start = self.offset;
+
+            for value in matrix.row_as_flat_mut(row) {
+                *value = read(self);
+            }
+
+            self.offset = start + padded;
+        }
+    }
+}
+
+impl DenseSVM {
+    /// Serializes this SVM into FFSVM's binary model format.
+    ///
+    /// Loading the result with [`DenseSVM::try_from`] is much faster than parsing a libSVM model, as no text has
+    /// to be parsed and support vectors are stored exactly as they are laid out in memory. The format is
+    /// versioned and checksummed, so truncated or corrupted data produces an [`Error`] instead of a broken SVM.
+    ///
+    /// ```rust
+    /// use ffsvm::DenseSVM;
+    /// # use ffsvm::SAMPLE_MODEL;
+    ///
+    /// let svm = DenseSVM::try_from(SAMPLE_MODEL)?;
+    /// let bytes = svm.to_bytes();
+    /// let svm = DenseSVM::try_from(bytes.as_slice())?;
+    /// # Ok::<(), ffsvm::Error>(())
+    /// ```
+    #[must_use]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut kernel_header = Header::default();
+        self.kernel.write_header(&mut kernel_header);
+
+        let svm_type = SVM_TYPES.iter().position(|x| *x == self.svm_type).unwrap_or(usize::MAX);
+        let kernel_type = KERNELS.iter().position(|x| *x == kernel_header.kernel_type).unwrap_or(usize::MAX);
+
+        let mut flags = 0;
+        let mut payload = Vec::new();
+
+        write_block(&mut payload, self.rho.data.iter().map(|x| x.to_le_bytes()));
+
+        if let Some(probabilities) = &self.probabilities {
+            flags |= FLAG_PROB_A;
+            write_block(&mut payload, probabilities.a.data.iter().map(|x| x.to_le_bytes()));
+
+            // For SVRs only one probability array is given
+            if !probabilities.b.data.is_empty() {
+                flags |= FLAG_PROB_B;
+                write_block(&mut payload, probabilities.b.data.iter().map(|x| x.to_le_bytes()));
+            }
+        }
+
+        write_block(
+            &mut payload,
+            self.classes.iter().map(|class| {
+                let mut entry = [0; 8];
+                entry[..4].copy_from_slice(&class.label.to_le_bytes());
+                entry[4..].copy_from_slice(&(class.coefficients.dimension().1 as u32).to_le_bytes());
+                entry
+            }),
+        );
+
+        for class in &self.classes {
+            for row in class.coefficients.row_iter() {
+                write_block(&mut payload, row.iter().flat_map(Simd::as_array).map(|x| x.to_le_bytes()));
+            }
+
+            for row in class.support_vectors.row_iter() {
+                write_block(&mut payload, row.iter().flat_map(Simd::as_array).map(|x| x.to_le_bytes()));
+            }
+        }
+
+        let mut bytes = Vec::with_capacity(HEADER_SIZE + payload.len());
+
+        bytes.extend_from_slice(&MAGIC);
+        bytes.extend_from_slice(&VERSION.to_le_bytes());
+        bytes.extend_from_slice(&(svm_type as u32).to_le_bytes());
+        bytes.extend_from_slice(&(kernel_type as u32).to_le_bytes());
+        bytes.extend_from_slice(&kernel_header.degree.unwrap_or_default().to_le_bytes());
+        bytes.extend_from_slice(&kernel_header.gamma.unwrap_or_default().to_le_bytes());
+        bytes.extend_from_slice(&kernel_header.coef0.unwrap_or_default().to_le_bytes());
+        bytes.extend_from_slice(&(self.classes.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(&(self.num_total_sv as u32).to_le_bytes());
+        bytes.extend_from_slice(&(self.num_attributes as u32).to_le_bytes());
+        bytes.extend_from_slice(&flags.to_le_bytes());
+        bytes.extend_from_slice(&(payload.len() as u64).to_le_bytes());
+        bytes.extend_from_slice(&checksum(&payload).to_le_bytes());
+        bytes.extend_from_slice(&payload);
+
+        bytes
+    }
+}
+
+impl<'a> TryFrom<&'a [u8]> for DenseSVM {
+    type Error = Error;
+
+    /// Loads an SVM from FFSVM's binary model format, as produced by [`DenseSVM::to_bytes`].
+    fn try_from(bytes: &'a [u8]) -> Result<Self, Error> {
+        let header = BinaryHeader::read(bytes)?;
+        let tables = header.read_tables(bytes)?;
+
+        let mut reader = Reader { bytes, offset: tables.offset };
+        let mut classes = Vec::with_capacity(tables.classes.len());
+
+        for (label, num_sv) in tables.classes {
+            let mut class = Class::<MatSimd<f32x8, Rows>>::with_parameters(header.num_coefficients() + 1, num_sv, header.num_attributes, label);
+
+            reader.matrix(&mut class.coefficients, Reader::f64);
+            reader.matrix(&mut class.support_vectors, Reader::f32);
+
+            classes.push(class);
+        }
+
+        let mut svm = Self {
+            num_total_sv: header.num_total_sv,
+            num_attributes: header.num_attributes,
+            rho: tables.rho,
+            probabilities: tables.probabilities,
+            svm_type: header.svm_type,
+            kernel: header.kernel()?,
+            classes,
+            weights: None,
+        };
+
+        // Linear models don't need their support vectors for predictions
+        if header.kernel_type == "linear" {
+            svm.weights = Some(svm.linear_weights());
+        }
+
+        Ok(svm)
+    }
+}
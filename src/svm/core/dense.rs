@@ -1,19 +1,56 @@
 use crate::{
     errors::Error,
-    parser::ModelFile,
+    parser::{Attribute, Header, ModelFile, SupportVector},
     svm::{
+        calibrate::fit_probabilities,
         class::Class,
-        features::{FeatureVector, Label},
+        features::{AsFeatures, FeatureVector, Label},
         kernel::{KernelDense, Linear, Poly, Rbf, Sigmoid},
+        laplace::Laplace,
         predict::Predict,
+        core::validate::{attribute_limit, validate},
         Probabilities, SVMType,
     },
     util::{find_max_index, set_all, sigmoid_predict},
     vectors::Triangular,
 };
-use simd_aligned::{arch::f32x8, traits::Simd, MatSimd, Rows, VecSimd};
+
+#[cfg(feature = "rayon")]
+use crate::svm::parallel::{compute_chunks, rows_per_chunk};
+use simd_aligned::{
+    arch::{f32x8, f64x4},
+    traits::Simd,
+    MatSimd, Rows, VecSimd,
+};
 use std::convert::TryFrom;
 
+/// Number of samples [`DenseSVM::predict_values_batch`] and [`DenseSVM::predict_probabilities_batch`] process together.
+const BATCH_SAMPLES: usize = 16;
+
+/// Size of the blocks of support vectors batch predictions compare to all samples of a batch, chosen to fit into the L1 cache.
+const BATCH_BLOCK_BYTES: usize = 16 * 1024;
+
+/// How a [`DenseSVM`] determines its number of attributes when loading a model.
+///
+/// libSVM's `svm-train` omits attributes that are `0` from the support vectors it writes, so
+/// models usually list attributes like `0:`, `1:`, `4:`, ... instead of `0:`, `1:`, `2:`, ...
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum AttributePolicy {
+    /// Attributes are stored by index, omitted attributes are `0`, and the largest index of any
+    /// support vector determines the number of attributes. Indices far larger than the model justifies
+    /// produce an [`Error::TooManyAttributes`].
+    #[default]
+    Infer,
+
+    /// Like [`AttributePolicy::Infer`], but with the given number of attributes, e.g., when the last
+    /// attributes were `0` in all support vectors. Larger indices produce an [`Error::AttributeCountMismatch`].
+    Fixed(usize),
+
+    /// Attributes must be numbered `0, 1, 2, ...` without omissions, and the first support vector
+    /// determines the number of attributes. Models omitting attributes produce an [`Error::AttributesUnordered`].
+    Strict,
+}
+
 /// An SVM using [SIMD](https://en.wikipedia.org/wiki/SIMD) intrinsics optimized for speed.
 ///
 ///
@@ -44,6 +81,10 @@ pub struct DenseSVM {
 
     /// All classes
     pub(crate) classes: Vec<Class<MatSimd<f32x8, Rows>>>,
+
+    /// For linear kernels, the weight vector `w` of each class pair in the order of `rho`, so that a
+    /// decision value is `w·x - rho`.
+    pub(crate) weights: Option<MatSimd<f32x8, Rows>>,
 }
 
 impl DenseSVM {
@@ -95,17 +136,28 @@ impl DenseSVM {
     }
 
     /// Computes the kernel values for this problem
-    pub(crate) fn compute_kernel_values(&self, problem: &mut FeatureVector<VecSimd<f32x8>>) {
+    pub(crate) fn compute_kernel_values<F: AsFeatures<VecSimd<f32x8>>>(&self, problem: &mut FeatureVector<F>) {
         // Get current problem and decision values array
-        let features = &problem.features;
+        let features = problem.features.as_features();
         let kernel_values = &mut problem.kernel_values;
 
         // Compute kernel values per class
         for (i, class) in self.classes.iter().enumerate() {
             let kvalues = kernel_values.row_as_flat_mut(i);
 
+            // Classes with many support vectors are split into chunks computed in parallel.
+            #[cfg(feature = "rayon")]
+            if let Some(rows) = rows_per_chunk(class.support_vectors.dimension().0, self.num_attributes) {
+                compute_chunks(kvalues, class.support_vectors.dimension().0, rows, |range, output| {
+                    self.kernel.compute_range(&class.support_vectors, range, features, output);
+                });
+                continue;
+            }
+
             self.kernel.compute(&class.support_vectors, features, kvalues);
         }
+
+        problem.has_kernel_values = true;
     }
 
     // This is pretty much copy-paste of `multiclass_probability` from libSVM which we need
@@ -113,26 +165,195 @@ impl DenseSVM {
     // based on Method 2 from the paper "Probability Estimates for Multi-class
     // Classification by Pairwise Coupling", Journal of Machine Learning Research 5 (2004) 975-1005,
     // by Ting-Fan Wu, Chih-Jen Lin and Ruby C. Weng.
-    pub(crate) fn compute_multiclass_probabilities(&self, problem: &mut FeatureVector<VecSimd<f32x8>>) -> Result<(), Error> {
+    pub(crate) fn compute_multiclass_probabilities<F>(&self, problem: &mut FeatureVector<F>) -> Result<(), Error> {
         compute_multiclass_probabilities_impl!(self, problem)
     }
 
-    /// Based on kernel values, computes the decision values for this problem.
-    pub(crate) fn compute_classification_values(&self, problem: &mut FeatureVector<VecSimd<f32x8>>) {
-        compute_classification_values_impl!(self, problem);
+    /// Based on kernel values, or the weights of linear kernels, computes the decision values for this problem.
+    pub(crate) fn compute_classification_values<F: AsFeatures<VecSimd<f32x8>>>(&self, problem: &mut FeatureVector<F>) {
+        if let Some(weights) = &self.weights {
+            compute_linear_classification_values_impl!(self, problem, weights);
+        } else {
+            compute_classification_values_impl!(self, problem);
+        }
     }
 
-    /// Based on kernel values, computes the decision values for this problem.
-    pub(crate) fn compute_regression_values(&self, problem: &mut FeatureVector<VecSimd<f32x8>>) {
-        let class = &self.classes[0];
-        let coef = class.coefficients.row(0);
-        let kvalues = problem.kernel_values.row(0);
+    /// Based on kernel values, or the weights of linear kernels, computes the single decision value of regression and one-class SVMs.
+    pub(crate) fn compute_regression_values<F: AsFeatures<VecSimd<f32x8>>>(&self, problem: &mut FeatureVector<F>) {
+        // For linear kernels `w·x` replaces the weighted sum over all support vectors
+        let mut sum = if let Some(weights) = &self.weights {
+            let mut dot = [0.0];
+            problem.has_kernel_values = false;
+            self.kernel.compute(weights, problem.features.as_features(), &mut dot);
+            dot[0]
+        } else {
+            let class = &self.classes[0];
+            let coef = class.coefficients.row(0);
+            let kvalues = problem.kernel_values.row(0);
 
-        let mut sum = coef.iter().zip(kvalues).map(|(a, b)| (*a * *b).sum()).sum::<f64>();
+            coef.iter().zip(kvalues).map(|(a, b)| (*a * *b).sum()).sum::<f64>()
+        };
 
         sum -= self.rho[0];
 
-        problem.result = Label::Value(sum as f32);
+        problem.raw_value = sum;
+        problem.result = match self.svm_type {
+            // Same as `libSVM`, one-class SVMs report inliers as `+1` and outliers as `-1`.
+            SVMType::OneClass => Label::Class(if sum > 0.0 { 1 } else { -1 }),
+            _ => Label::Value(sum as f32),
+        };
+    }
+
+    /// Based on kernel values, or the weights of linear kernels, computes the decision values and the label for this problem.
+    pub(crate) fn compute_label<F: AsFeatures<VecSimd<f32x8>>>(&self, fv: &mut FeatureVector<F>) {
+        match self.svm_type {
+            SVMType::CSvc | SVMType::NuSvc => {
+                self.compute_classification_values(fv);
+
+                // Compute the highest vote
+                let highest_vote = find_max_index(&fv.vote);
+                fv.result = Label::Class(self.classes[highest_vote].label);
+            }
+            SVMType::ESvr | SVMType::NuSvr | SVMType::OneClass => self.compute_regression_values(fv),
+        }
+    }
+
+    /// Same as [`DenseSVM::compute_label`], but also computes probability estimates like [`Predict::predict_probability`].
+    pub(crate) fn compute_label_probability<F: AsFeatures<VecSimd<f32x8>>>(&self, problem: &mut FeatureVector<F>) -> Result<(), Error> {
+        predict_probability_impl!(self, problem, self.compute_label(problem))
+    }
+
+    /// Computes the kernel values for a batch of problems.
+    ///
+    /// Support vectors are processed in blocks of about `BATCH_BLOCK_BYTES`, and each block is compared to
+    /// all problems before moving on, so support vectors are read from memory once per batch instead of
+    /// once per problem.
+    pub(crate) fn compute_kernel_values_batch(&self, problems: &mut [FeatureVector<VecSimd<f32x8>>]) {
+        let bytes_per_sv = self.num_attributes.div_ceil(f32x8::LANES) * size_of::<f32x8>();
+        let block = (BATCH_BLOCK_BYTES / bytes_per_sv.max(1)).max(1);
+
+        for (i, class) in self.classes.iter().enumerate() {
+            let num_sv = class.support_vectors.dimension().0;
+
+            for start in (0..num_sv).step_by(block) {
+                let rows = start..num_sv.min(start + block);
+
+                for problem in problems.iter_mut() {
+                    let kvalues = &mut problem.kernel_values.row_as_flat_mut(i)[rows.clone()];
+
+                    self.kernel.compute_range(&class.support_vectors, rows.clone(), &problem.features, kvalues);
+                }
+            }
+        }
+
+        for problem in problems {
+            problem.has_kernel_values = true;
+        }
+    }
+
+    /// Predicts the labels of many samples at once.
+    ///
+    /// Each row of `features` holds the features of one sample, just like [`FeatureVector::features`] would, and
+    /// the matrix must have the dimension `(out.len(), self.attributes())`. Once this method returns, `out[i]`
+    /// holds the label of row `i`, the same label [`Predict::predict_value`] would produce for it.
+    ///
+    /// Compared to calling [`Predict::predict_value`] in a loop this is faster for larger models, as samples
+    /// are processed in small batches that share the support vectors while they are in cache.
+    ///
+    /// ```
+    /// use ffsvm::{DenseSVM, Label};
+    /// use simd_aligned::{arch::f32x8, MatSimd, Rows};
+    /// # use ffsvm::SAMPLE_MODEL;
+    ///
+    /// # fn main() -> Result<(), ffsvm::Error> {
+    /// let svm = DenseSVM::try_from(SAMPLE_MODEL)?;
+    ///
+    /// let mut features = MatSimd::<f32x8, Rows>::with_dimension(100, svm.attributes());
+    /// let mut labels = vec![Label::None; 100];
+    ///
+    /// features.flat_mut()[(0, 0)] = 0.55838;
+    ///
+    /// svm.predict_values_batch(&features, &mut labels)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::BatchDimensionMismatch`] if `features` has the wrong dimension.
+    pub fn predict_values_batch(&self, features: &MatSimd<f32x8, Rows>, out: &mut [Label]) -> Result<(), Error> {
+        self.predict_batch(features, out, None)
+    }
+
+    /// Predicts the labels and probability estimates of many samples at once.
+    ///
+    /// Works like [`DenseSVM::predict_values_batch`], but produces the same results as [`Predict::predict_probability`].
+    /// Row `i` of `probabilities` receives the probability estimates of sample `i`, and must have one column per class.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::BatchDimensionMismatch`] if `features` or `probabilities` have the wrong dimension, and
+    /// can fail for the same reasons as [`Predict::predict_probability`].
+    pub fn predict_probabilities_batch(&self, features: &MatSimd<f32x8, Rows>, out: &mut [Label], probabilities: &mut MatSimd<f64x4, Rows>) -> Result<(), Error> {
+        let expected = (out.len(), self.classes.len());
+
+        if probabilities.dimension() != expected {
+            return Err(Error::BatchDimensionMismatch { expected, found: probabilities.dimension() });
+        }
+
+        self.predict_batch(features, out, Some(probabilities))
+    }
+
+    /// Common part of the batch predictions, computing probabilities if a matrix to store them is given.
+    fn predict_batch(&self, features: &MatSimd<f32x8, Rows>, out: &mut [Label], mut probabilities: Option<&mut MatSimd<f64x4, Rows>>) -> Result<(), Error> {
+        let expected = (out.len(), self.num_attributes);
+
+        if features.dimension() != expected {
+            return Err(Error::BatchDimensionMismatch { expected, found: features.dimension() });
+        }
+
+        let mut problems = vec![FeatureVector::from(self); BATCH_SAMPLES.min(out.len())];
+
+        for (batch, labels) in out.chunks_mut(BATCH_SAMPLES).enumerate() {
+            let first = batch * BATCH_SAMPLES;
+            let problems = &mut problems[..labels.len()];
+
+            for (i, problem) in problems.iter_mut().enumerate() {
+                problem.features.copy_from_slice(features.row(first + i));
+            }
+
+            // Linear kernels with weights don't need kernel values.
+            if self.weights.is_none() {
+                self.compute_kernel_values_batch(problems);
+            }
+
+            for (i, (problem, label)) in problems.iter_mut().zip(labels).enumerate() {
+                if let Some(probabilities) = probabilities.as_deref_mut() {
+                    self.compute_label_probability(problem)?;
+                    probabilities.row_as_flat_mut(first + i).copy_from_slice(problem.probabilities());
+                } else {
+                    self.compute_label(problem);
+                }
+
+                *label = problem.result;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// For linear kernels, computes the weight vector of each class pair from the support vectors and their coefficients.
+    pub(crate) fn linear_weights(&self) -> MatSimd<f32x8, Rows> {
+        let pairs = linear_weights_impl!(self, vec![0.0; self.num_attributes]);
+        let mut weights = MatSimd::with_dimension(pairs.len(), self.num_attributes);
+
+        for (row, w) in pairs.iter().enumerate() {
+            for (weight, value) in weights.row_as_flat_mut(row).iter_mut().zip(w) {
+                *weight = *value as f32;
+            }
+        }
+
+        weights
     }
 
     /// Returns number of attributes, reflecting the libSVM model.
@@ -146,33 +367,96 @@ impl DenseSVM {
     pub fn classes(&self) -> usize {
         self.classes.len()
     }
+
+    /// For ε-SVR and ν-SVR models with probability estimates, returns the scale `sigma` of the Laplace distribution
+    /// of the difference between target and predicted values, the `probA` of the model.
+    ///
+    /// Regression models have probability estimates if they were trained with `svm-train -b 1`, or calibrated with
+    /// [`DenseSVM::calibrate_probabilities`]. For all other models, and models whose `probA` is not positive, `None`
+    /// is returned.
+    #[must_use]
+    pub fn svr_sigma(&self) -> Option<f64> {
+        svr_sigma_impl!(self)
+    }
+
+    /// For ε-SVR and ν-SVR models with probability estimates, returns the distribution of the target value given the
+    /// predicted `value`, e.g., a [`FeatureVector::raw_value`], see [`Laplace`].
+    ///
+    /// Returns `None` if the model is no regression model, or has no probability estimates, see
+    /// [`DenseSVM::svr_sigma`].
+    #[must_use]
+    pub fn svr_distribution(&self, value: f64) -> Option<Laplace> {
+        self.svr_sigma().map(|sigma| Laplace::new(value, sigma))
+    }
+
+    /// Calibrates the probability estimates of a classifier or regression SVM on labeled samples, e.g., a held-out
+    /// validation set, so that [`Predict::predict_probability`] can be used on models trained without `-b 1`.
+    ///
+    /// All samples are predicted, then for each pair of classes a sigmoid is fitted to the decision values of the
+    /// samples of these two classes, with the same method libSVM's `svm-train -b 1` uses on cross-validation decision
+    /// values. Existing probability estimates are replaced. Samples are `(label, features)` as produced by a
+    /// [`DataReader`](crate::DataReader):
+    ///
+    /// ```
+    /// use ffsvm::{DataReader, DenseSVM, Error, Predict};
+    /// # use ffsvm::SAMPLE_MODEL;
+    ///
+    /// # fn main() -> Result<(), Error> {
+    /// # let validation = "42 0:0.55838 1:-0.157895 2:0.581292 3:-0.221184\n21 0:-0.4 1:-0.5 2:-0.3 3:-0.6\n";
+    /// let mut svm = DenseSVM::try_from(SAMPLE_MODEL)?;
+    /// let mut samples = DataReader::new(validation.as_bytes(), &svm).collect::<Result<Vec<_>, _>>()?;
+    ///
+    /// svm.calibrate_probabilities(&mut samples)?;
+    /// svm.predict_probability(&mut samples[0].1)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// Each class needs samples, and the more samples each class has, the better the estimates.
+    ///
+    /// For ε-SVR and ν-SVR models, `sigma` is estimated from the prediction errors of the samples instead, like
+    /// `svm-train -b 1` does, see [`DenseSVM::svr_sigma`]. Samples predicted without any error give no estimate.
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`Error::InvalidCalibrationData`] if there are no samples, a label is not a class of the SVM, a
+    /// class has no samples, a regression SVM predicts all samples exactly, or the SVM is a one-class SVM, and if a
+    /// feature vector doesn't fit the SVM.
+    pub fn calibrate_probabilities(&mut self, samples: &mut [(f64, FeatureVector<VecSimd<f32x8>>)]) -> Result<(), Error> {
+        self.probabilities = Some(fit_probabilities(self, self.svm_type, samples)?);
+        Ok(())
+    }
+}
+
+impl DenseSVM {
+    /// Predicts the value for one problem, of this SVM or an [`Svm`](crate::Svm) holding it.
+    pub(crate) fn predict_features<F: AsFeatures<VecSimd<f32x8>>>(&self, fv: &mut FeatureVector<F>) -> Result<(), Error> {
+        check_feature_vector_impl!(self, fv);
+
+        // Linear kernels with weights don't need kernel values.
+        if self.weights.is_none() {
+            self.compute_kernel_values(fv);
+        }
+
+        self.compute_label(fv);
+
+        Ok(())
+    }
+
+    /// Predicts the probabilities for one problem, of this SVM or an [`Svm`](crate::Svm) holding it.
+    pub(crate) fn predict_features_probability<F: AsFeatures<VecSimd<f32x8>>>(&self, problem: &mut FeatureVector<F>) -> Result<(), Error> {
+        predict_probability_impl!(self, problem, self.predict_features(problem)?)
+    }
 }
 
 impl Predict<VecSimd<f32x8>> for DenseSVM {
     // Predict the value for one problem.
     fn predict_value(&self, fv: &mut FeatureVector<VecSimd<f32x8>>) -> Result<(), Error> {
-        match self.svm_type {
-            SVMType::CSvc | SVMType::NuSvc => {
-                // Compute kernel, decision values and eventually the label
-                self.compute_kernel_values(fv);
-                self.compute_classification_values(fv);
-
-                // Compute the highest vote
-                let highest_vote = find_max_index(&fv.vote);
-                fv.result = Label::Class(self.classes[highest_vote].label);
-
-                Ok(())
-            }
-            SVMType::ESvr | SVMType::NuSvr => {
-                self.compute_kernel_values(fv);
-                self.compute_regression_values(fv);
-                Ok(())
-            }
-        }
+        self.predict_features(fv)
     }
 
     fn predict_probability(&self, problem: &mut FeatureVector<VecSimd<f32x8>>) -> Result<(), Error> {
-        predict_probability_impl!(self, problem)
+        self.predict_features_probability(problem)
     }
 }
 
@@ -188,8 +472,35 @@ impl<'a> TryFrom<&'a str> for DenseSVM {
 impl<'a> TryFrom<&'a ModelFile<'_>> for DenseSVM {
     type Error = Error;
 
+    /// Loads the model, inferring the number of attributes with [`AttributePolicy::Infer`].
     fn try_from(raw_model: &'a ModelFile<'_>) -> Result<Self, Error> {
-        let (mut svm, nr_sv) = prepare_svm!(raw_model, dyn KernelDense, MatSimd<f32x8, Rows>, Self);
+        Self::with_attribute_policy(raw_model, AttributePolicy::Infer)
+    }
+}
+
+impl DenseSVM {
+    /// Loads the model, determining the number of attributes according to `policy`.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the model is invalid, or its support vectors don't fit the number of attributes, see [`AttributePolicy`].
+    pub fn with_attribute_policy(raw_model: &ModelFile<'_>, policy: AttributePolicy) -> Result<Self, Error> {
+        let num_attributes = |raw_model: &ModelFile<'_>| match policy {
+            AttributePolicy::Infer => {
+                let found = raw_model.vectors().iter().filter_map(|x| x.features.last()).map(|x| x.index as usize + 1).max().unwrap_or_default();
+                let limit = attribute_limit(raw_model);
+
+                if found > limit {
+                    Err(Error::TooManyAttributes { limit, found })
+                } else {
+                    Ok(found)
+                }
+            }
+            AttributePolicy::Fixed(n) => Ok(n),
+            AttributePolicy::Strict => Ok(raw_model.vectors()[0].features.len()),
+        };
+
+        let (mut svm, nr_sv) = prepare_svm!(raw_model, dyn KernelDense, MatSimd<f32x8, Rows>, Self, num_attributes(raw_model)?);
 
         let vectors = &raw_model.vectors();
 
@@ -203,26 +514,47 @@ impl<'a> TryFrom<&'a ModelFile<'_>> for DenseSVM {
 
             // Set support vector and coefficients
             for (i_vector, vector) in vectors[start_offset..stop_offset].iter().enumerate() {
-                let mut last_attribute = None;
+                // Attributes must be strictly the successor of the previous one in strict mode, and a later one
+                // otherwise. This is checked for the whole vector before storing anything, so that the last
+                // attribute is also the largest one when checking the number of attributes below.
+                for pair in vector.features.windows(2) {
+                    let ordered = match policy {
+                        AttributePolicy::Strict => pair[1].index == pair[0].index + 1,
+                        AttributePolicy::Infer | AttributePolicy::Fixed(_) => pair[1].index > pair[0].index,
+                    };
+
+                    if !ordered {
+                        return Result::Err(Error::AttributesUnordered {
+                            index: pair[1].index,
+                            value: pair[1].value,
+                            last_index: pair[0].index,
+                        });
+                    }
+                }
+
+                // In strict mode the attributes are stored by position, otherwise by index
+                let found = match policy {
+                    AttributePolicy::Strict => vector.features.len(),
+                    AttributePolicy::Infer | AttributePolicy::Fixed(_) => vector.features.last().map_or(0, |x| x.index as usize + 1),
+                };
+
+                if found > svm.num_attributes {
+                    return Err(Error::AttributeCountMismatch {
+                        sv_index: start_offset + i_vector,
+                        expected: svm.num_attributes,
+                        found,
+                    });
+                }
 
                 // Set support vectors
                 for (i_attribute, attribute) in vector.features.iter().enumerate() {
-                    if let Some(last) = last_attribute {
-                        // In case we have seen an attribute already, this one must be strictly
-                        // the successor attribute
-                        if attribute.index != last + 1 {
-                            return Result::Err(Error::AttributesUnordered {
-                                index: attribute.index,
-                                value: attribute.value,
-                                last_index: last,
-                            });
-                        }
+                    let position = match policy {
+                        AttributePolicy::Strict => i_attribute,
+                        AttributePolicy::Infer | AttributePolicy::Fixed(_) => attribute.index as usize,
                     };
 
                     let mut support_vectors = svm.classes[i].support_vectors.flat_mut();
-                    support_vectors[(i_vector, i_attribute)] = attribute.value;
-
-                    last_attribute = Some(attribute.index);
+                    support_vectors[(i_vector, position)] = attribute.value;
                 }
 
                 // Set coefficients
@@ -236,11 +568,35 @@ impl<'a> TryFrom<&'a ModelFile<'_>> for DenseSVM {
             start_offset = stop_offset;
         }
 
+        // Linear models don't need their support vectors for predictions
+        if raw_model.header().kernel_type == "linear" {
+            svm.weights = Some(svm.linear_weights());
+        }
+
         // Return what we have
         Ok(svm)
     }
 }
 
+impl From<&DenseSVM> for ModelFile<'static> {
+    /// Converts the SVM back into a model, which can be written in libSVM's format via [`ToString`].
+    fn from(svm: &DenseSVM) -> Self {
+        model_file_impl!(
+            svm,
+            |class: &Class<MatSimd<f32x8, Rows>>, i_vector| {
+                class
+                    .support_vectors
+                    .row_as_flat(i_vector)
+                    .iter()
+                    .enumerate()
+                    .map(|(index, value)| Attribute { value: *value, index: index as u32 })
+                    .collect()
+            },
+            kernel
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::*;
@@ -255,4 +611,66 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn linear_weights_match_kernel() -> Result<(), Error> {
+        let models = [
+            include_str!("../../../tests/data_dense/m_csvm_linear_prob.libsvm"),
+            include_str!("../../../tests/data_dense/m_nusvm_linear.libsvm"),
+            include_str!("../../../tests/data_dense/m_e_svr_linear.libsvm"),
+            include_str!("../../../tests/data_dense/m_one_class_linear.libsvm"),
+        ];
+
+        for model in models {
+            let mut svm = DenseSVM::try_from(model)?;
+            let mut fast = FeatureVector::from(&svm);
+            let mut slow = FeatureVector::from(&svm);
+
+            fast.features().clone_from_slice(&[1.287_784_9, 0.986_031_7, 1.486_247_2, 1.128_083, 0.891_030_55, 1.164_363_4, 0.928_599_1, 1.140_762_9]);
+            slow.features().clone_from_slice(fast.features());
+
+            assert!(svm.weights.is_some());
+            svm.predict_value(&mut fast)?;
+
+            svm.weights = None;
+            svm.predict_value(&mut slow)?;
+
+            assert!((fast.raw_value - slow.raw_value).abs() <= 1e-4 * slow.raw_value.abs().max(1.0));
+
+            for (a, b) in fast.decision_values.data.iter().zip(&slow.decision_values.data) {
+                assert!((a - b).abs() <= 1e-4 * b.abs().max(1.0));
+            }
+
+            if let Label::Class(_) = slow.label() {
+                assert_eq!(fast.label(), slow.label());
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "rayon")]
+    fn parallel_kernel_values_match_serial() -> Result<(), Error> {
+        use crate::svm::parallel::compute_chunks;
+
+        let svm = DenseSVM::try_from(include_str!("../../../tests/data_misc/model_large.libsvm"))?;
+        let mut problem = FeatureVector::from(&svm);
+        problem.features().iter_mut().enumerate().for_each(|(i, x)| *x = 0.25 * i as f32);
+
+        for class in &svm.classes {
+            let num_sv = class.support_vectors.dimension().0;
+            let mut serial = vec![0.0; num_sv];
+            let mut parallel = vec![0.0; num_sv];
+
+            svm.kernel.compute(&class.support_vectors, &problem.features, &mut serial);
+            compute_chunks(&mut parallel, num_sv, 7, |range, output| {
+                svm.kernel.compute_range(&class.support_vectors, range, &problem.features, output);
+            });
+
+            assert_eq!(serial, parallel);
+        }
+
+        Ok(())
+    }
 }
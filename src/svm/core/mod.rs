@@ -1,43 +1,62 @@
 macro_rules! prepare_svm {
+    // SVMs evaluating kernel functions on their own, i.e., the `DenseSVM` and `SparseSVM`, with the
+    // number of attributes given by the first support vector.
     ($raw_model:expr, $k:ty, $m32:ty, $svm:tt) => {
+        prepare_svm!($raw_model, $k, $m32, $svm, $raw_model.vectors()[0].features.len())
+    };
+
+    // Same, with the number of attributes given by the caller.
+    ($raw_model:expr, $k:ty, $m32:ty, $svm:tt, $num_attributes:expr) => {{
+        let svm_type = validate($raw_model)?;
+
+        let kernel: Box<$k> = match $raw_model.header().kernel_type {
+            "rbf" => Box::new(Rbf::try_from($raw_model)?),
+            "linear" => Box::new(Linear::from($raw_model)),
+            "polynomial" => Box::new(Poly::try_from($raw_model)?),
+            "sigmoid" => Box::new(Sigmoid::try_from($raw_model)?),
+            kernel_type => return Err(Error::UnsupportedKernel(kernel_type.to_owned())),
+        };
+
+        prepare_svm!(@build $raw_model, $m32, $svm, svm_type, $num_attributes, kernel)
+    }};
+
+    // SVMs being handed kernel values by the caller, i.e., the `PrecomputedSVM`.
+    ($raw_model:expr, $m32:ty, $svm:tt) => {{
+        let svm_type = validate($raw_model)?;
+
+        match $raw_model.header().kernel_type {
+            "precomputed" => {}
+            kernel_type => return Err(Error::UnsupportedKernel(kernel_type.to_owned())),
+        };
+
+        prepare_svm!(@build $raw_model, $m32, $svm, svm_type, $raw_model.vectors()[0].features.len(),)
+    }};
+
+    // Common part, expects the model to be validated.
+    (@build $raw_model:expr, $m32:ty, $svm:tt, $svm_type:ident, $num_attributes:expr, $($kernel:ident)?) => {
         // To quickly check what broke again during parsing ...
         // println!("{:?}", raw_model);
         {
             let header = &$raw_model.header();
-            let vectors = &$raw_model.vectors();
 
             // Get basic info
-            let num_attributes = vectors[0].features.len();
+            let num_attributes = $num_attributes;
             let num_total_sv = header.total_sv as usize;
 
-            let svm_type = match $raw_model.header().svm_type {
-                "c_svc" => SVMType::CSvc,
-                "nu_svc" => SVMType::NuSvc,
-                "epsilon_svr" => SVMType::ESvr,
-                "nu_svr" => SVMType::NuSvr,
-                _ => unimplemented!(),
-            };
-
-            let kernel: Box<$k> = match $raw_model.header().kernel_type {
-                "rbf" => Box::new(Rbf::try_from($raw_model)?),
-                "linear" => Box::new(Linear::from($raw_model)),
-                "polynomial" => Box::new(Poly::try_from($raw_model)?),
-                "sigmoid" => Box::new(Sigmoid::try_from($raw_model)?),
-                _ => unimplemented!(),
-            };
+            let svm_type = $svm_type;
 
             let num_classes = match svm_type {
                 SVMType::CSvc | SVMType::NuSvc => header.nr_class as usize,
-                // For SVRs we set number of classes to 1, since that resonates better
-                // with our internal handling
-                SVMType::ESvr | SVMType::NuSvr => 1,
+                // For SVRs and one-class SVMs we set number of classes to 1, since that
+                // resonates better with our internal handling
+                SVMType::ESvr | SVMType::NuSvr | SVMType::OneClass => 1,
             };
 
             let nr_sv = match svm_type {
                 SVMType::CSvc | SVMType::NuSvc => header.nr_sv.clone(),
-                // For SVRs we set number of classes to 1, since that resonates better
-                // with our internal handling
-                SVMType::ESvr | SVMType::NuSvr => vec![num_total_sv as u32],
+                // For SVRs and one-class SVMs we set number of classes to 1, since that
+                // resonates better with our internal handling
+                SVMType::ESvr | SVMType::NuSvr | SVMType::OneClass => vec![num_total_sv as u32],
             };
 
             // Construct vector of classes
@@ -50,7 +69,7 @@ macro_rules! prepare_svm {
                         Class::<$m32>::with_parameters(num_classes, num_sv, num_attributes, label)
                     })
                     .collect::<Vec<Class<$m32>>>(),
-                SVMType::ESvr | SVMType::NuSvr => vec![Class::<$m32>::with_parameters(2, num_total_sv, num_attributes, 0)],
+                SVMType::ESvr | SVMType::NuSvr | SVMType::OneClass => vec![Class::<$m32>::with_parameters(2, num_total_sv, num_attributes, 0)],
             };
 
             let probabilities = match (&$raw_model.header().prob_a, &$raw_model.header().prob_b) {
@@ -74,7 +93,8 @@ macro_rules! prepare_svm {
                     num_total_sv,
                     num_attributes,
                     probabilities,
-                    kernel,
+                    // Only SVMs with their own kernel can have weights, these are set once the model is loaded.
+                    $($kernel, weights: None,)?
                     svm_type,
                     rho: Triangular::from(&header.rho),
                     classes,
@@ -85,6 +105,30 @@ macro_rules! prepare_svm {
     };
 }
 
+macro_rules! check_feature_vector_impl {
+    // Makes sure the feature vector was created for an SVM of the same shape, otherwise its buffers don't fit.
+    ($self:tt, $problem:tt) => {{
+        if $problem.num_attributes != $self.num_attributes {
+            return Err(Error::FeatureVectorMismatch("num_attributes"));
+        }
+
+        if $problem.num_total_sv != $self.num_total_sv {
+            return Err(Error::FeatureVectorMismatch("total_sv"));
+        }
+
+        let same_classes = $problem.classes.len() == $self.classes.len()
+            && $self
+                .classes
+                .iter()
+                .zip(&$problem.classes)
+                .all(|(class, (label, num_sv))| class.label == *label && class.num_support_vectors() == *num_sv);
+
+        if !same_classes {
+            return Err(Error::FeatureVectorMismatch("classes"));
+        }
+    }};
+}
+
 macro_rules! compute_multiclass_probabilities_impl {
     ($self:tt, $problem:tt) => {{
         let num_classes = $self.classes.len();
@@ -212,8 +256,63 @@ macro_rules! compute_classification_values_impl {
     }};
 }
 
+macro_rules! compute_linear_classification_values_impl {
+    ($self:tt, $problem:tt, $weights:expr) => {{
+        set_all(&mut $problem.vote, 0);
+        $problem.has_kernel_values = false;
+
+        // For linear kernels the decision value of a class pair (i, j) is `w·x - rho`. Instead of
+        // computing kernel values for all support vectors we compute a single dot product per
+        // pair, straight into the decision values, as the weights are stored in the same order.
+        $self.kernel.compute($weights, $problem.features.as_features(), &mut $problem.decision_values.data);
+
+        for i in 0..$self.classes.len() {
+            for j in (i + 1)..$self.classes.len() {
+                let sum = $problem.decision_values[(i, j)] - $self.rho[(i, j)];
+                let index_to_vote = if sum > 0.0 { i } else { j };
+
+                $problem.decision_values[(i, j)] = sum;
+                $problem.vote[index_to_vote] += 1;
+            }
+        }
+    }};
+}
+
+macro_rules! linear_weights_impl {
+    // Sums up the weighted support vectors of each class pair, starting from `$zero`, in the order of `rho`.
+    ($self:tt, $zero:expr) => {{
+        let mut weights = Vec::with_capacity($self.rho.data.len());
+
+        match $self.svm_type {
+            SVMType::CSvc | SVMType::NuSvc => {
+                // Coefficients are combined just like in `compute_classification_values_impl`.
+                for i in 0..$self.classes.len() {
+                    for j in (i + 1)..$self.classes.len() {
+                        let mut w = $zero;
+                        $self.classes[i].add_weighted(&mut w, j - 1);
+                        $self.classes[j].add_weighted(&mut w, i);
+                        weights.push(w);
+                    }
+                }
+            }
+            SVMType::ESvr | SVMType::NuSvr | SVMType::OneClass => {
+                let mut w = $zero;
+                $self.classes[0].add_weighted(&mut w, 0);
+                weights.push(w);
+            }
+        }
+
+        weights
+    }};
+}
+
 macro_rules! predict_probability_impl {
-    ($self:tt, $problem:tt) => {{
+    ($self:tt, $problem:tt) => {
+        predict_probability_impl!($self, $problem, $self.predict_value($problem)?)
+    };
+
+    // `$predict_value` computes the decision values and the label of the problem, e.g., when kernel values are already known.
+    ($self:tt, $problem:tt, $predict_value:expr) => {{
         match $self.svm_type {
             SVMType::CSvc | SVMType::NuSvc => {
                 const MIN_PROB: f64 = 1e-7;
@@ -227,7 +326,7 @@ macro_rules! predict_probability_impl {
                 let probabilities = $self.probabilities.as_ref().unwrap();
 
                 // First we need to predict the problem for our decision values
-                $self.predict_value($problem)?;
+                $predict_value;
 
                 let mut pairwise = $problem.pairwise.flat_mut();
 
@@ -260,11 +359,82 @@ macro_rules! predict_probability_impl {
                 Ok(())
             }
             // This fallback behavior is mandated by `libSVM`.
-            SVMType::ESvr | SVMType::NuSvr => $self.predict_value($problem),
+            SVMType::ESvr | SVMType::NuSvr | SVMType::OneClass => {
+                $predict_value;
+                Ok(())
+            }
         }
     }};
 }
 
+macro_rules! svr_sigma_impl {
+    // For SVRs, `probA` holds the scale of the Laplace distribution of the prediction error, which must be positive.
+    ($self:tt) => {
+        match ($self.svm_type, &$self.probabilities) {
+            (SVMType::ESvr | SVMType::NuSvr, Some(probabilities)) => probabilities.a.data.first().copied().filter(|sigma| *sigma > 0.0),
+            _ => None,
+        }
+    };
+}
+
+macro_rules! model_file_impl {
+    // Converts an SVM back into a `ModelFile`. The closure `$attributes` produces the attributes of the
+    // `i_vector`-th support vector of a class, `$kernel` writes the kernel parameters if the SVM has any.
+    ($self:tt, $attributes:expr, $($kernel:ident)?) => {{
+        let is_classifier = matches!($self.svm_type, SVMType::CSvc | SVMType::NuSvc);
+
+        let mut header = Header {
+            svm_type: $self.svm_type.as_str(),
+            kernel_type: "precomputed",
+            // Same as `libSVM`, regression and one-class models claim to have two classes.
+            nr_class: if is_classifier { $self.classes.len() as u32 } else { 2 },
+            total_sv: $self.num_total_sv as u32,
+            rho: $self.rho.data.clone(),
+            ..Header::default()
+        };
+
+        $($self.$kernel.write_header(&mut header);)?
+
+        // Labels and support vector counts are only written for classifiers.
+        if is_classifier {
+            header.label = $self.classes.iter().map(|class| class.label).collect();
+            header.nr_sv = $self.classes.iter().map(|class| class.coefficients.dimension().1 as u32).collect();
+        }
+
+        if let Some(probabilities) = &$self.probabilities {
+            header.prob_a = Some(probabilities.a.data.clone());
+
+            // For SVRs only one probability array is given
+            if !probabilities.b.data.is_empty() {
+                header.prob_b = Some(probabilities.b.data.clone());
+            }
+        }
+
+        let mut vectors = Vec::with_capacity($self.num_total_sv);
+
+        // Support vectors are grouped by class, in the same order as the labels.
+        for class in &$self.classes {
+            let coefficients = class.coefficients.flat();
+            let (num_coefficients, num_sv) = class.coefficients.dimension();
+
+            for i_vector in 0..num_sv {
+                vectors.push(SupportVector {
+                    coefs: (0..num_coefficients).map(|i_coefficient| coefficients[(i_coefficient, i_vector)] as f32).collect(),
+                    features: $attributes(class, i_vector),
+                });
+            }
+        }
+
+        ModelFile::new(header, vectors)
+    }};
+}
+
 // We do late include here to capture our macros above ...
+pub mod binary;
 pub mod dense;
+pub mod precomputed;
 pub mod sparse;
+pub mod unified;
+pub mod validate;
+#[cfg(target_endian = "little")]
+pub mod view;
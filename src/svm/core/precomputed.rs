@@ -0,0 +1,296 @@
+use simd_aligned::traits::Simd;
+use std::convert::TryFrom;
+
+use crate::{
+    errors::Error,
+    parser::{Attribute, Header, ModelFile, SupportVector},
+    svm::{
+        calibrate::fit_probabilities,
+        class::Class,
+        features::{FeatureVector, Label},
+        laplace::Laplace,
+        predict::Predict,
+        core::validate::{kernel_row_limit, validate},
+        Probabilities, SVMType,
+    },
+    util::{find_max_index, set_all, sigmoid_predict},
+    vectors::Triangular,
+};
+
+/// Serial numbers are read as `f32`, which can't tell whole numbers from this one on apart from their neighbors, e.g.,
+/// `16777217` reads as `16777216`.
+const MAX_EXACT_SERIAL: f32 = 16_777_216.0;
+
+/// An SVM for models trained with a precomputed kernel (`svm-train -t 4`).
+///
+/// Instead of features, each [`FeatureVector`] holds the kernel row of the instance to classify, i.e.,
+/// the values `K(x, x_i)` for the training instances `x_i` the model was trained with. The SVM then
+/// performs the decision function, voting and probability estimation.
+///
+/// # Creating an SVM
+///
+/// This SVM can be created by passing a [`ModelFile`](crate::ModelFile) or [`&str`] into [`ModelFile::try_from`]:
+///
+/// ```
+/// use ffsvm::PrecomputedSVM;
+///
+/// let svm = PrecomputedSVM::try_from("...");
+/// ```
+pub struct PrecomputedSVM {
+    /// Total number of support vectors
+    pub(crate) num_total_sv: usize,
+
+    /// Length of a kernel row, which is the highest serial number referenced by any support vector plus one.
+    pub(crate) num_attributes: usize,
+
+    pub(crate) rho: Triangular<f64>,
+
+    pub(crate) probabilities: Option<Probabilities>,
+
+    pub(crate) svm_type: SVMType,
+
+    /// All classes
+    pub(crate) classes: Vec<Class<Vec<u32>>>,
+}
+
+impl PrecomputedSVM {
+    /// Finds the class index for a given label.
+    ///
+    /// # Description
+    ///
+    /// This method takes a `label` as defined in the libSVM training model
+    /// and returns the internal `index` where this label resides. The index
+    /// equals [`FeatureVector::probabilities`] index where that label's
+    /// probability can be found.
+    ///
+    /// # Returns
+    ///
+    /// If the label was found its index returned in the [`Option`], otherwise `None`
+    /// is returned.
+    #[must_use]
+    pub fn class_index_for_label(&self, label: i32) -> Option<usize> {
+        for (i, class) in self.classes.iter().enumerate() {
+            if class.label != label {
+                continue;
+            }
+
+            return Some(i);
+        }
+
+        None
+    }
+
+    /// Returns the class label for a given index.
+    ///
+    /// # Description
+    ///
+    /// The inverse of [`PrecomputedSVM::class_index_for_label`], this function returns the class label
+    /// associated with a certain internal index. The index equals the [`FeatureVector::probabilities`]
+    /// index where a label's probability can be found.
+    ///
+    /// # Returns
+    ///
+    /// If the index was found it is returned in the [`Option`], otherwise `None`
+    /// is returned.
+    #[must_use]
+    pub fn class_label_for_index(&self, index: usize) -> Option<i32> {
+        if index >= self.classes.len() {
+            None
+        } else {
+            Some(self.classes[index].label)
+        }
+    }
+
+    /// Gathers the kernel values of all support vectors from the problem's kernel row.
+    pub(crate) fn compute_kernel_values(&self, problem: &mut FeatureVector<Vec<f64>>) {
+        // Get current problem and decision values array
+        let features = &problem.features;
+        let kernel_values = &mut problem.kernel_values;
+
+        // Look up kernel values per class
+        for (i, class) in self.classes.iter().enumerate() {
+            let kvalues = kernel_values.row_as_flat_mut(i);
+
+            for (kvalue, serial) in kvalues.iter_mut().zip(&class.support_vectors) {
+                *kvalue = features[*serial as usize];
+            }
+        }
+
+        problem.has_kernel_values = true;
+    }
+
+    // This is pretty much copy-paste of `multiclass_probability` from libSVM which we need
+    // to be compatibly for predicting probability for multiclass SVMs. The method is in turn
+    // based on Method 2 from the paper "Probability Estimates for Multi-class
+    // Classification by Pairwise Coupling", Journal of Machine Learning Research 5 (2004) 975-1005,
+    // by Ting-Fan Wu, Chih-Jen Lin and Ruby C. Weng.
+    pub(crate) fn compute_multiclass_probabilities(&self, problem: &mut FeatureVector<Vec<f64>>) -> Result<(), Error> {
+        compute_multiclass_probabilities_impl!(self, problem)
+    }
+
+    /// Based on kernel values, computes the decision values for this problem.
+    pub(crate) fn compute_classification_values(&self, problem: &mut FeatureVector<Vec<f64>>) {
+        compute_classification_values_impl!(self, problem);
+    }
+
+    /// Based on kernel values, computes the single decision value of regression and one-class SVMs.
+    pub(crate) fn compute_regression_values(&self, problem: &mut FeatureVector<Vec<f64>>) {
+        let class = &self.classes[0];
+        let coef = class.coefficients.row(0);
+        let kvalues = problem.kernel_values.row(0);
+
+        let mut sum = coef.iter().zip(kvalues).map(|(a, b)| (*a * *b).sum()).sum::<f64>();
+
+        sum -= self.rho[0];
+
+        problem.raw_value = sum;
+        problem.result = match self.svm_type {
+            // Same as `libSVM`, one-class SVMs report inliers as `+1` and outliers as `-1`.
+            SVMType::OneClass => Label::Class(if sum > 0.0 { 1 } else { -1 }),
+            _ => Label::Value(sum as f32),
+        };
+    }
+
+    /// Returns the length of the kernel row, i.e., the highest training serial number referenced by the model plus one.
+    #[must_use]
+    pub const fn attributes(&self) -> usize {
+        self.num_attributes
+    }
+
+    /// Returns number of classes, reflecting the libSVM model.
+    #[must_use]
+    pub fn classes(&self) -> usize {
+        self.classes.len()
+    }
+
+    /// For ε-SVR and ν-SVR models with probability estimates, returns the scale `sigma` of the Laplace distribution
+    /// of the difference between target and predicted values, see [`DenseSVM::svr_sigma`](crate::DenseSVM::svr_sigma).
+    #[must_use]
+    pub fn svr_sigma(&self) -> Option<f64> {
+        svr_sigma_impl!(self)
+    }
+
+    /// For ε-SVR and ν-SVR models with probability estimates, returns the distribution of the target value given the
+    /// predicted `value`, see [`DenseSVM::svr_distribution`](crate::DenseSVM::svr_distribution).
+    #[must_use]
+    pub fn svr_distribution(&self, value: f64) -> Option<Laplace> {
+        self.svr_sigma().map(|sigma| Laplace::new(value, sigma))
+    }
+
+    /// Calibrates the probability estimates of a classifier or regression SVM on labeled samples, e.g., a held-out
+    /// validation set, see [`DenseSVM::calibrate_probabilities`](crate::DenseSVM::calibrate_probabilities).
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`Error::InvalidCalibrationData`] if there are no samples, a label is not a class of the SVM, a
+    /// class has no samples, a regression SVM predicts all samples exactly, or the SVM is a one-class SVM, and if a
+    /// feature vector doesn't fit the SVM.
+    pub fn calibrate_probabilities(&mut self, samples: &mut [(f64, FeatureVector<Vec<f64>>)]) -> Result<(), Error> {
+        self.probabilities = Some(fit_probabilities(self, self.svm_type, samples)?);
+        Ok(())
+    }
+}
+
+impl Predict<Vec<f64>> for PrecomputedSVM {
+    // Predict the value for one problem.
+    fn predict_value(&self, problem: &mut FeatureVector<Vec<f64>>) -> Result<(), Error> {
+        check_feature_vector_impl!(self, problem);
+
+        match self.svm_type {
+            SVMType::CSvc | SVMType::NuSvc => {
+                // Look up kernel, compute decision values and eventually the label
+                self.compute_kernel_values(problem);
+                self.compute_classification_values(problem);
+
+                // Compute highest vote
+                let highest_vote = find_max_index(&problem.vote);
+                problem.result = Label::Class(self.classes[highest_vote].label);
+
+                Ok(())
+            }
+            SVMType::ESvr | SVMType::NuSvr | SVMType::OneClass => {
+                self.compute_kernel_values(problem);
+                self.compute_regression_values(problem);
+                Ok(())
+            }
+        }
+    }
+
+    fn predict_probability(&self, problem: &mut FeatureVector<Vec<f64>>) -> Result<(), Error> {
+        predict_probability_impl!(self, problem)
+    }
+}
+
+impl<'a> TryFrom<&'a str> for PrecomputedSVM {
+    type Error = Error;
+
+    fn try_from(input: &'a str) -> Result<Self, Error> {
+        let raw_model = ModelFile::try_from(input)?;
+        Self::try_from(&raw_model)
+    }
+}
+
+impl<'a> TryFrom<&'a ModelFile<'_>> for PrecomputedSVM {
+    type Error = Error;
+
+    #[allow(clippy::cast_sign_loss)]
+    fn try_from(raw_model: &'a ModelFile<'_>) -> Result<Self, Error> {
+        let (mut svm, nr_sv) = prepare_svm!(raw_model, Vec<u32>, Self);
+
+        let vectors = &raw_model.vectors();
+
+        // Things down here are a bit ugly as the file format is a bit ugly ...
+        // Now read all vectors and decode stored information
+        let mut start_offset = 0;
+        let mut max_serial = 0;
+
+        // In the raw file, support vectors are grouped by class
+        for (i, num_sv_per_class) in nr_sv.iter().enumerate() {
+            let stop_offset = start_offset + *num_sv_per_class as usize;
+
+            // Set support vector and coefficients
+            for (i_vector, vector) in vectors[start_offset..stop_offset].iter().enumerate() {
+                // Support vectors only reference their training instance via `0:serial_number`
+                let serial = match vector.features.as_slice() {
+                    [attribute] if attribute.index == 0 && (1.0..MAX_EXACT_SERIAL).contains(&attribute.value) && attribute.value.fract() == 0.0 => attribute.value as u32,
+                    _ => return Err(Error::InvalidSerialNumber { sv_index: start_offset + i_vector }),
+                };
+
+                svm.classes[i].support_vectors[i_vector] = serial;
+                max_serial = max_serial.max(serial);
+
+                // Set coefficients
+                for (i_coefficient, coefficient) in vector.coefs.iter().enumerate() {
+                    let mut coefficients = svm.classes[i].coefficients.flat_mut();
+                    coefficients[(i_coefficient, i_vector)] = f64::from(*coefficient);
+                }
+            }
+
+            // Update last offset.
+            start_offset = stop_offset;
+        }
+
+        let limit = kernel_row_limit(raw_model);
+        svm.num_attributes = max_serial as usize + 1;
+
+        if svm.num_attributes > limit {
+            return Err(Error::TooManyAttributes { limit, found: svm.num_attributes });
+        }
+
+        // Return what we have
+        Ok(svm)
+    }
+}
+
+impl From<&PrecomputedSVM> for ModelFile<'static> {
+    /// Converts the SVM back into a model, which can be written in libSVM's format via [`ToString`].
+    fn from(svm: &PrecomputedSVM) -> Self {
+        model_file_impl!(
+            svm,
+            |class: &Class<Vec<u32>>, i_vector: usize| vec![Attribute {
+                value: class.support_vectors[i_vector] as f32,
+                index: 0,
+            }],
+        )
+    }
+}
@@ -1,22 +1,28 @@
 use crate::sparse::{SparseMatrix, SparseVector};
 
 use simd_aligned::traits::Simd;
-use std::convert::TryFrom;
+use std::{collections::BTreeMap, convert::TryFrom};
 
 use crate::{
     errors::Error,
-    parser::ModelFile,
+    parser::{Attribute, Header, ModelFile, SupportVector},
     svm::{
+        calibrate::fit_probabilities,
         class::Class,
-        features::{FeatureVector, Label},
+        features::{AsFeatures, FeatureVector, Label},
         kernel::{KernelSparse, Linear, Poly, Rbf, Sigmoid},
+        laplace::Laplace,
         predict::Predict,
+        core::validate::validate,
         Probabilities, SVMType,
     },
     util::{find_max_index, set_all, sigmoid_predict},
     vectors::Triangular,
 };
 
+#[cfg(feature = "rayon")]
+use crate::svm::parallel::{compute_chunks, rows_per_chunk};
+
 /// an SVM optimized for large models with many empty attributes.
 ///
 /// # Creating an SVM
@@ -47,6 +53,10 @@ pub struct SparseSVM {
 
     /// All classes
     pub(crate) classes: Vec<Class<SparseMatrix<f32>>>,
+
+    /// For linear kernels, the weight vector `w` of each class pair in the order of `rho`, so that a
+    /// decision value is `w·x - rho`.
+    pub(crate) weights: Option<SparseMatrix<f32>>,
 }
 
 impl SparseSVM {
@@ -98,17 +108,28 @@ impl SparseSVM {
     }
 
     /// Computes the kernel values for this problem
-    pub(crate) fn compute_kernel_values(&self, problem: &mut FeatureVector<SparseVector<f32>>) {
+    pub(crate) fn compute_kernel_values<F: AsFeatures<SparseVector<f32>>>(&self, problem: &mut FeatureVector<F>) {
         // Get current problem and decision values array
-        let features = &problem.features;
+        let features = problem.features.as_features();
         let kernel_values = &mut problem.kernel_values;
 
         // Compute kernel values per class
         for (i, class) in self.classes.iter().enumerate() {
             let kvalues = kernel_values.row_as_flat_mut(i);
 
+            // Classes with many support vectors are split into chunks computed in parallel.
+            #[cfg(feature = "rayon")]
+            if let Some(rows) = rows_per_chunk(class.coefficients.dimension().1, self.num_attributes) {
+                compute_chunks(kvalues, class.coefficients.dimension().1, rows, |range, output| {
+                    self.kernel.compute_range(&class.support_vectors, range, features, output);
+                });
+                continue;
+            }
+
             self.kernel.compute(&class.support_vectors, features, kvalues);
         }
+
+        problem.has_kernel_values = true;
     }
 
     // This is pretty much copy-paste of `multiclass_probability` from libSVM which we need
@@ -116,26 +137,57 @@ impl SparseSVM {
     // based on Method 2 from the paper "Probability Estimates for Multi-class
     // Classification by Pairwise Coupling", Journal of Machine Learning Research 5 (2004) 975-1005,
     // by Ting-Fan Wu, Chih-Jen Lin and Ruby C. Weng.
-    pub(crate) fn compute_multiclass_probabilities(&self, problem: &mut FeatureVector<SparseVector<f32>>) -> Result<(), Error> {
+    pub(crate) fn compute_multiclass_probabilities<F>(&self, problem: &mut FeatureVector<F>) -> Result<(), Error> {
         compute_multiclass_probabilities_impl!(self, problem)
     }
 
-    /// Based on kernel values, computes the decision values for this problem.
-    pub(crate) fn compute_classification_values(&self, problem: &mut FeatureVector<SparseVector<f32>>) {
-        compute_classification_values_impl!(self, problem);
+    /// Based on kernel values, or the weights of linear kernels, computes the decision values for this problem.
+    pub(crate) fn compute_classification_values<F: AsFeatures<SparseVector<f32>>>(&self, problem: &mut FeatureVector<F>) {
+        if let Some(weights) = &self.weights {
+            compute_linear_classification_values_impl!(self, problem, weights);
+        } else {
+            compute_classification_values_impl!(self, problem);
+        }
     }
 
-    /// Based on kernel values, computes the decision values for this problem.
-    pub(crate) fn compute_regression_values(&self, problem: &mut FeatureVector<SparseVector<f32>>) {
-        let class = &self.classes[0];
-        let coef = class.coefficients.row(0);
-        let kvalues = problem.kernel_values.row(0);
+    /// Based on kernel values, or the weights of linear kernels, computes the single decision value of regression and one-class SVMs.
+    pub(crate) fn compute_regression_values<F: AsFeatures<SparseVector<f32>>>(&self, problem: &mut FeatureVector<F>) {
+        // For linear kernels `w·x` replaces the weighted sum over all support vectors
+        let mut sum = if let Some(weights) = &self.weights {
+            let mut dot = [0.0];
+            problem.has_kernel_values = false;
+            self.kernel.compute(weights, problem.features.as_features(), &mut dot);
+            dot[0]
+        } else {
+            let class = &self.classes[0];
+            let coef = class.coefficients.row(0);
+            let kvalues = problem.kernel_values.row(0);
 
-        let mut sum = coef.iter().zip(kvalues).map(|(a, b)| (*a * *b).sum()).sum::<f64>();
+            coef.iter().zip(kvalues).map(|(a, b)| (*a * *b).sum()).sum::<f64>()
+        };
 
         sum -= self.rho[0];
 
-        problem.result = Label::Value(sum as f32);
+        problem.raw_value = sum;
+        problem.result = match self.svm_type {
+            // Same as `libSVM`, one-class SVMs report inliers as `+1` and outliers as `-1`.
+            SVMType::OneClass => Label::Class(if sum > 0.0 { 1 } else { -1 }),
+            _ => Label::Value(sum as f32),
+        };
+    }
+
+    /// For linear kernels, computes the weight vector of each class pair from the support vectors and their coefficients.
+    pub(crate) fn linear_weights(&self) -> SparseMatrix<f32> {
+        let pairs = linear_weights_impl!(self, BTreeMap::new());
+        let mut weights = SparseMatrix::with(pairs.len());
+
+        for (row, w) in pairs.iter().enumerate() {
+            for (index, value) in w {
+                weights[(row, *index as usize)] = *value as f32;
+            }
+        }
+
+        weights
     }
 
     /// Returns number of attributes, reflecting the libSVM model.
@@ -149,15 +201,47 @@ impl SparseSVM {
     pub fn classes(&self) -> usize {
         self.classes.len()
     }
+
+    /// For ε-SVR and ν-SVR models with probability estimates, returns the scale `sigma` of the Laplace distribution
+    /// of the difference between target and predicted values, see [`DenseSVM::svr_sigma`](crate::DenseSVM::svr_sigma).
+    #[must_use]
+    pub fn svr_sigma(&self) -> Option<f64> {
+        svr_sigma_impl!(self)
+    }
+
+    /// For ε-SVR and ν-SVR models with probability estimates, returns the distribution of the target value given the
+    /// predicted `value`, see [`DenseSVM::svr_distribution`](crate::DenseSVM::svr_distribution).
+    #[must_use]
+    pub fn svr_distribution(&self, value: f64) -> Option<Laplace> {
+        self.svr_sigma().map(|sigma| Laplace::new(value, sigma))
+    }
+
+    /// Calibrates the probability estimates of a classifier or regression SVM on labeled samples, e.g., a held-out
+    /// validation set, see [`DenseSVM::calibrate_probabilities`](crate::DenseSVM::calibrate_probabilities).
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`Error::InvalidCalibrationData`] if there are no samples, a label is not a class of the SVM, a
+    /// class has no samples, a regression SVM predicts all samples exactly, or the SVM is a one-class SVM, and if a
+    /// feature vector doesn't fit the SVM.
+    pub fn calibrate_probabilities(&mut self, samples: &mut [(f64, FeatureVector<SparseVector<f32>>)]) -> Result<(), Error> {
+        self.probabilities = Some(fit_probabilities(self, self.svm_type, samples)?);
+        Ok(())
+    }
 }
 
-impl Predict<SparseVector<f32>> for SparseSVM {
-    // Predict the value for one problem.
-    fn predict_value(&self, problem: &mut FeatureVector<SparseVector<f32>>) -> Result<(), Error> {
+impl SparseSVM {
+    /// Predicts the value for one problem, of this SVM or an [`Svm`](crate::Svm) holding it.
+    pub(crate) fn predict_features<F: AsFeatures<SparseVector<f32>>>(&self, problem: &mut FeatureVector<F>) -> Result<(), Error> {
+        check_feature_vector_impl!(self, problem);
+
         match self.svm_type {
             SVMType::CSvc | SVMType::NuSvc => {
-                // Compute kernel, decision values and eventually the label
-                self.compute_kernel_values(problem);
+                // Compute kernel, decision values and eventually the label. Linear kernels with weights don't need kernel values.
+                if self.weights.is_none() {
+                    self.compute_kernel_values(problem);
+                }
+
                 self.compute_classification_values(problem);
 
                 // Compute highest vote
@@ -166,16 +250,31 @@ impl Predict<SparseVector<f32>> for SparseSVM {
 
                 Ok(())
             }
-            SVMType::ESvr | SVMType::NuSvr => {
-                self.compute_kernel_values(problem);
+            SVMType::ESvr | SVMType::NuSvr | SVMType::OneClass => {
+                if self.weights.is_none() {
+                    self.compute_kernel_values(problem);
+                }
+
                 self.compute_regression_values(problem);
                 Ok(())
             }
         }
     }
 
+    /// Predicts the probabilities for one problem, of this SVM or an [`Svm`](crate::Svm) holding it.
+    pub(crate) fn predict_features_probability<F: AsFeatures<SparseVector<f32>>>(&self, problem: &mut FeatureVector<F>) -> Result<(), Error> {
+        predict_probability_impl!(self, problem, self.predict_features(problem)?)
+    }
+}
+
+impl Predict<SparseVector<f32>> for SparseSVM {
+    // Predict the value for one problem.
+    fn predict_value(&self, problem: &mut FeatureVector<SparseVector<f32>>) -> Result<(), Error> {
+        self.predict_features(problem)
+    }
+
     fn predict_probability(&self, problem: &mut FeatureVector<SparseVector<f32>>) -> Result<(), Error> {
-        predict_probability_impl!(self, problem)
+        self.predict_features_probability(problem)
     }
 }
 
@@ -206,10 +305,25 @@ impl<'a> TryFrom<&'a ModelFile<'_>> for SparseSVM {
 
             // Set support vector and coefficients
             for (i_vector, vector) in vectors[start_offset..stop_offset].iter().enumerate() {
+                let mut last_attribute = None;
+
                 // Set support vectors
                 for attribute in &vector.features {
+                    if let Some(last) = last_attribute {
+                        // Attributes may skip indices, but must be strictly increasing
+                        if attribute.index <= last {
+                            return Result::Err(Error::AttributesUnordered {
+                                index: attribute.index,
+                                value: attribute.value,
+                                last_index: last,
+                            });
+                        }
+                    }
+
                     let support_vectors = &mut svm.classes[i].support_vectors;
                     support_vectors[(i_vector, attribute.index as usize)] = attribute.value;
+
+                    last_attribute = Some(attribute.index);
                 }
 
                 // Set coefficients
@@ -223,7 +337,96 @@ impl<'a> TryFrom<&'a ModelFile<'_>> for SparseSVM {
             start_offset = stop_offset;
         }
 
+        // Linear models don't need their support vectors for predictions
+        if raw_model.header().kernel_type == "linear" {
+            svm.weights = Some(svm.linear_weights());
+        }
+
         // Return what we have
         Ok(svm)
     }
 }
+
+impl From<&SparseSVM> for ModelFile<'static> {
+    /// Converts the SVM back into a model, which can be written in libSVM's format via [`ToString`].
+    fn from(svm: &SparseSVM) -> Self {
+        model_file_impl!(
+            svm,
+            |class: &Class<SparseMatrix<f32>>, i_vector| class.support_vectors.row(i_vector).iter().map(|(index, value)| Attribute { value, index }).collect(),
+            kernel
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn linear_weights_match_kernel() -> Result<(), Error> {
+        let models = [
+            include_str!("../../../tests/data_sparse/m_csvm_linear_prob.libsvm"),
+            include_str!("../../../tests/data_sparse/m_nusvm_linear.libsvm"),
+            include_str!("../../../tests/data_sparse/m_e_svr_linear.libsvm"),
+            include_str!("../../../tests/data_sparse/m_one_class_linear.libsvm"),
+        ];
+
+        for model in models {
+            let mut svm = SparseSVM::try_from(model)?;
+            let mut fast = FeatureVector::from(&svm);
+            let mut slow = FeatureVector::from(&svm);
+
+            for (index, value) in [(3, 0.930_907_6), (4, 1.264_398_9), (6, 1.417_500_6), (32, 1.090_475_8), (46, 1.475_075), (110, 1.500_999)] {
+                fast.features()[index] = value;
+                slow.features()[index] = value;
+            }
+
+            assert!(svm.weights.is_some());
+            svm.predict_value(&mut fast)?;
+
+            svm.weights = None;
+            svm.predict_value(&mut slow)?;
+
+            assert!((fast.raw_value - slow.raw_value).abs() <= 1e-4 * slow.raw_value.abs().max(1.0));
+
+            for (a, b) in fast.decision_values.data.iter().zip(&slow.decision_values.data) {
+                assert!((a - b).abs() <= 1e-4 * b.abs().max(1.0));
+            }
+
+            if let Label::Class(_) = slow.label() {
+                assert_eq!(fast.label(), slow.label());
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "rayon")]
+    fn parallel_kernel_values_match_serial() -> Result<(), Error> {
+        use crate::svm::parallel::compute_chunks;
+
+        let svm = SparseSVM::try_from(include_str!("../../../tests/data_sparse/m_csvm_rbf_prob.libsvm"))?;
+        let mut problem = FeatureVector::from(&svm);
+
+        for (index, value) in [(3, 0.930_907_6), (4, 1.264_398_9), (6, 1.417_500_6), (32, 1.090_475_8), (46, 1.475_075), (110, 1.500_999)] {
+            problem.features()[index] = value;
+        }
+
+        for class in &svm.classes {
+            let num_sv = class.coefficients.dimension().1;
+            let mut serial = vec![0.0; num_sv];
+            let mut parallel = vec![0.0; num_sv];
+
+            svm.kernel.compute(&class.support_vectors, &problem.features, &mut serial);
+            compute_chunks(&mut parallel, num_sv, 3, |range, output| {
+                svm.kernel.compute_range(&class.support_vectors, range, &problem.features, output);
+            });
+
+            assert_eq!(serial, parallel);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,368 @@
+use crate::{
+    data::DataFeatures,
+    errors::Error,
+    parser::ModelFile,
+    scaler::{ScaledFeatures, Scaler},
+    sparse::SparseVector,
+    stats::{recommend, Representation},
+    svm::{
+        calibrate::fit_probabilities,
+        core::validate::validate,
+        features::{AsFeatures, FeatureVector},
+        guard::GuardedFeatures,
+        laplace::Laplace,
+        predict::Predict,
+        DenseSVM, SparseSVM,
+    },
+};
+use simd_aligned::{arch::f32x8, VecSimd};
+use std::convert::TryFrom;
+
+/// How an [`Svm`] picks the SVM a model is loaded into.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum BackendPolicy {
+    /// Load the model into the SVM recommended by [`ModelStats::recommended`](crate::ModelStats::recommended).
+    #[default]
+    Automatic,
+
+    /// Always load the model into a [`DenseSVM`].
+    Dense,
+
+    /// Always load the model into a [`SparseSVM`].
+    Sparse,
+}
+
+/// Feature storage of an [`SvmFeatures`] vector, matching the SVM the vector was created for.
+#[derive(Clone, Debug)]
+pub struct Features(Storage);
+
+#[derive(Clone, Debug)]
+enum Storage {
+    Dense(VecSimd<f32x8>),
+    Sparse(SparseVector<f32>),
+}
+
+/// Feature vectors produced for [`Svm`]s.
+///
+/// Also see [`FeatureVector`] for more methods for this type.
+pub type SvmFeatures = FeatureVector<Features>;
+
+/// An SVM that picks between a [`DenseSVM`] and a [`SparseSVM`] on its own.
+///
+/// A [`DenseSVM`] is faster for most models, but wastes time on models where most attributes are `0`. An [`Svm`] inspects the model, loads it into the better of both, and predicts
+/// [`SvmFeatures`] like any other SVM:
+///
+/// ```
+/// use ffsvm::{Error, FeatureVector, Label, Predict, Svm};
+/// # use ffsvm::SAMPLE_MODEL;
+///
+/// # fn main() -> Result<(), Error> {
+/// let svm = Svm::try_from(SAMPLE_MODEL)?;
+///
+/// let mut fv = FeatureVector::from(&svm);
+/// fv.set(0, 0.558_382);
+/// fv.set(1, -0.157_895);
+/// fv.set(2, 0.581_292);
+/// fv.set(3, -0.221_184);
+///
+/// svm.predict_value(&mut fv)?;
+///
+/// assert_eq!(fv.label(), Label::Class(42));
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Use [`Svm::with_policy`] to choose the SVM yourself.
+pub enum Svm {
+    /// The model was loaded into a [`DenseSVM`].
+    Dense(DenseSVM),
+
+    /// The model was loaded into a [`SparseSVM`].
+    Sparse(SparseSVM),
+}
+
+impl Svm {
+    /// Loads the model into the SVM chosen by `policy`.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the model can't be loaded into the chosen SVM. Models with a precomputed kernel can't be loaded
+    /// into either and produce an [`Error::UnsupportedKernel`].
+    pub fn with_policy(model: &ModelFile<'_>, policy: BackendPolicy) -> Result<Self, Error> {
+        // The model is validated before inspecting it, and only its attributes are counted, so that broken or
+        // malicious models fail with an error instead of making the inspection allocate unbounded memory.
+        let representation = match policy {
+            BackendPolicy::Automatic => {
+                validate(model)?;
+                recommend(model)
+            }
+            BackendPolicy::Dense => Representation::Dense,
+            BackendPolicy::Sparse => Representation::Sparse,
+        };
+
+        match representation {
+            Representation::Dense => Ok(Self::Dense(DenseSVM::try_from(model)?)),
+            Representation::Sparse => Ok(Self::Sparse(SparseSVM::try_from(model)?)),
+            Representation::Precomputed => Err(Error::UnsupportedKernel(model.header().kernel_type.to_string())),
+        }
+    }
+
+    /// Returns the SVM the model was loaded into.
+    #[must_use]
+    pub const fn representation(&self) -> Representation {
+        match self {
+            Self::Dense(_) => Representation::Dense,
+            Self::Sparse(_) => Representation::Sparse,
+        }
+    }
+
+    /// Finds the class index for a given label.
+    ///
+    /// # Description
+    ///
+    /// This method takes a `label` as defined in the libSVM training model
+    /// and returns the internal `index` where this label resides. The index
+    /// equals the [`FeatureVector`]'s `.probabilities()` index where that label's
+    /// probability can be found.
+    ///
+    /// # Returns
+    ///
+    /// If the label was found its index returned in the [`Option`], otherwise `None`
+    /// is returned.
+    #[must_use]
+    pub fn class_index_for_label(&self, label: i32) -> Option<usize> {
+        match self {
+            Self::Dense(svm) => svm.class_index_for_label(label),
+            Self::Sparse(svm) => svm.class_index_for_label(label),
+        }
+    }
+
+    /// Returns the class label for a given index.
+    ///
+    /// # Description
+    ///
+    /// The inverse of [`Svm::class_index_for_label`], this function returns the class label
+    /// associated with a certain internal index. The index equals the [`FeatureVector`]'s
+    /// `.probabilities()` index where a label's probability can be found.
+    ///
+    /// # Returns
+    ///
+    /// If the index was found it is returned in the [`Option`], otherwise `None`
+    /// is returned.
+    #[must_use]
+    pub fn class_label_for_index(&self, index: usize) -> Option<i32> {
+        match self {
+            Self::Dense(svm) => svm.class_label_for_index(index),
+            Self::Sparse(svm) => svm.class_label_for_index(index),
+        }
+    }
+
+    /// Returns number of attributes, reflecting the libSVM model.
+    #[must_use]
+    pub const fn attributes(&self) -> usize {
+        match self {
+            Self::Dense(svm) => svm.attributes(),
+            Self::Sparse(svm) => svm.attributes(),
+        }
+    }
+
+    /// Returns number of classes, reflecting the libSVM model.
+    #[must_use]
+    pub fn classes(&self) -> usize {
+        match self {
+            Self::Dense(svm) => svm.classes(),
+            Self::Sparse(svm) => svm.classes(),
+        }
+    }
+
+    /// For ε-SVR and ν-SVR models with probability estimates, returns the scale `sigma` of the Laplace distribution
+    /// of the difference between target and predicted values, see [`DenseSVM::svr_sigma`].
+    #[must_use]
+    pub fn svr_sigma(&self) -> Option<f64> {
+        match self {
+            Self::Dense(svm) => svm.svr_sigma(),
+            Self::Sparse(svm) => svm.svr_sigma(),
+        }
+    }
+
+    /// For ε-SVR and ν-SVR models with probability estimates, returns the distribution of the target value given the
+    /// predicted `value`, see [`DenseSVM::svr_distribution`].
+    #[must_use]
+    pub fn svr_distribution(&self, value: f64) -> Option<Laplace> {
+        self.svr_sigma().map(|sigma| Laplace::new(value, sigma))
+    }
+
+    /// Calibrates the probability estimates of a classifier or regression SVM on labeled samples, e.g., a held-out
+    /// validation set, see [`DenseSVM::calibrate_probabilities`].
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`Error::InvalidCalibrationData`] if there are no samples, a label is not a class of the SVM, a
+    /// class has no samples, a regression SVM predicts all samples exactly, or the SVM is a one-class SVM, and if a
+    /// feature vector doesn't fit the SVM.
+    pub fn calibrate_probabilities(&mut self, samples: &mut [(f64, SvmFeatures)]) -> Result<(), Error> {
+        let svm_type = match self {
+            Self::Dense(svm) => svm.svm_type,
+            Self::Sparse(svm) => svm.svm_type,
+        };
+
+        let probabilities = Some(fit_probabilities(self, svm_type, samples)?);
+
+        match self {
+            Self::Dense(svm) => svm.probabilities = probabilities,
+            Self::Sparse(svm) => svm.probabilities = probabilities,
+        }
+
+        Ok(())
+    }
+
+    /// Predicts `problem` in place with the SVM the model was loaded into.
+    fn predict(&self, problem: &mut SvmFeatures, probability: bool) -> Result<(), Error> {
+        match (self, &problem.features.0) {
+            (Self::Dense(svm), Storage::Dense(_)) if probability => svm.predict_features_probability(problem),
+            (Self::Dense(svm), Storage::Dense(_)) => svm.predict_features(problem),
+            (Self::Sparse(svm), Storage::Sparse(_)) if probability => svm.predict_features_probability(problem),
+            (Self::Sparse(svm), Storage::Sparse(_)) => svm.predict_features(problem),
+            _ => Err(Error::FeatureVectorMismatch("representation")),
+        }
+    }
+}
+
+impl Predict<Features> for Svm {
+    fn predict_value(&self, problem: &mut SvmFeatures) -> Result<(), Error> {
+        self.predict(problem, false)
+    }
+
+    fn predict_probability(&self, problem: &mut SvmFeatures) -> Result<(), Error> {
+        self.predict(problem, true)
+    }
+}
+
+impl TryFrom<&str> for Svm {
+    type Error = Error;
+
+    /// Parses the model and loads it into the SVM recommended for it.
+    fn try_from(input: &str) -> Result<Self, Error> {
+        Self::try_from(&ModelFile::try_from(input)?)
+    }
+}
+
+impl<'a> TryFrom<&'a ModelFile<'_>> for Svm {
+    type Error = Error;
+
+    /// Loads the model into the SVM recommended for it.
+    fn try_from(model: &'a ModelFile<'_>) -> Result<Self, Error> {
+        Self::with_policy(model, BackendPolicy::Automatic)
+    }
+}
+
+impl From<&Svm> for ModelFile<'static> {
+    /// Converts the SVM back into a model, which can be written in libSVM's format via [`ToString`].
+    fn from(svm: &Svm) -> Self {
+        match svm {
+            Svm::Dense(svm) => Self::from(svm),
+            Svm::Sparse(svm) => Self::from(svm),
+        }
+    }
+}
+
+impl From<&Svm> for SvmFeatures {
+    fn from(svm: &Svm) -> Self {
+        match svm {
+            Svm::Dense(svm) => {
+                let (features, problem) = FeatureVector::from(svm).replace_features(());
+                problem.replace_features(Features(Storage::Dense(features))).1
+            }
+            Svm::Sparse(svm) => {
+                let (features, problem) = FeatureVector::from(svm).replace_features(());
+                problem.replace_features(Features(Storage::Sparse(features))).1
+            }
+        }
+    }
+}
+
+impl SvmFeatures {
+    /// Sets feature `index` to `value`.
+    ///
+    /// # Panics
+    ///
+    /// For dense storage `index` must be smaller than the number of attributes.
+    pub fn set(&mut self, index: usize, value: f32) {
+        match &mut self.features.0 {
+            Storage::Dense(features) => features.flat_mut()[index] = value,
+            Storage::Sparse(features) => features[index] = value,
+        }
+    }
+
+    /// Returns feature `index`, `0` if it was never set.
+    #[must_use]
+    pub fn get(&self, index: usize) -> f32 {
+        match &self.features.0 {
+            Storage::Dense(features) => features.flat().get(index).copied().unwrap_or_default(),
+            Storage::Sparse(features) => features.get(index).copied().unwrap_or_default(),
+        }
+    }
+
+    /// Resets all features to `0`, e.g., before reusing the feature vector.
+    pub fn clear(&mut self) {
+        match &mut self.features.0 {
+            Storage::Dense(features) => features.flat_mut().fill(0.0),
+            Storage::Sparse(features) => features.clear(),
+        }
+    }
+
+    /// Returns the storage this feature vector uses, matching the [`Svm`] it was created for.
+    #[must_use]
+    pub const fn representation(&self) -> Representation {
+        match self.features.0 {
+            Storage::Dense(_) => Representation::Dense,
+            Storage::Sparse(_) => Representation::Sparse,
+        }
+    }
+}
+
+// `Svm::predict` only hands dense storage to a `DenseSVM`, and sparse storage to a `SparseSVM`.
+impl AsFeatures<VecSimd<f32x8>> for Features {
+    fn as_features(&self) -> &VecSimd<f32x8> {
+        match &self.0 {
+            Storage::Dense(features) => features,
+            Storage::Sparse(_) => unreachable!("A dense SVM can't predict sparse features."),
+        }
+    }
+}
+
+impl AsFeatures<SparseVector<f32>> for Features {
+    fn as_features(&self) -> &SparseVector<f32> {
+        match &self.0 {
+            Storage::Sparse(features) => features,
+            Storage::Dense(_) => unreachable!("A sparse SVM can't predict dense features."),
+        }
+    }
+}
+
+impl DataFeatures for Features {
+    fn set_feature(&mut self, num_attributes: usize, index: usize, value: f64) -> bool {
+        match &mut self.0 {
+            Storage::Dense(features) => features.set_feature(num_attributes, index, value),
+            Storage::Sparse(features) => features.set_feature(num_attributes, index, value),
+        }
+    }
+}
+
+impl ScaledFeatures for Features {
+    fn scale_features(&mut self, num_attributes: usize, scaler: &Scaler) {
+        match &mut self.0 {
+            Storage::Dense(features) => features.scale_features(num_attributes, scaler),
+            Storage::Sparse(features) => features.scale_features(num_attributes, scaler),
+        }
+    }
+}
+
+impl GuardedFeatures for Features {
+    fn replace_features(&mut self, num_attributes: usize, f: impl FnMut(usize, f64) -> Result<Option<f64>, Error>) -> Result<(), Error> {
+        match &mut self.0 {
+            Storage::Dense(features) => features.replace_features(num_attributes, f),
+            Storage::Sparse(features) => features.replace_features(num_attributes, f),
+        }
+    }
+}
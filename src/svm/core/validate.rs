@@ -0,0 +1,121 @@
+use crate::{errors::Error, parser::ModelFile, svm::SVMType};
+use std::convert::TryFrom;
+
+/// Checks the header of a model for consistency, and that its support vectors agree with it.
+///
+/// This runs before any SVM is built from a [`ModelFile`], so that broken models produce an [`Error`]
+/// instead of a panic or a silently corrupt SVM. On success the model's [`SVMType`] is returned.
+pub fn validate(raw_model: &ModelFile<'_>) -> Result<SVMType, Error> {
+    let header = raw_model.header();
+    let vectors = raw_model.vectors();
+
+    let svm_type = SVMType::try_from(header.svm_type)?;
+    let total_sv = header.total_sv as usize;
+
+    if vectors.is_empty() {
+        return Err(Error::NoSupportVectors);
+    }
+
+    if vectors.len() != total_sv {
+        return Err(Error::InconsistentSupportVectorCount { total_sv, found: vectors.len() });
+    }
+
+    // Classifiers have a decision function per class pair, regression and one-class SVMs have only one.
+    let (num_classes, num_pairs) = match svm_type {
+        SVMType::CSvc | SVMType::NuSvc => {
+            let nr_class = header.nr_class as usize;
+
+            if nr_class < 2 {
+                return Err(Error::InvalidClassCount(header.nr_class));
+            }
+
+            check_length("label", header.label.len(), nr_class)?;
+            check_length("nr_sv", header.nr_sv.len(), nr_class)?;
+
+            let found = header.nr_sv.iter().map(|x| *x as usize).sum::<usize>();
+
+            if found != total_sv {
+                return Err(Error::InconsistentSupportVectorCount { total_sv, found });
+            }
+
+            (nr_class, nr_class * (nr_class - 1) / 2)
+        }
+        SVMType::ESvr | SVMType::NuSvr | SVMType::OneClass => (2, 1),
+    };
+
+    if header.rho.len() != num_pairs {
+        return Err(Error::RhoLengthMismatch {
+            expected: num_pairs,
+            found: header.rho.len(),
+        });
+    }
+
+    if let Some(prob_a) = &header.prob_a {
+        check_length("probA", prob_a.len(), num_pairs)?;
+    }
+
+    if let Some(prob_b) = &header.prob_b {
+        check_length("probB", prob_b.len(), num_pairs)?;
+    }
+
+    // Probability estimates of classifiers need both sigmoid parameters, only regression SVMs have `probA` alone.
+    if matches!(svm_type, SVMType::CSvc | SVMType::NuSvc) {
+        match (&header.prob_a, &header.prob_b) {
+            (Some(_), None) => check_length("probB", 0, num_pairs)?,
+            (None, Some(_)) => check_length("probA", 0, num_pairs)?,
+            _ => {}
+        }
+    }
+
+    for (sv_index, vector) in vectors.iter().enumerate() {
+        if vector.coefs.len() != num_classes - 1 {
+            return Err(Error::CoefficientCountMismatch {
+                sv_index,
+                expected: num_classes - 1,
+                found: vector.coefs.len(),
+            });
+        }
+    }
+
+    Ok(svm_type)
+}
+
+/// Smallest number of values, `total_sv × num_attributes`, any model may have when stored densely.
+const MIN_DENSE_VALUES: usize = 1 << 24;
+
+/// Number of values a model may have when stored densely per attribute it actually stores.
+const DENSE_VALUES_PER_STORED: usize = 256;
+
+/// Returns the largest number of attributes an SVM may infer from the largest attribute index of a model.
+///
+/// Attribute indices come straight from the model, so a single huge index would otherwise make loading allocate
+/// arbitrary amounts of memory. Models may be up to [`DENSE_VALUES_PER_STORED`] times larger when stored densely than
+/// they are in the file, but always at least [`MIN_DENSE_VALUES`] values, i.e., 64 MB of `f32`.
+pub fn attribute_limit(raw_model: &ModelFile<'_>) -> usize {
+    let vectors = raw_model.vectors();
+    let num_stored = vectors.iter().map(|x| x.features.len()).sum::<usize>();
+
+    (num_stored.saturating_mul(DENSE_VALUES_PER_STORED)).max(MIN_DENSE_VALUES) / vectors.len().max(1)
+}
+
+/// Smallest kernel row length any precomputed kernel model may have.
+const MIN_KERNEL_ROW: usize = 1 << 20;
+
+/// Returns the largest kernel row length a precomputed kernel model may have, i.e., its largest serial number plus one.
+///
+/// Each feature vector of a precomputed kernel SVM holds a whole kernel row, so like [`attribute_limit`] this keeps a
+/// single huge serial number from making feature vectors allocate arbitrary amounts of memory. Rows may be up to
+/// [`DENSE_VALUES_PER_STORED`] times longer than the number of support vectors, but always at least
+/// [`MIN_KERNEL_ROW`] values long.
+pub fn kernel_row_limit(raw_model: &ModelFile<'_>) -> usize {
+    raw_model.vectors().len().saturating_mul(DENSE_VALUES_PER_STORED).max(MIN_KERNEL_ROW)
+}
+
+/// Makes sure a multi value header has the expected number of entries.
+const fn check_length(key: &'static str, found: usize, expected: usize) -> Result<(), Error> {
+    if found == expected {
+        Ok(())
+    } else {
+        Err(Error::HeaderLengthMismatch { key, expected, found })
+    }
+}
@@ -0,0 +1,377 @@
+use simd_aligned::{
+    arch::{f32x8, f64x4},
+    traits::Simd,
+    MatSimd, Rows, VecSimd,
+};
+use std::convert::TryFrom;
+
+use crate::{
+    errors::Error,
+    svm::{
+        core::binary::{matrix_size, BinaryHeader, ALIGNMENT},
+        features::{AsFeatures, FeatureVector, Label},
+        kernel::KernelDense,
+        laplace::Laplace,
+        predict::Predict,
+        Probabilities, SVMType,
+    },
+    util::{find_max_index, set_all, sigmoid_predict},
+    vectors::Triangular,
+};
+
+/// Rows of SIMD vectors borrowed from a binary model, laid out like a `MatSimd<T, Rows>`.
+#[derive(Clone, Copy, Debug)]
+pub struct RowsView<'a, T> {
+    /// All rows, one after the other.
+    pub(crate) data: &'a [T],
+
+    /// Number of SIMD vectors per row.
+    pub(crate) row_length: usize,
+}
+
+impl<'a, T> RowsView<'a, T> {
+    /// Returns the SIMD vectors of the given row.
+    #[inline]
+    pub fn row(&self, i: usize) -> &'a [T] {
+        &self.data[i * self.row_length..(i + 1) * self.row_length]
+    }
+}
+
+/// Represents one class of a [`DenseSVMView`].
+#[derive(Clone, Debug)]
+pub struct ClassView<'a> {
+    /// The label of this class
+    pub(crate) label: i32,
+
+    /// Coefficients between this class and n-1 other classes.
+    pub(crate) coefficients: RowsView<'a, f64x4>,
+
+    /// All support vectors in this class.
+    pub(crate) support_vectors: &'a [f32x8],
+
+    /// The number of support vectors in this class.
+    pub(crate) num_support_vectors: usize,
+}
+
+impl ClassView<'_> {
+    /// Returns the number of support vectors in this class.
+    pub const fn num_support_vectors(&self) -> usize {
+        self.num_support_vectors
+    }
+
+    /// Adds all support vectors, weighted by their coefficients in the given row, to `weights`.
+    pub fn add_weighted(&self, weights: &mut [f64], row: usize) {
+        let coefficients = self.coefficients.row(row).iter().flat_map(Simd::as_array);
+        let row_length = weights.len().div_ceil(f32x8::LANES);
+
+        for (coefficient, sv) in coefficients.zip(self.support_vectors.chunks_exact(row_length)) {
+            for (weight, value) in weights.iter_mut().zip(sv.iter().flat_map(Simd::as_array)) {
+                *weight += coefficient * f64::from(*value);
+            }
+        }
+    }
+}
+
+/// A [`DenseSVM`](crate::DenseSVM) borrowing its support vectors and coefficients from a binary model.
+///
+/// Where a [`DenseSVM`](crate::DenseSVM) loaded from a binary model copies all support vectors to the heap, this
+/// view uses the given bytes directly, for example a model embedded with `include_bytes!` or a memory mapped file.
+/// Only the small `rho` and probability tables are copied. Binary models are produced by
+/// [`DenseSVM::to_bytes`](crate::DenseSVM::to_bytes).
+///
+/// # Creating a View
+///
+/// The bytes must start at an address aligned to 32 bytes, the alignment of the SIMD vectors inside. As with a
+/// [`DenseSVM`](crate::DenseSVM), the format version, size and checksum of the model are checked, which reads
+/// the whole model once.
+///
+/// ```rust
+/// use ffsvm::{DenseFeatures, DenseSVM, DenseSVMView, Predict};
+/// # use ffsvm::SAMPLE_MODEL;
+///
+/// let bytes = DenseSVM::try_from(SAMPLE_MODEL)?.to_bytes();
+///
+/// // Get 32 byte aligned memory; usually the model would be mapped or embedded that way.
+/// let mut buffer = vec![0_u8; bytes.len() + 32];
+/// let offset = buffer.as_ptr().align_offset(32);
+/// buffer[offset..offset + bytes.len()].copy_from_slice(&bytes);
+///
+/// let svm = DenseSVMView::try_from(&buffer[offset..offset + bytes.len()])?;
+/// let mut features = DenseFeatures::from(&svm);
+///
+/// svm.predict_value(&mut features)?;
+/// # Ok::<(), ffsvm::Error>(())
+/// ```
+///
+/// Views are only available on little endian targets, as the binary format is little endian.
+pub struct DenseSVMView<'a> {
+    /// Total number of support vectors
+    pub(crate) num_total_sv: usize,
+
+    /// Number of attributes per support vector
+    pub(crate) num_attributes: usize,
+
+    pub(crate) rho: Triangular<f64>,
+
+    pub(crate) probabilities: Option<Probabilities>,
+
+    pub(crate) svm_type: SVMType,
+
+    /// SVM specific data needed for classification
+    pub(crate) kernel: Box<dyn KernelDense>,
+
+    /// All classes
+    pub(crate) classes: Vec<ClassView<'a>>,
+
+    /// For linear kernels, the weight vector `w` of each class pair in the order of `rho`, so that a
+    /// decision value is `w·x - rho`.
+    pub(crate) weights: Option<MatSimd<f32x8, Rows>>,
+}
+
+impl DenseSVMView<'_> {
+    /// Finds the class index for a given label.
+    ///
+    /// # Description
+    ///
+    /// This method takes a `label` as defined in the libSVM training model
+    /// and returns the internal `index` where this label resides. The index
+    /// equals [`FeatureVector::probabilities`] index where that label's
+    /// probability can be found.
+    ///
+    /// # Returns
+    ///
+    /// If the label was found its index returned in the [`Option`], otherwise `None`
+    /// is returned.
+    #[must_use]
+    pub fn class_index_for_label(&self, label: i32) -> Option<usize> {
+        for (i, class) in self.classes.iter().enumerate() {
+            if class.label != label {
+                continue;
+            }
+
+            return Some(i);
+        }
+
+        None
+    }
+
+    /// Returns the class label for a given index.
+    ///
+    /// # Description
+    ///
+    /// The inverse of [`DenseSVMView::class_index_for_label`], this function returns the class label
+    /// associated with a certain internal index. The index equals the [`FeatureVector::probabilities`]
+    /// index where a label's probability can be found.
+    ///
+    /// # Returns
+    ///
+    /// If the index was found it is returned in the [`Option`], otherwise `None`
+    /// is returned.
+    #[must_use]
+    pub fn class_label_for_index(&self, index: usize) -> Option<i32> {
+        if index >= self.classes.len() {
+            None
+        } else {
+            Some(self.classes[index].label)
+        }
+    }
+
+    /// Computes the kernel values for this problem
+    pub(crate) fn compute_kernel_values(&self, problem: &mut FeatureVector<VecSimd<f32x8>>) {
+        // Get current problem and decision values array
+        let features = &problem.features;
+        let kernel_values = &mut problem.kernel_values;
+
+        // Compute kernel values per class
+        for (i, class) in self.classes.iter().enumerate() {
+            let kvalues = kernel_values.row_as_flat_mut(i);
+
+            self.kernel.compute_rows(class.support_vectors, features, kvalues);
+        }
+
+        problem.has_kernel_values = true;
+    }
+
+    // This is pretty much copy-paste of `multiclass_probability` from libSVM which we need
+    // to be compatibly for predicting probability for multiclass SVMs. The method is in turn
+    // based on Method 2 from the paper "Probability Estimates for Multi-class
+    // Classification by Pairwise Coupling", Journal of Machine Learning Research 5 (2004) 975-1005,
+    // by Ting-Fan Wu, Chih-Jen Lin and Ruby C. Weng.
+    pub(crate) fn compute_multiclass_probabilities(&self, problem: &mut FeatureVector<VecSimd<f32x8>>) -> Result<(), Error> {
+        compute_multiclass_probabilities_impl!(self, problem)
+    }
+
+    /// Based on kernel values, or the weights of linear kernels, computes the decision values for this problem.
+    pub(crate) fn compute_classification_values(&self, problem: &mut FeatureVector<VecSimd<f32x8>>) {
+        if let Some(weights) = &self.weights {
+            compute_linear_classification_values_impl!(self, problem, weights);
+        } else {
+            compute_classification_values_impl!(self, problem);
+        }
+    }
+
+    /// Based on kernel values, or the weights of linear kernels, computes the single decision value of regression and one-class SVMs.
+    pub(crate) fn compute_regression_values(&self, problem: &mut FeatureVector<VecSimd<f32x8>>) {
+        // For linear kernels `w·x` replaces the weighted sum over all support vectors
+        let mut sum = if let Some(weights) = &self.weights {
+            let mut dot = [0.0];
+            problem.has_kernel_values = false;
+            self.kernel.compute(weights, &problem.features, &mut dot);
+            dot[0]
+        } else {
+            let class = &self.classes[0];
+            let coef = class.coefficients.row(0);
+            let kvalues = problem.kernel_values.row(0);
+
+            coef.iter().zip(kvalues).map(|(a, b)| (*a * *b).sum()).sum::<f64>()
+        };
+
+        sum -= self.rho[0];
+
+        problem.raw_value = sum;
+        problem.result = match self.svm_type {
+            // Same as `libSVM`, one-class SVMs report inliers as `+1` and outliers as `-1`.
+            SVMType::OneClass => Label::Class(if sum > 0.0 { 1 } else { -1 }),
+            _ => Label::Value(sum as f32),
+        };
+    }
+
+    /// For linear kernels, computes the weight vector of each class pair from the support vectors and their coefficients.
+    pub(crate) fn linear_weights(&self) -> MatSimd<f32x8, Rows> {
+        let pairs = linear_weights_impl!(self, vec![0.0; self.num_attributes]);
+        let mut weights = MatSimd::with_dimension(pairs.len(), self.num_attributes);
+
+        for (row, w) in pairs.iter().enumerate() {
+            for (weight, value) in weights.row_as_flat_mut(row).iter_mut().zip(w) {
+                *weight = *value as f32;
+            }
+        }
+
+        weights
+    }
+
+    /// Returns number of attributes, reflecting the libSVM model.
+    #[must_use]
+    pub const fn attributes(&self) -> usize {
+        self.num_attributes
+    }
+
+    /// Returns number of classes, reflecting the libSVM model.
+    #[must_use]
+    pub fn classes(&self) -> usize {
+        self.classes.len()
+    }
+
+    /// For ε-SVR and ν-SVR models with probability estimates, returns the scale `sigma` of the Laplace distribution
+    /// of the difference between target and predicted values, see [`DenseSVM::svr_sigma`](crate::DenseSVM::svr_sigma).
+    #[must_use]
+    pub fn svr_sigma(&self) -> Option<f64> {
+        svr_sigma_impl!(self)
+    }
+
+    /// For ε-SVR and ν-SVR models with probability estimates, returns the distribution of the target value given the
+    /// predicted `value`, see [`DenseSVM::svr_distribution`](crate::DenseSVM::svr_distribution).
+    #[must_use]
+    pub fn svr_distribution(&self, value: f64) -> Option<Laplace> {
+        self.svr_sigma().map(|sigma| Laplace::new(value, sigma))
+    }
+}
+
+impl Predict<VecSimd<f32x8>> for DenseSVMView<'_> {
+    // Predict the value for one problem.
+    fn predict_value(&self, fv: &mut FeatureVector<VecSimd<f32x8>>) -> Result<(), Error> {
+        check_feature_vector_impl!(self, fv);
+
+        match self.svm_type {
+            SVMType::CSvc | SVMType::NuSvc => {
+                // Compute kernel, decision values and eventually the label. Linear kernels with weights don't need kernel values.
+                if self.weights.is_none() {
+                    self.compute_kernel_values(fv);
+                }
+
+                self.compute_classification_values(fv);
+
+                // Compute the highest vote
+                let highest_vote = find_max_index(&fv.vote);
+                fv.result = Label::Class(self.classes[highest_vote].label);
+
+                Ok(())
+            }
+            SVMType::ESvr | SVMType::NuSvr | SVMType::OneClass => {
+                if self.weights.is_none() {
+                    self.compute_kernel_values(fv);
+                }
+
+                self.compute_regression_values(fv);
+                Ok(())
+            }
+        }
+    }
+
+    fn predict_probability(&self, problem: &mut FeatureVector<VecSimd<f32x8>>) -> Result<(), Error> {
+        predict_probability_impl!(self, problem)
+    }
+}
+
+impl<'a> TryFrom<&'a [u8]> for DenseSVMView<'a> {
+    type Error = Error;
+
+    /// Creates a view over a binary model, as produced by [`DenseSVM::to_bytes`](crate::DenseSVM::to_bytes).
+    fn try_from(bytes: &'a [u8]) -> Result<Self, Error> {
+        let header = BinaryHeader::read(bytes)?;
+
+        // Blocks are aligned relative to the start of the model, so this aligns all of them.
+        if bytes.as_ptr().align_offset(ALIGNMENT) != 0 {
+            return Err(Error::BinaryUnaligned { alignment: ALIGNMENT });
+        }
+
+        // Dense rows can't be told apart without attributes
+        if header.num_attributes == 0 {
+            return Err(Error::BinaryInvalidHeader("num_attributes"));
+        }
+
+        let tables = header.read_tables(bytes)?;
+        let mut offset = tables.offset;
+        let mut classes = Vec::with_capacity(tables.classes.len());
+
+        // Returns the next `length` bytes of the payload.
+        let mut borrow = |length: usize| {
+            let block = &bytes[offset..offset + length];
+            offset += length;
+            block
+        };
+
+        for (label, num_sv) in tables.classes {
+            let coefficients = borrow(matrix_size::<f64x4>(header.num_coefficients(), num_sv));
+            let support_vectors = borrow(matrix_size::<f32x8>(num_sv, header.num_attributes));
+
+            classes.push(ClassView {
+                label,
+                coefficients: RowsView {
+                    data: bytemuck::try_cast_slice(coefficients).map_err(|_| Error::BinaryUnaligned { alignment: ALIGNMENT })?,
+                    row_length: num_sv.div_ceil(f64x4::LANES),
+                },
+                support_vectors: bytemuck::try_cast_slice(support_vectors).map_err(|_| Error::BinaryUnaligned { alignment: ALIGNMENT })?,
+                num_support_vectors: num_sv,
+            });
+        }
+
+        let mut svm = Self {
+            num_total_sv: header.num_total_sv,
+            num_attributes: header.num_attributes,
+            rho: tables.rho,
+            probabilities: tables.probabilities,
+            svm_type: header.svm_type,
+            kernel: header.kernel()?,
+            classes,
+            weights: None,
+        };
+
+        // Linear models don't need their support vectors for predictions. The weights are small compared to them.
+        if header.kernel_type == "linear" {
+            svm.weights = Some(svm.linear_weights());
+        }
+
+        Ok(svm)
+    }
+}
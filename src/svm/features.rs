@@ -1,6 +1,6 @@
 use crate::{
     sparse::SparseVector,
-    svm::{DenseSVM, SparseSVM},
+    svm::{class::Class, DenseSVM, PrecomputedSVM, SparseSVM},
     vectors::Triangular,
 };
 
@@ -9,6 +9,9 @@ use simd_aligned::{
     MatSimd, Rows, VecSimd,
 };
 
+#[cfg(target_endian = "little")]
+use crate::svm::DenseSVMView;
+
 /// Feature vectors produced for [`DenseSVM`]s.
 ///
 /// Also see [`FeatureVector`] for more methods for this type.
@@ -19,10 +22,16 @@ pub type DenseFeatures = FeatureVector<VecSimd<f32x8>>;
 /// Also see [`FeatureVector`] for more methods for this type.
 pub type SparseFeatures = FeatureVector<SparseVector<f32>>;
 
+/// Feature vectors produced for [`PrecomputedSVM`]s.
+///
+/// Instead of features these hold the kernel row `K(x, x_i)` of the instance to classify. Also see [`FeatureVector`]
+/// for more methods for this type.
+pub type PrecomputedFeatures = FeatureVector<Vec<f64>>;
+
 /// The result of a classification
 #[derive(Copy, Debug, Clone, PartialEq)]
 pub enum Label {
-    /// If classified this will hold the label.
+    /// If classified this will hold the label. For one-class SVMs this is `1` for inliers and `-1` for outliers.
     Class(i32),
 
     /// If regression was performed contains regression result.
@@ -74,6 +83,9 @@ pub struct FeatureVector<T> {
     /// KernelDense values. A vector for each class.
     pub(crate) kernel_values: MatSimd<f64x4, Rows>,
 
+    /// Whether `kernel_values` hold the values of the last prediction, linear models with weights don't compute them.
+    pub(crate) has_kernel_values: bool,
+
     /// All votes for a given class label.
     pub(crate) vote: Vec<u32>,
 
@@ -93,22 +105,170 @@ pub struct FeatureVector<T> {
     /// by `predict_probability`.
     pub(crate) probabilities: VecSimd<f64x4>,
 
+    /// Raw decision value of regression and one-class SVMs.
+    pub(crate) raw_value: f64,
+
     /// Computed label that will be updated after this problem was processed.
     pub(crate) result: Label,
+
+    /// Number of attributes of the SVM this was created for.
+    pub(crate) num_attributes: usize,
+
+    /// Total number of support vectors of the SVM this was created for.
+    pub(crate) num_total_sv: usize,
+
+    /// Label and number of support vectors of each class of the SVM this was created for.
+    pub(crate) classes: Vec<(i32, usize)>,
 }
 
 impl<T> FeatureVector<T> {
+    /// Creates a new problem with the given feature storage, all other buffers sized by the given parameters.
+    pub(crate) fn with_features(features: T, total_sv: usize, classes: Vec<(i32, usize)>, num_attributes: usize) -> Self {
+        let num_classes = classes.len();
+
+        Self {
+            features,
+            kernel_values: MatSimd::with_dimension(num_classes, total_sv),
+            has_kernel_values: false,
+            pairwise: MatSimd::with_dimension(num_classes, num_classes),
+            q: MatSimd::with_dimension(num_classes, num_classes),
+            qp: vec![Default::default(); num_classes],
+            decision_values: Triangular::with_dimension(num_classes, Default::default()),
+            vote: vec![Default::default(); num_classes],
+            probabilities: VecSimd::with(0.0, num_classes),
+            raw_value: 0.0,
+            result: Label::None,
+            num_attributes,
+            num_total_sv: total_sv,
+            classes,
+        }
+    }
+
+    /// Replaces the feature storage, returning the old one, while keeping all other buffers.
+    pub(crate) fn replace_features<U>(self, features: U) -> (T, FeatureVector<U>) {
+        let Self { features: old, kernel_values, has_kernel_values, vote, decision_values, pairwise, q, qp, probabilities, raw_value, result, num_attributes, num_total_sv, classes } = self;
+
+        let replaced = FeatureVector {
+            features,
+            kernel_values,
+            has_kernel_values,
+            vote,
+            decision_values,
+            pairwise,
+            q,
+            qp,
+            probabilities,
+            raw_value,
+            result,
+            num_attributes,
+            num_total_sv,
+            classes,
+        };
+
+        (old, replaced)
+    }
+
     /// After a [`Problem`](crate::FeatureVector) has been classified, this will hold the SVMs solution label.
     pub const fn label(&self) -> Label {
         self.result
     }
 
+    /// For regression and one-class SVMs, returns the raw decision value of the last prediction.
+    ///
+    /// This is the value `libSVM` computes in `svm_predict_values`, i.e., the weighted kernel sum minus `rho`. For
+    /// one-class SVMs its sign determines whether the [`FeatureVector::label`] is `1` (inlier) or `-1` (outlier), for
+    /// regression SVMs the [`Label::Value`] holds the same value, cast to `f32`.
+    pub const fn raw_value(&self) -> f64 {
+        self.raw_value
+    }
+
+    /// For classification SVMs, returns the decision value between the classes with index `i` and `j`.
+    ///
+    /// Class indices are the same as for [`FeatureVector::probabilities`], i.e., in the order the labels appear in
+    /// the model file, and can be mapped to labels via the SVM's `class_label_for_index`. Same as in `libSVM`, a
+    /// positive value is a vote for class `i`, a negative one for class `j`. Decision values are antisymmetric, so
+    /// asking for `(j, i)` returns the negated value of `(i, j)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i` equals `j`, or if either index is not a valid class index.
+    pub fn decision_value(&self, i: usize, j: usize) -> f64 {
+        assert_ne!(i, j, "There is no decision value between a class and itself.");
+
+        if i < j {
+            self.decision_values[(i, j)]
+        } else {
+            -self.decision_values[(j, i)]
+        }
+    }
+
+    /// For classification SVMs, iterates over all pairwise decision values as `(label_i, label_j, value)`.
+    ///
+    /// The order is the one of `libSVM`'s `svm_predict_values`, i.e., `(0, 1)`, `(0, 2)`, ..., `(0, n-1)`, `(1, 2)`,
+    /// ..., `(n-2, n-1)` in terms of class indices, so collecting the values reproduces its `dec_values` array.
+    /// Regression and one-class SVMs have no pairwise decision values, see [`FeatureVector::raw_value`] instead.
+    pub fn decision_values(&self) -> impl Iterator<Item = (i32, i32, f64)> + '_ {
+        let labels = self.classes.iter().map(|(label, _)| *label);
+
+        labels.clone().enumerate().flat_map(move |(i, label_i)| {
+            labels
+                .clone()
+                .enumerate()
+                .skip(i + 1)
+                .map(move |(j, label_j)| (label_i, label_j, self.decision_values[(i, j)]))
+        })
+    }
+
+    /// For classification SVMs, returns the number of votes each class received in the last prediction.
+    ///
+    /// Indices are the same as for [`FeatureVector::probabilities`]. Same as in `libSVM`, the predicted label is
+    /// the one with the most votes, ties are won by the lower index.
+    pub fn votes(&self) -> &[u32] {
+        &self.vote
+    }
+
+    /// Returns the kernel values `K(x, x_i)` between the features and all support vectors of the class with index
+    /// `class`, as computed by the last prediction.
+    ///
+    /// Support vectors are in the order they appear in the model file. For regression and one-class SVMs all
+    /// support vectors belong to class `0`. Returns `None` before the first prediction, and for linear models, which
+    /// don't compute kernel values but use a single weight vector per class pair instead.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `class` is not a valid class index.
+    pub fn kernel_values(&self, class: usize) -> Option<&[f64]> {
+        let (_, num_sv) = self.classes[class];
+
+        self.has_kernel_values.then(|| &self.kernel_values.row_as_flat(class)[..num_sv])
+    }
+
     /// Returns the probability estimates. Only really useful if the model was trained with probability estimates and you classified with them.
     pub fn probabilities(&self) -> &[f64] {
         self.probabilities.flat()
     }
 }
 
+/// Implemented by feature storage holding features of type `T`, so SVMs can predict their own feature vectors and
+/// [`SvmFeatures`](crate::SvmFeatures) alike.
+#[doc(hidden)]
+pub trait AsFeatures<T> {
+    /// Returns the features.
+    fn as_features(&self) -> &T;
+}
+
+impl AsFeatures<Self> for VecSimd<f32x8> {
+    fn as_features(&self) -> &Self {
+        self
+    }
+}
+
+impl AsFeatures<Self> for SparseVector<f32> {
+    fn as_features(&self) -> &Self {
+        self
+    }
+}
+
 impl FeatureVector<VecSimd<f32x8>> {
     /// Returns the features. You must set them first and classify the problem before you can get a solution.
     pub fn features(&mut self) -> &mut [f32] {
@@ -118,25 +278,26 @@ impl FeatureVector<VecSimd<f32x8>> {
 
 impl FeatureVector<SparseVector<f32>> {
     /// Returns the features. You must set them first and classify the problem before you can get a solution.
-    pub fn features(&mut self) -> &mut SparseVector<f32> {
+    pub const fn features(&mut self) -> &mut SparseVector<f32> {
+        &mut self.features
+    }
+}
+
+impl FeatureVector<Vec<f64>> {
+    /// Returns the kernel row you must set before classifying the problem.
+    ///
+    /// Entry `i` must hold the kernel value `K(x, x_i)` between this problem and the training instance with
+    /// serial number `i`, the same numbering `libSVM` uses in precomputed kernel files. As serial numbers
+    /// start with `1`, entry `0` is not used.
+    pub fn features(&mut self) -> &mut [f64] {
         &mut self.features
     }
 }
 
 impl DenseFeatures {
     /// Creates a new problem with the given parameters.
-    pub(crate) fn with_dimension(total_sv: usize, num_classes: usize, num_attributes: usize) -> Self {
-        Self {
-            features: VecSimd::with(0.0, num_attributes),
-            kernel_values: MatSimd::with_dimension(num_classes, total_sv),
-            pairwise: MatSimd::with_dimension(num_classes, num_classes),
-            q: MatSimd::with_dimension(num_classes, num_classes),
-            qp: vec![Default::default(); num_classes],
-            decision_values: Triangular::with_dimension(num_classes, Default::default()),
-            vote: vec![Default::default(); num_classes],
-            probabilities: VecSimd::with(0.0, num_classes),
-            result: Label::None,
-        }
+    pub(crate) fn with_dimension(total_sv: usize, classes: Vec<(i32, usize)>, num_attributes: usize) -> Self {
+        Self::with_features(VecSimd::with(0.0, num_attributes), total_sv, classes, num_attributes)
     }
 }
 
@@ -147,29 +308,46 @@ impl SparseFeatures {
     }
 
     /// Creates a new problem with the given parameters.
-    pub(crate) fn with_dimension(total_sv: usize, num_classes: usize, _num_attributes: usize) -> Self {
-        Self {
-            features: SparseVector::new(),
-            kernel_values: MatSimd::with_dimension(num_classes, total_sv),
-            pairwise: MatSimd::with_dimension(num_classes, num_classes),
-            q: MatSimd::with_dimension(num_classes, num_classes),
-            qp: vec![Default::default(); num_classes],
-            decision_values: Triangular::with_dimension(num_classes, Default::default()),
-            vote: vec![Default::default(); num_classes],
-            probabilities: VecSimd::with(0.0, num_classes),
-            result: Label::None,
-        }
+    pub(crate) fn with_dimension(total_sv: usize, classes: Vec<(i32, usize)>, num_attributes: usize) -> Self {
+        Self::with_features(SparseVector::new(), total_sv, classes, num_attributes)
     }
 }
 
+impl PrecomputedFeatures {
+    /// Creates a new problem with the given parameters.
+    pub(crate) fn with_dimension(total_sv: usize, classes: Vec<(i32, usize)>, num_attributes: usize) -> Self {
+        Self::with_features(vec![0.0; num_attributes], total_sv, classes, num_attributes)
+    }
+}
+
+/// Returns label and number of support vectors of all classes.
+fn classes<M32>(classes: &[Class<M32>]) -> Vec<(i32, usize)> {
+    classes.iter().map(|class| (class.label, class.num_support_vectors())).collect()
+}
+
 impl From<&DenseSVM> for DenseFeatures {
     fn from(svm: &DenseSVM) -> Self {
-        Self::with_dimension(svm.num_total_sv, svm.classes.len(), svm.num_attributes)
+        Self::with_dimension(svm.num_total_sv, classes(&svm.classes), svm.num_attributes)
+    }
+}
+
+#[cfg(target_endian = "little")]
+impl From<&DenseSVMView<'_>> for DenseFeatures {
+    fn from(svm: &DenseSVMView<'_>) -> Self {
+        let classes = svm.classes.iter().map(|class| (class.label, class.num_support_vectors())).collect();
+
+        Self::with_dimension(svm.num_total_sv, classes, svm.num_attributes)
     }
 }
 
 impl From<&SparseSVM> for SparseFeatures {
     fn from(svm: &SparseSVM) -> Self {
-        Self::with_dimension(svm.num_total_sv, svm.classes.len(), svm.num_attributes)
+        Self::with_dimension(svm.num_total_sv, classes(&svm.classes), svm.num_attributes)
+    }
+}
+
+impl From<&PrecomputedSVM> for PrecomputedFeatures {
+    fn from(svm: &PrecomputedSVM) -> Self {
+        Self::with_dimension(svm.num_total_sv, classes(&svm.classes), svm.num_attributes)
     }
 }
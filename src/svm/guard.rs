@@ -0,0 +1,152 @@
+use crate::{
+    errors::Error,
+    sparse::SparseVector,
+    svm::{features::FeatureVector, predict::Predict},
+};
+use simd_aligned::{arch::f32x8, VecSimd};
+
+/// What a [`Guarded`] SVM does when it finds an invalid feature.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum InvalidFeaturePolicy {
+    /// Don't predict, but return [`Error::NonFiniteFeature`] or [`Error::FeatureOutOfRange`] naming the feature.
+    Error,
+
+    /// Replace the invalid feature with the given value, e.g., the feature's mean in the training data, and predict.
+    Substitute(f64),
+}
+
+/// Implemented by the feature storage of all [`FeatureVector`]s, so a [`Guarded`] SVM can inspect and replace features.
+#[doc(hidden)]
+pub trait GuardedFeatures {
+    /// Calls `f` with the index and value of each of the first `num_attributes` features, replacing the feature
+    /// if `f` returns a new value.
+    fn replace_features(&mut self, num_attributes: usize, f: impl FnMut(usize, f64) -> Result<Option<f64>, Error>) -> Result<(), Error>;
+}
+
+impl GuardedFeatures for VecSimd<f32x8> {
+    fn replace_features(&mut self, num_attributes: usize, mut f: impl FnMut(usize, f64) -> Result<Option<f64>, Error>) -> Result<(), Error> {
+        for (index, value) in self.flat_mut()[..num_attributes].iter_mut().enumerate() {
+            if let Some(replacement) = f(index, f64::from(*value))? {
+                *value = replacement as f32;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl GuardedFeatures for SparseVector<f32> {
+    fn replace_features(&mut self, _num_attributes: usize, mut f: impl FnMut(usize, f64) -> Result<Option<f64>, Error>) -> Result<(), Error> {
+        for (index, value) in self.iter_mut() {
+            if let Some(replacement) = f(index as usize, f64::from(*value))? {
+                *value = replacement as f32;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl GuardedFeatures for Vec<f64> {
+    fn replace_features(&mut self, num_attributes: usize, mut f: impl FnMut(usize, f64) -> Result<Option<f64>, Error>) -> Result<(), Error> {
+        for (index, value) in self[..num_attributes].iter_mut().enumerate() {
+            if let Some(replacement) = f(index, *value)? {
+                *value = replacement;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Wraps an SVM to check all features before predicting them.
+///
+/// A single `NaN` feature can turn all kernel values into `NaN`, which then silently produces a seemingly confident
+/// prediction. A [`Guarded`] SVM finds non-finite features (`NaN` and infinities) and, if a training range was given,
+/// features outside of it, and handles them according to its [`InvalidFeaturePolicy`]:
+///
+/// ```
+/// use ffsvm::{DenseSVM, Error, FeatureVector, Guarded, InvalidFeaturePolicy, Predict};
+/// # use ffsvm::SAMPLE_MODEL;
+///
+/// # fn main() -> Result<(), Error> {
+/// let svm = Guarded::new(DenseSVM::try_from(SAMPLE_MODEL)?, InvalidFeaturePolicy::Error);
+///
+/// let mut fv = FeatureVector::from(svm.svm());
+/// fv.features()[2] = f32::NAN;
+///
+/// assert!(matches!(svm.predict_value(&mut fv), Err(Error::NonFiniteFeature { index: 2, .. })));
+/// # Ok(())
+/// # }
+/// ```
+///
+/// For [`SparseSVM`](crate::SparseSVM)s only features that were set are checked, for [`PrecomputedSVM`](crate::PrecomputedSVM)s
+/// the kernel values are.
+#[derive(Clone, Debug)]
+pub struct Guarded<S> {
+    svm: S,
+    policy: InvalidFeaturePolicy,
+    range: Option<Vec<(f64, f64)>>,
+}
+
+impl<S> Guarded<S> {
+    /// Wraps the SVM, handling non-finite features according to `policy`.
+    pub const fn new(svm: S, policy: InvalidFeaturePolicy) -> Self {
+        Self { svm, policy, range: None }
+    }
+
+    /// Also treats features outside the training range as invalid.
+    ///
+    /// Entry `i` holds the inclusive `(min, max)` range of feature `i`, features past the end of `range` are not checked.
+    #[must_use]
+    pub fn with_training_range(mut self, range: Vec<(f64, f64)>) -> Self {
+        self.range = Some(range);
+        self
+    }
+
+    /// Returns the wrapped SVM, e.g., to create a [`FeatureVector`] for it.
+    pub const fn svm(&self) -> &S {
+        &self.svm
+    }
+
+    /// Returns the wrapped SVM.
+    pub fn into_inner(self) -> S {
+        self.svm
+    }
+
+    /// Checks all features of the problem, replacing or rejecting invalid ones.
+    fn guard<T: GuardedFeatures>(&self, problem: &mut FeatureVector<T>) -> Result<(), Error> {
+        let range = self.range.as_deref().unwrap_or_default();
+
+        problem.features.replace_features(problem.num_attributes, |index, value| {
+            let error = if !value.is_finite() {
+                Error::NonFiniteFeature { index, value }
+            } else if let Some((min, max)) = range.get(index).filter(|(min, max)| value < *min || value > *max) {
+                Error::FeatureOutOfRange { index, value, min: *min, max: *max }
+            } else {
+                return Ok(None);
+            };
+
+            match self.policy {
+                InvalidFeaturePolicy::Error => Err(error),
+                InvalidFeaturePolicy::Substitute(replacement) => Ok(Some(replacement)),
+            }
+        })
+    }
+}
+
+impl<S, T> Predict<T> for Guarded<S>
+where
+    S: Predict<T>,
+    T: GuardedFeatures,
+{
+    fn predict_value(&self, problem: &mut FeatureVector<T>) -> Result<(), Error> {
+        self.guard(problem)?;
+        self.svm.predict_value(problem)
+    }
+
+    fn predict_probability(&self, problem: &mut FeatureVector<T>) -> Result<(), Error> {
+        self.guard(problem)?;
+        self.svm.predict_probability(problem)
+    }
+}
@@ -1,8 +1,8 @@
-use std::convert::From;
+use std::{convert::From, ops::Range};
 
-use super::{KernelDense, KernelSparse};
+use super::{Kernel, KernelDense, KernelSparse};
 use crate::{
-    parser::ModelFile,
+    parser::{Header, ModelFile},
     sparse::{SparseMatrix, SparseVector},
 };
 
@@ -12,11 +12,18 @@ use simd_aligned::{arch::f32x8, traits::Simd, MatSimd, Rows, VecSimd};
 #[doc(hidden)]
 pub struct Linear {}
 
-impl KernelDense for Linear {
-    fn compute(&self, vectors: &MatSimd<f32x8, Rows>, feature: &VecSimd<f32x8>, output: &mut [f64]) {
-        for (i, sv) in vectors.row_iter().enumerate() {
+impl Kernel for Linear {
+    fn write_header(&self, header: &mut Header<'_>) {
+        header.kernel_type = "linear";
+    }
+}
+
+impl Linear {
+    /// Computes the kernel values for the given rows of support vectors.
+    #[inline]
+    fn compute_dense<'a>(vectors: impl Iterator<Item = &'a [f32x8]>, feature: &[f32x8], output: &mut [f64]) {
+        for (i, sv) in vectors.enumerate() {
             let mut sum = f32x8::splat(0.0);
-            let feature: &[f32x8] = feature;
 
             for (a, b) in sv.iter().zip(feature) {
                 sum += *a * *b;
@@ -25,11 +32,11 @@ impl KernelDense for Linear {
             output[i] = f64::from(sum.sum());
         }
     }
-}
 
-impl KernelSparse for Linear {
-    fn compute(&self, vectors: &SparseMatrix<f32>, feature: &SparseVector<f32>, output: &mut [f64]) {
-        for (i, sv) in vectors.row_iter().enumerate() {
+    /// Computes the kernel values for the given sparse support vectors.
+    #[inline]
+    fn compute_sparse<'a>(vectors: impl Iterator<Item = &'a SparseVector<f32>>, feature: &SparseVector<f32>, output: &mut [f64]) {
+        for (i, sv) in vectors.enumerate() {
             let mut sum = 0.0;
             let mut a_iter = sv.iter();
             let mut b_iter = feature.iter();
@@ -53,6 +60,30 @@ impl KernelSparse for Linear {
     }
 }
 
+impl KernelDense for Linear {
+    fn compute(&self, vectors: &MatSimd<f32x8, Rows>, feature: &VecSimd<f32x8>, output: &mut [f64]) {
+        Self::compute_dense(vectors.row_iter(), feature, output);
+    }
+
+    fn compute_rows(&self, vectors: &[f32x8], feature: &[f32x8], output: &mut [f64]) {
+        Self::compute_dense(vectors.chunks_exact(feature.len()), feature, output);
+    }
+
+    fn compute_range(&self, vectors: &MatSimd<f32x8, Rows>, rows: Range<usize>, feature: &[f32x8], output: &mut [f64]) {
+        Self::compute_dense(rows.map(|i| vectors.row(i)), feature, output);
+    }
+}
+
+impl KernelSparse for Linear {
+    fn compute(&self, vectors: &SparseMatrix<f32>, feature: &SparseVector<f32>, output: &mut [f64]) {
+        Self::compute_sparse(vectors.row_iter(), feature, output);
+    }
+
+    fn compute_range(&self, vectors: &SparseMatrix<f32>, rows: Range<usize>, feature: &SparseVector<f32>, output: &mut [f64]) {
+        Self::compute_sparse(rows.map(|i| vectors.row(i)), feature, output);
+    }
+}
+
 impl<'a> From<&'a ModelFile<'a>> for Linear {
     fn from(_model: &'a ModelFile<'a>) -> Self {
         Self {}
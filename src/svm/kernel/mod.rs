@@ -4,23 +4,46 @@ mod rbf;
 mod sigmoid;
 
 pub use self::{linear::*, poly::*, rbf::*, sigmoid::*};
-use crate::sparse::{SparseMatrix, SparseVector};
+use crate::{
+    parser::Header,
+    sparse::{SparseMatrix, SparseVector},
+};
 use simd_aligned::{arch::f32x8, MatSimd, Rows, VecSimd};
+use std::ops::Range;
 
 /// Base trait for kernels
 #[doc(hidden)]
-pub trait KernelDense
+pub trait Kernel
 where
     Self: Send + Sync,
+{
+    /// Writes the `kernel_type` and the parameters of this kernel into a model header.
+    fn write_header(&self, header: &mut Header<'_>);
+}
+
+/// Base trait for kernels
+#[doc(hidden)]
+pub trait KernelDense
+where
+    Self: Kernel,
 {
     fn compute(&self, vectors: &MatSimd<f32x8, Rows>, feature: &VecSimd<f32x8>, output: &mut [f64]);
+
+    /// Same as `compute`, but for support vectors stored as consecutive rows of `feature.len()` SIMD vectors.
+    fn compute_rows(&self, vectors: &[f32x8], feature: &[f32x8], output: &mut [f64]);
+
+    /// Same as `compute`, but only for the support vectors in `rows`, with `output` holding one entry per row.
+    fn compute_range(&self, vectors: &MatSimd<f32x8, Rows>, rows: Range<usize>, feature: &[f32x8], output: &mut [f64]);
 }
 
 /// Base trait for kernels
 #[doc(hidden)]
 pub trait KernelSparse
 where
-    Self: Send + Sync,
+    Self: Kernel,
 {
     fn compute(&self, vectors: &SparseMatrix<f32>, feature: &SparseVector<f32>, output: &mut [f64]);
+
+    /// Same as `compute`, but only for the support vectors in `rows`, with `output` holding one entry per row.
+    fn compute_range(&self, vectors: &SparseMatrix<f32>, rows: Range<usize>, feature: &SparseVector<f32>, output: &mut [f64]);
 }
@@ -1,9 +1,12 @@
-use std::convert::{From, TryFrom};
+use std::{
+    convert::{From, TryFrom},
+    ops::Range,
+};
 
-use super::{KernelDense, KernelSparse};
+use super::{Kernel, KernelDense, KernelSparse};
 use crate::{
     errors::Error,
-    parser::ModelFile,
+    parser::{Header, ModelFile},
     sparse::{SparseMatrix, SparseVector},
 };
 
@@ -16,12 +19,11 @@ pub struct Rbf {
 }
 
 #[inline]
-fn compute_core(rbf: Rbf, vectors: &MatSimd<f32x8, Rows>, feature: &VecSimd<f32x8>, output: &mut [f64]) {
+fn compute_core<'a>(rbf: Rbf, vectors: impl Iterator<Item = &'a [f32x8]>, feature: &[f32x8], output: &mut [f64]) {
     // According to Instruments, for realistic SVMs and feature vectors, the VAST majority of our
     // CPU time is spent in this loop.
-    for (i, sv) in vectors.row_iter().enumerate() {
+    for (i, sv) in vectors.enumerate() {
         let mut sum = f32x8::splat(0.0);
-        let feature: &[f32x8] = feature;
 
         for (a, b) in sv.iter().zip(feature) {
             sum += (*a - *b) * (*a - *b);
@@ -34,20 +36,32 @@ fn compute_core(rbf: Rbf, vectors: &MatSimd<f32x8, Rows>, feature: &VecSimd<f32x
     }
 }
 
-#[inline]
-fn compute(rbf: Rbf, vectors: &MatSimd<f32x8, Rows>, feature: &VecSimd<f32x8>, output: &mut [f64]) {
-    compute_core(rbf, vectors, feature, output);
+impl Kernel for Rbf {
+    fn write_header(&self, header: &mut Header<'_>) {
+        header.kernel_type = "rbf";
+        header.gamma = Some(self.gamma);
+    }
 }
 
 impl KernelDense for Rbf {
     fn compute(&self, vectors: &MatSimd<f32x8, Rows>, feature: &VecSimd<f32x8>, output: &mut [f64]) {
-        compute(*self, vectors, feature, output);
+        compute_core(*self, vectors.row_iter(), feature, output);
+    }
+
+    fn compute_rows(&self, vectors: &[f32x8], feature: &[f32x8], output: &mut [f64]) {
+        compute_core(*self, vectors.chunks_exact(feature.len()), feature, output);
+    }
+
+    fn compute_range(&self, vectors: &MatSimd<f32x8, Rows>, rows: Range<usize>, feature: &[f32x8], output: &mut [f64]) {
+        compute_core(*self, rows.map(|i| vectors.row(i)), feature, output);
     }
 }
 
-impl KernelSparse for Rbf {
-    fn compute(&self, vectors: &SparseMatrix<f32>, feature: &SparseVector<f32>, output: &mut [f64]) {
-        for (i, sv) in vectors.row_iter().enumerate() {
+impl Rbf {
+    /// Computes the kernel values for the given sparse support vectors.
+    #[inline]
+    fn compute_sparse<'a>(self, vectors: impl Iterator<Item = &'a SparseVector<f32>>, feature: &SparseVector<f32>, output: &mut [f64]) {
+        for (i, sv) in vectors.enumerate() {
             let mut sum = 0.0;
             let mut a_iter = sv.iter();
             let mut b_iter = feature.iter();
@@ -70,6 +84,15 @@ impl KernelSparse for Rbf {
                         sum += y * y;
                         b = b_iter.next();
                     }
+                    // Once one side is exhausted the remaining attributes of the other still contribute.
+                    (Some((_, x)), None) => {
+                        sum += x * x;
+                        a = a_iter.next();
+                    }
+                    (None, Some((_, y))) => {
+                        sum += y * y;
+                        b = b_iter.next();
+                    }
                     _ => break f64::from((-self.gamma * sum).exp()),
                 }
             }
@@ -77,6 +100,16 @@ impl KernelSparse for Rbf {
     }
 }
 
+impl KernelSparse for Rbf {
+    fn compute(&self, vectors: &SparseMatrix<f32>, feature: &SparseVector<f32>, output: &mut [f64]) {
+        self.compute_sparse(vectors.row_iter(), feature, output);
+    }
+
+    fn compute_range(&self, vectors: &SparseMatrix<f32>, rows: Range<usize>, feature: &SparseVector<f32>, output: &mut [f64]) {
+        self.compute_sparse(rows.map(|i| vectors.row(i)), feature, output);
+    }
+}
+
 impl<'a, 'b> TryFrom<&'a ModelFile<'b>> for Rbf {
     type Error = Error;
 
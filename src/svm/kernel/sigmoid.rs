@@ -1,9 +1,12 @@
-use std::convert::{From, TryFrom};
+use std::{
+    convert::{From, TryFrom},
+    ops::Range,
+};
 
-use super::{KernelDense, KernelSparse};
+use super::{Kernel, KernelDense, KernelSparse};
 use crate::{
     errors::Error,
-    parser::ModelFile,
+    parser::{Header, ModelFile},
     sparse::{SparseMatrix, SparseVector},
 };
 
@@ -16,11 +19,20 @@ pub struct Sigmoid {
     coef0: f32,
 }
 
-impl KernelDense for Sigmoid {
-    fn compute(&self, vectors: &MatSimd<f32x8, Rows>, feature: &VecSimd<f32x8>, output: &mut [f64]) {
-        for (i, sv) in vectors.row_iter().enumerate() {
+impl Kernel for Sigmoid {
+    fn write_header(&self, header: &mut Header<'_>) {
+        header.kernel_type = "sigmoid";
+        header.gamma = Some(self.gamma);
+        header.coef0 = Some(self.coef0);
+    }
+}
+
+impl Sigmoid {
+    /// Computes the kernel values for the given rows of support vectors.
+    #[inline]
+    fn compute_dense<'a>(self, vectors: impl Iterator<Item = &'a [f32x8]>, feature: &[f32x8], output: &mut [f64]) {
+        for (i, sv) in vectors.enumerate() {
             let mut sum = f32x8::splat(0.0);
-            let feature: &[f32x8] = feature;
 
             for (a, b) in sv.iter().zip(feature) {
                 sum += *a * *b;
@@ -29,11 +41,11 @@ impl KernelDense for Sigmoid {
             output[i] = (f64::from(self.gamma.mul_add(sum.sum(), self.coef0))).tanh();
         }
     }
-}
 
-impl KernelSparse for Sigmoid {
-    fn compute(&self, vectors: &SparseMatrix<f32>, feature: &SparseVector<f32>, output: &mut [f64]) {
-        for (i, sv) in vectors.row_iter().enumerate() {
+    /// Computes the kernel values for the given sparse support vectors.
+    #[inline]
+    fn compute_sparse<'a>(self, vectors: impl Iterator<Item = &'a SparseVector<f32>>, feature: &SparseVector<f32>, output: &mut [f64]) {
+        for (i, sv) in vectors.enumerate() {
             let mut sum = 0.0;
             let mut a_iter = sv.iter();
             let mut b_iter = feature.iter();
@@ -57,6 +69,30 @@ impl KernelSparse for Sigmoid {
     }
 }
 
+impl KernelDense for Sigmoid {
+    fn compute(&self, vectors: &MatSimd<f32x8, Rows>, feature: &VecSimd<f32x8>, output: &mut [f64]) {
+        self.compute_dense(vectors.row_iter(), feature, output);
+    }
+
+    fn compute_rows(&self, vectors: &[f32x8], feature: &[f32x8], output: &mut [f64]) {
+        self.compute_dense(vectors.chunks_exact(feature.len()), feature, output);
+    }
+
+    fn compute_range(&self, vectors: &MatSimd<f32x8, Rows>, rows: Range<usize>, feature: &[f32x8], output: &mut [f64]) {
+        self.compute_dense(rows.map(|i| vectors.row(i)), feature, output);
+    }
+}
+
+impl KernelSparse for Sigmoid {
+    fn compute(&self, vectors: &SparseMatrix<f32>, feature: &SparseVector<f32>, output: &mut [f64]) {
+        self.compute_sparse(vectors.row_iter(), feature, output);
+    }
+
+    fn compute_range(&self, vectors: &SparseMatrix<f32>, rows: Range<usize>, feature: &SparseVector<f32>, output: &mut [f64]) {
+        self.compute_sparse(rows.map(|i| vectors.row(i)), feature, output);
+    }
+}
+
 impl<'a, 'b> TryFrom<&'a ModelFile<'b>> for Sigmoid {
     type Error = Error;
 
@@ -0,0 +1,90 @@
+/// Laplace distribution of the target value of an ε-SVR or ν-SVR, given a predicted value.
+///
+/// libSVM models the difference between target and predicted values of regression SVMs trained with probability
+/// estimates (`svm-train -b 1`) as a Laplace distribution with density `e^(-|z| / sigma) / (2 sigma)`. Centered at a
+/// predicted value, it gives densities, probabilities and prediction intervals of the target value:
+///
+/// ```
+/// use ffsvm::Laplace;
+///
+/// // What `svm.svr_distribution(fv.raw_value())` returns for an SVR with `sigma` 0.5 that predicted 3.
+/// let distribution = Laplace::new(3.0, 0.5);
+/// let (low, high) = distribution.interval(0.95).unwrap();
+///
+/// assert!((distribution.cdf(high) - distribution.cdf(low) - 0.95).abs() < 1e-12);
+/// assert_eq!(distribution.cdf(3.0), 0.5);
+/// ```
+///
+/// Use [`DenseSVM::svr_distribution`](crate::DenseSVM::svr_distribution) or the same method of the other SVMs to
+/// obtain the distribution for a prediction.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Laplace {
+    location: f64,
+    scale: f64,
+}
+
+impl Laplace {
+    /// Creates a Laplace distribution centered at `location`, with scale `scale`, e.g., an SVR's `sigma`. The scale
+    /// must be positive, otherwise densities and probabilities are `NaN`.
+    #[must_use]
+    pub const fn new(location: f64, scale: f64) -> Self {
+        Self { location, scale }
+    }
+
+    /// Returns the center of the distribution, i.e., the predicted value.
+    #[must_use]
+    pub const fn location(&self) -> f64 {
+        self.location
+    }
+
+    /// Returns the scale of the distribution, i.e., the SVR's `sigma`.
+    #[must_use]
+    pub const fn scale(&self) -> f64 {
+        self.scale
+    }
+
+    /// Returns the probability density at `x`.
+    #[must_use]
+    pub fn density(&self, x: f64) -> f64 {
+        (-(x - self.location).abs() / self.scale).exp() / (2.0 * self.scale)
+    }
+
+    /// Returns the probability of a value of at most `x`.
+    #[must_use]
+    pub fn cdf(&self, x: f64) -> f64 {
+        let z = (x - self.location) / self.scale;
+
+        if z < 0.0 {
+            z.exp() / 2.0
+        } else {
+            1.0 - (-z).exp() / 2.0
+        }
+    }
+
+    /// Returns the value `x` for which [`Laplace::cdf`] is `p`, or `None` if `p` is not in `[0, 1]`.
+    #[must_use]
+    pub fn quantile(&self, p: f64) -> Option<f64> {
+        if !(0.0..=1.0).contains(&p) {
+            return None;
+        }
+
+        if p < 0.5 {
+            Some(self.scale.mul_add((2.0 * p).ln(), self.location))
+        } else {
+            Some(self.scale.mul_add(-(2.0 * (1.0 - p)).ln(), self.location))
+        }
+    }
+
+    /// Returns the smallest interval around the location that contains a value with probability `level`, e.g.,
+    /// `0.95` for a 95% prediction interval. Returns `None` if `level` is not in `[0, 1]`.
+    #[must_use]
+    pub fn interval(&self, level: f64) -> Option<(f64, f64)> {
+        if !(0.0..=1.0).contains(&level) {
+            return None;
+        }
+
+        let half_width = -self.scale * (1.0 - level).ln();
+
+        Some((self.location - half_width, self.location + half_width))
+    }
+}
@@ -1,10 +1,16 @@
+pub mod calibrate;
 pub mod class;
 pub mod core;
 pub mod features;
+pub mod guard;
 pub mod kernel;
+pub mod laplace;
+#[cfg(feature = "rayon")]
+pub mod parallel;
 pub mod predict;
 
-use crate::vectors::Triangular;
+use crate::{errors::Error, vectors::Triangular};
+use std::convert::TryFrom;
 
 #[derive(Clone, Debug, Default)]
 pub struct Probabilities {
@@ -13,13 +19,60 @@ pub struct Probabilities {
     pub(crate) b: Triangular<f64>,
 }
 
-/// Classifier type.
-#[doc(hidden)]
+/// Classifier type, the `svm_type` of a libSVM model.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum SVMType {
+    /// C-support vector classification, `c_svc`.
     CSvc,
+
+    /// ν-support vector classification, `nu_svc`.
     NuSvc,
+
+    /// ε-support vector regression, `epsilon_svr`.
     ESvr,
+
+    /// ν-support vector regression, `nu_svr`.
     NuSvr,
+
+    /// One-class SVM for novelty detection, `one_class`.
+    OneClass,
+}
+
+impl SVMType {
+    /// Returns the name of this type as used by the `svm_type` line of a libSVM model.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::CSvc => "c_svc",
+            Self::NuSvc => "nu_svc",
+            Self::ESvr => "epsilon_svr",
+            Self::NuSvr => "nu_svr",
+            Self::OneClass => "one_class",
+        }
+    }
 }
 
-pub use self::core::{dense::DenseSVM, sparse::SparseSVM};
+impl TryFrom<&str> for SVMType {
+    type Error = Error;
+
+    fn try_from(svm_type: &str) -> Result<Self, Error> {
+        match svm_type {
+            "c_svc" => Ok(Self::CSvc),
+            "nu_svc" => Ok(Self::NuSvc),
+            "epsilon_svr" => Ok(Self::ESvr),
+            "nu_svr" => Ok(Self::NuSvr),
+            "one_class" => Ok(Self::OneClass),
+            _ => Err(Error::UnsupportedSvmType(svm_type.to_owned())),
+        }
+    }
+}
+
+pub use self::core::{
+    dense::{AttributePolicy, DenseSVM},
+    precomputed::PrecomputedSVM,
+    sparse::SparseSVM,
+    unified::{BackendPolicy, Features, Svm, SvmFeatures},
+};
+
+#[cfg(target_endian = "little")]
+pub use self::core::view::DenseSVMView;
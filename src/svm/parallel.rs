@@ -0,0 +1,76 @@
+use crate::{
+    errors::Error,
+    svm::{features::FeatureVector, predict::Predict},
+};
+use rayon::prelude::*;
+use std::ops::Range;
+
+/// Number of attribute evaluations, i.e., support vectors times attributes, a parallel chunk of kernel values should at least have.
+const CHUNK_ATTRIBUTES: usize = 16 * 1024;
+
+/// Returns how many support vectors each parallel chunk of a class should have, or `None` if the class is too small to be split.
+pub fn rows_per_chunk(num_sv: usize, num_attributes: usize) -> Option<usize> {
+    let rows = (CHUNK_ATTRIBUTES / num_attributes.max(1)).max(1);
+
+    (num_sv > rows).then_some(rows)
+}
+
+/// Splits the first `num_sv` kernel values of `output` into chunks of `rows` and has `compute` fill them in parallel.
+///
+/// Each kernel value is computed exactly like it would be serially, so results are bit-identical.
+pub fn compute_chunks(output: &mut [f64], num_sv: usize, rows: usize, compute: impl Fn(Range<usize>, &mut [f64]) + Sync) {
+    output[..num_sv].par_chunks_mut(rows).enumerate().for_each(|(i, output)| {
+        let start = i * rows;
+        compute(start..start + output.len(), output);
+    });
+}
+
+/// Implemented for all SVMs when the `rayon` feature is enabled, predicts many [`FeatureVector`]s in parallel.
+///
+/// Each problem is predicted exactly like [`Predict::predict_value`] or [`Predict::predict_probability`] would,
+/// so the results are bit-identical to predicting them one by one.
+///
+/// ```
+/// use ffsvm::{DenseSVM, FeatureVector, ParallelPredict};
+/// # use ffsvm::SAMPLE_MODEL;
+///
+/// # fn main() -> Result<(), ffsvm::Error> {
+/// let svm = DenseSVM::try_from(SAMPLE_MODEL)?;
+/// let mut problems = vec![FeatureVector::from(&svm); 100];
+///
+/// svm.par_predict_values(&mut problems)?;
+/// # Ok(())
+/// # }
+/// ```
+pub trait ParallelPredict<T>
+where
+    Self: Predict<T>,
+{
+    /// Predicts the values of all `problems` in parallel, see [`Predict::predict_value`].
+    ///
+    /// # Errors
+    ///
+    /// If predicting any problem fails one of the errors is returned, the other problems may or may not have been predicted.
+    fn par_predict_values(&self, problems: &mut [FeatureVector<T>]) -> Result<(), Error>;
+
+    /// Predicts the probabilities of all `problems` in parallel, see [`Predict::predict_probability`].
+    ///
+    /// # Errors
+    ///
+    /// If predicting any problem fails one of the errors is returned, the other problems may or may not have been predicted.
+    fn par_predict_probabilities(&self, problems: &mut [FeatureVector<T>]) -> Result<(), Error>;
+}
+
+impl<S, T> ParallelPredict<T> for S
+where
+    S: Predict<T>,
+    T: Send,
+{
+    fn par_predict_values(&self, problems: &mut [FeatureVector<T>]) -> Result<(), Error> {
+        problems.par_iter_mut().try_for_each(|problem| self.predict_value(problem))
+    }
+
+    fn par_predict_probabilities(&self, problems: &mut [FeatureVector<T>]) -> Result<(), Error> {
+        problems.par_iter_mut().try_for_each(|problem| self.predict_probability(problem))
+    }
+}
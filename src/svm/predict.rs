@@ -1,6 +1,6 @@
 use crate::{errors::Error, svm::features::FeatureVector};
 
-/// Implemented by [`DenseSVM`](crate::DenseSVM) and [`SparseSVM`](crate::SparseSVM) to predict a [`FeatureVector`].
+/// Implemented by [`DenseSVM`](crate::DenseSVM), [`SparseSVM`](crate::SparseSVM) and [`PrecomputedSVM`](crate::PrecomputedSVM) to predict a [`FeatureVector`].
 ///
 /// # Predicting a label
 ///
@@ -20,6 +20,8 @@ use crate::{errors::Error, svm::features::FeatureVector};
 /// If the libSVM model was trained with probability estimates FFSVM can not only predict the
 /// label, but it can also give information about the likelihood distribution of all classes.
 /// This can be helpful if you want to consider alternatives.
+/// Models trained without probability estimates can be calibrated on labeled samples instead, see
+/// [`DenseSVM::calibrate_probabilities`](crate::DenseSVM::calibrate_probabilities).
 ///
 /// Probabilities are estimated like this:
 ///
@@ -0,0 +1,102 @@
+use crate::{data::DataReader, errors::Error, sparse::SparseVector, svm::features::FeatureVector};
+use std::{convert::TryFrom, io::BufRead};
+
+/// Labeled samples to train on, see [`Trainer`](crate::Trainer).
+///
+/// Datasets are usually read from files in libSVM's `label index:value index:value ...` format, which keep their
+/// indices as written, but can also be built sample by sample:
+///
+/// ```
+/// use ffsvm::{Dataset, SparseVector};
+///
+/// let mut data = Dataset::try_from("1 0:0.5 1:0.25\n-1 1:-0.75\n")?;
+/// data.push(1.0, [(0, 0.25), (1, 0.5)].into_iter().collect());
+///
+/// assert_eq!(data.len(), 3);
+/// assert_eq!(data.labels(), &[1.0, -1.0, 1.0]);
+/// # Ok::<(), ffsvm::Error>(())
+/// ```
+///
+/// For classification, labels are truncated to integers, like `svm-train` does.
+#[derive(Clone, Debug, Default)]
+pub struct Dataset {
+    labels: Vec<f64>,
+    samples: Vec<SparseVector<f64>>,
+}
+
+impl Dataset {
+    /// Creates an empty dataset.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { labels: Vec::new(), samples: Vec::new() }
+    }
+
+    /// Reads all samples of a data file in libSVM's format.
+    ///
+    /// # Errors
+    ///
+    /// Fails if reading fails, or a line can't be parsed, see [`DataReader`].
+    pub fn read(reader: impl BufRead) -> Result<Self, Error> {
+        let prototype = FeatureVector::with_features(SparseVector::new(), 0, Vec::new(), 0);
+
+        DataReader::with_prototype(reader, prototype)
+            .map(|sample| sample.map(|(label, problem)| (label, problem.replace_features(()).0)))
+            .collect()
+    }
+
+    /// Adds a sample with the given label.
+    pub fn push(&mut self, label: f64, features: SparseVector<f64>) {
+        self.labels.push(label);
+        self.samples.push(features);
+    }
+
+    /// Returns the number of samples.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.labels.len()
+    }
+
+    /// Returns `true` if there are no samples.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.labels.is_empty()
+    }
+
+    /// Returns the labels of all samples.
+    #[must_use]
+    pub fn labels(&self) -> &[f64] {
+        &self.labels
+    }
+
+    /// Returns the features of all samples.
+    #[must_use]
+    pub fn samples(&self) -> &[SparseVector<f64>] {
+        &self.samples
+    }
+
+    /// Returns the largest feature index of any sample, `0` if there are no features.
+    pub(crate) fn max_index(&self) -> usize {
+        self.samples.iter().filter_map(|x| x.iter().last()).map(|(index, _)| index as usize).max().unwrap_or_default()
+    }
+}
+
+impl TryFrom<&str> for Dataset {
+    type Error = Error;
+
+    /// Parses samples in libSVM's data format.
+    fn try_from(input: &str) -> Result<Self, Error> {
+        Self::read(input.as_bytes())
+    }
+}
+
+impl FromIterator<(f64, SparseVector<f64>)> for Dataset {
+    fn from_iter<I: IntoIterator<Item = (f64, SparseVector<f64>)>>(iter: I) -> Self {
+        let mut data = Self::new();
+
+        for (label, features) in iter {
+            data.push(label, features);
+        }
+
+        data
+    }
+}
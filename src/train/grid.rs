@@ -0,0 +1,229 @@
+use crate::{
+    errors::Error,
+    train::{validation::Score, Dataset, Folds, Trainer},
+};
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+/// Searches the best parameters of a [`Trainer`] by cross-validating all combinations of given values, like
+/// libSVM's `tools/grid.py`.
+///
+/// Each parameter without values keeps the value of the trainer. Ranges on a log2 scale work like the `-log2c` and
+/// `-log2g` options of `grid.py`, which by default searches `C = 2^-5, 2^-3, ..., 2^15` and
+/// `gamma = 2^3, 2^1, ..., 2^-15`:
+///
+/// ```
+/// use ffsvm::{Dataset, Error, Folds, GridSearch, Trainer};
+///
+/// # fn main() -> Result<(), Error> {
+/// let data = Dataset::try_from("1 0:1\n1 0:0.8\n1 0:0.5\n1 0:0.9\n-1 0:-1\n-1 0:-0.7\n-1 0:-0.2\n-1 0:-0.9\n")?;
+///
+/// let result = GridSearch::new(Trainer::default())
+///     .with_log2_c(-1.0, 5.0, 2.0)
+///     .with_log2_gamma(1.0, -3.0, -2.0)
+///     .with_folds(Folds::stratified(4))
+///     .run(&data)?;
+///
+/// assert_eq!(result.points().len(), 4 * 3);
+/// println!("best C {}, gamma {:?}: {:?}", result.best().trainer().c(), result.best().trainer().gamma(), result.best().score());
+///
+/// let model = result.best().trainer().train(&data)?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Grid points are cross-validated in parallel, on Rayon's thread pool if the `rayon` feature is enabled, and on
+/// one thread per core otherwise.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GridSearch {
+    trainer: Trainer,
+    folds: Folds,
+    c: Vec<f64>,
+    gamma: Vec<f64>,
+    degree: Vec<u32>,
+    coef0: Vec<f64>,
+    nu: Vec<f64>,
+}
+
+impl GridSearch {
+    /// Creates a search around the parameters of `trainer`, cross-validating with 5 stratified folds like `grid.py`.
+    #[must_use]
+    pub const fn new(trainer: Trainer) -> Self {
+        Self { trainer, folds: Folds::stratified(5), c: Vec::new(), gamma: Vec::new(), degree: Vec::new(), coef0: Vec::new(), nu: Vec::new() }
+    }
+
+    /// Sets the folds each grid point is cross-validated with.
+    #[must_use]
+    pub const fn with_folds(mut self, folds: Folds) -> Self {
+        self.folds = folds;
+        self
+    }
+
+    /// Sets the values of `C` to search.
+    #[must_use]
+    pub fn with_c(mut self, values: impl IntoIterator<Item = f64>) -> Self {
+        self.c = values.into_iter().collect();
+        self
+    }
+
+    /// Searches `C = 2^begin, 2^(begin + step), ...` up to `2^end`, `-log2c begin,end,step` of `grid.py`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `step` is `0`, or leads away from `end`.
+    #[must_use]
+    pub fn with_log2_c(self, begin: f64, end: f64, step: f64) -> Self {
+        self.with_c(log2_range(begin, end, step))
+    }
+
+    /// Sets the values of `gamma` to search.
+    #[must_use]
+    pub fn with_gamma(mut self, values: impl IntoIterator<Item = f64>) -> Self {
+        self.gamma = values.into_iter().collect();
+        self
+    }
+
+    /// Searches `gamma = 2^begin, 2^(begin + step), ...` up to `2^end`, `-log2g begin,end,step` of `grid.py`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `step` is `0`, or leads away from `end`.
+    #[must_use]
+    pub fn with_log2_gamma(self, begin: f64, end: f64, step: f64) -> Self {
+        self.with_gamma(log2_range(begin, end, step))
+    }
+
+    /// Sets the values of `degree` to search.
+    #[must_use]
+    pub fn with_degree(mut self, values: impl IntoIterator<Item = u32>) -> Self {
+        self.degree = values.into_iter().collect();
+        self
+    }
+
+    /// Sets the values of `coef0` to search.
+    #[must_use]
+    pub fn with_coef0(mut self, values: impl IntoIterator<Item = f64>) -> Self {
+        self.coef0 = values.into_iter().collect();
+        self
+    }
+
+    /// Sets the values of `nu` to search.
+    #[must_use]
+    pub fn with_nu(mut self, values: impl IntoIterator<Item = f64>) -> Self {
+        self.nu = values.into_iter().collect();
+        self
+    }
+
+    /// Cross-validates all grid points on `data`.
+    ///
+    /// # Errors
+    ///
+    /// Fails like [`Trainer::cross_validate`] for the first grid point that can't be cross-validated, e.g., because
+    /// `nu` is infeasible for the data of a ν-SVC.
+    pub fn run(&self, data: &Dataset) -> Result<GridSearchResult, Error> {
+        let validate = |trainer: &Trainer| {
+            let score = trainer.cross_validate(data, self.folds)?.score();
+            Ok(GridPoint { trainer: trainer.clone(), score })
+        };
+
+        let trainers = self.trainers();
+
+        #[cfg(feature = "rayon")]
+        let points = trainers.par_iter().map(validate).collect::<Result<Vec<_>, Error>>()?;
+
+        #[cfg(not(feature = "rayon"))]
+        let points = map_threaded(&trainers, validate).into_iter().collect::<Result<Vec<_>, Error>>()?;
+
+        // Of equally good points the first one wins, i.e., the one with the smallest index in the grid.
+        let best = (1..points.len()).fold(0, |best, i| if points[i].score.is_better_than(points[best].score) { i } else { best });
+
+        Ok(GridSearchResult { points, best })
+    }
+
+    /// Returns a trainer for each grid point, varying `C` slowest and `nu` fastest.
+    fn trainers(&self) -> Vec<Trainer> {
+        let trainers = vec![self.trainer.clone()];
+        let trainers = vary(trainers, &self.c, Trainer::with_c);
+        let trainers = vary(trainers, &self.gamma, Trainer::with_gamma);
+        let trainers = vary(trainers, &self.degree, Trainer::with_degree);
+        let trainers = vary(trainers, &self.coef0, Trainer::with_coef0);
+
+        vary(trainers, &self.nu, Trainer::with_nu)
+    }
+}
+
+/// Parameters of one grid point and their cross-validation score.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GridPoint {
+    trainer: Trainer,
+    score: Score,
+}
+
+impl GridPoint {
+    /// Returns the trainer with the parameters of this point.
+    #[must_use]
+    pub const fn trainer(&self) -> &Trainer {
+        &self.trainer
+    }
+
+    /// Returns the accuracy or mean squared error of the cross-validation.
+    #[must_use]
+    pub const fn score(&self) -> Score {
+        self.score
+    }
+}
+
+/// Result of a [`GridSearch`], all grid points and the best one.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GridSearchResult {
+    points: Vec<GridPoint>,
+    best: usize,
+}
+
+impl GridSearchResult {
+    /// Returns all grid points in the order they were searched in.
+    #[must_use]
+    pub fn points(&self) -> &[GridPoint] {
+        &self.points
+    }
+
+    /// Returns the point with the highest accuracy or lowest mean squared error.
+    #[must_use]
+    pub fn best(&self) -> &GridPoint {
+        &self.points[self.best]
+    }
+}
+
+/// Returns `2^x` for `x = begin, begin + step, ...` up to `end`, like `range_f` of `grid.py`.
+fn log2_range(begin: f64, end: f64, step: f64) -> Vec<f64> {
+    assert!(step != 0.0, "Step must not be 0.");
+    assert!((end - begin) * step >= 0.0, "Step must lead from begin to end.");
+
+    // Counting steps rather than adding them up keeps rounding errors from dropping `end`.
+    let steps = ((end - begin) / step + 1e-9).floor();
+
+    (0..=steps as i64).map(|i| (i as f64).mul_add(step, begin).exp2()).collect()
+}
+
+/// Replaces each trainer by one trainer per value, with the value set by `set`.
+fn vary<T: Copy>(trainers: Vec<Trainer>, values: &[T], set: fn(Trainer, T) -> Trainer) -> Vec<Trainer> {
+    if values.is_empty() {
+        return trainers;
+    }
+
+    trainers.into_iter().flat_map(|trainer| values.iter().map(move |x| set(trainer.clone(), *x))).collect()
+}
+
+/// Maps `items` on one scoped thread per core.
+#[cfg(not(feature = "rayon"))]
+fn map_threaded<T: Sync, U: Send>(items: &[T], f: impl Fn(&T) -> U + Sync) -> Vec<U> {
+    let threads = std::thread::available_parallelism().map_or(1, std::num::NonZeroUsize::get);
+    let chunk_size = items.len().div_ceil(threads).max(1);
+
+    std::thread::scope(|scope| {
+        let handles = items.chunks(chunk_size).map(|chunk| scope.spawn(|| chunk.iter().map(&f).collect::<Vec<_>>())).collect::<Vec<_>>();
+
+        handles.into_iter().flat_map(|handle| handle.join().unwrap_or_else(|panic| std::panic::resume_unwind(panic))).collect()
+    })
+}
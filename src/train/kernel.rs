@@ -0,0 +1,340 @@
+use crate::{sparse::SparseVector, train::KernelType};
+use std::{cmp::Ordering, mem::size_of};
+
+/// Kernel function and parameters, with `gamma` resolved for the training data.
+#[derive(Copy, Clone, Debug)]
+pub struct Parameters {
+    pub kernel_type: KernelType,
+    pub degree: u32,
+    pub gamma: f64,
+    pub coef0: f64,
+}
+
+/// Computes the dot product of two sparse vectors.
+fn dot(a: &SparseVector<f64>, b: &SparseVector<f64>) -> f64 {
+    let mut sum = 0.0;
+    let mut a_iter = a.iter();
+    let mut b_iter = b.iter();
+
+    let (mut a, mut b) = (a_iter.next(), b_iter.next());
+
+    while let (Some((i_a, x)), Some((i_b, y))) = (a, b) {
+        match i_a.cmp(&i_b) {
+            Ordering::Equal => {
+                sum += x * y;
+                a = a_iter.next();
+                b = b_iter.next();
+            }
+            Ordering::Less => a = a_iter.next(),
+            Ordering::Greater => b = b_iter.next(),
+        }
+    }
+
+    sum
+}
+
+/// Evaluates the kernel function between training samples, like libSVM's `Kernel`.
+pub struct Kernel<'a> {
+    x: Vec<&'a SparseVector<f64>>,
+
+    /// The squared norm of each sample, only needed for RBF kernels.
+    x_square: Vec<f64>,
+
+    parameters: Parameters,
+}
+
+impl<'a> Kernel<'a> {
+    pub fn new(x: &[&'a SparseVector<f64>], parameters: Parameters) -> Self {
+        let x_square = match parameters.kernel_type {
+            KernelType::Rbf => x.iter().map(|x| dot(x, x)).collect(),
+            _ => Vec::new(),
+        };
+
+        Self { x: x.to_vec(), x_square, parameters }
+    }
+
+    /// Returns the kernel value of samples `i` and `j`.
+    #[allow(clippy::suboptimal_flops)] // Same formulas as libSVM, for the same results.
+    pub fn evaluate(&self, i: usize, j: usize) -> f64 {
+        let Parameters { kernel_type, degree, gamma, coef0 } = self.parameters;
+
+        match kernel_type {
+            KernelType::Linear => dot(self.x[i], self.x[j]),
+            KernelType::Poly => (gamma * dot(self.x[i], self.x[j]) + coef0).powi(degree as i32),
+            KernelType::Rbf => (-gamma * (self.x_square[i] + self.x_square[j] - 2.0 * dot(self.x[i], self.x[j]))).exp(),
+            KernelType::Sigmoid => (gamma * dot(self.x[i], self.x[j]) + coef0).tanh(),
+        }
+    }
+
+    pub fn swap_index(&mut self, i: usize, j: usize) {
+        self.x.swap(i, j);
+
+        if !self.x_square.is_empty() {
+            self.x_square.swap(i, j);
+        }
+    }
+}
+
+/// Least recently used cache of kernel matrix columns, like libSVM's `Cache`.
+///
+/// Columns are computed lazily, so each holds the first `n` values of its column for some `n`.
+pub struct Cache {
+    /// Number of values that can still be cached.
+    available: usize,
+
+    columns: Vec<Vec<f32>>,
+
+    /// Doubly linked list of all cached columns, least recently used first, with the sentinel at index `l`.
+    prev: Vec<usize>,
+    next: Vec<usize>,
+}
+
+impl Cache {
+    /// Creates a cache for `l` columns of at most `l` values using about `bytes` of memory, but always enough
+    /// for two full columns.
+    pub fn new(l: usize, bytes: usize) -> Self {
+        let overhead = l * (size_of::<Vec<f32>>() + 2 * size_of::<usize>()) / size_of::<f32>();
+        let available = (bytes / size_of::<f32>()).saturating_sub(overhead).max(2 * l);
+
+        Self {
+            available,
+            columns: vec![Vec::new(); l],
+            prev: vec![l; l + 1],
+            next: vec![l; l + 1],
+        }
+    }
+
+    fn unlink(&mut self, i: usize) {
+        let (prev, next) = (self.prev[i], self.next[i]);
+        self.next[prev] = next;
+        self.prev[next] = prev;
+    }
+
+    /// Inserts column `i` as the most recently used one.
+    fn link(&mut self, i: usize) {
+        let sentinel = self.columns.len();
+        let last = self.prev[sentinel];
+
+        self.next[i] = sentinel;
+        self.prev[i] = last;
+        self.next[last] = i;
+        self.prev[sentinel] = i;
+    }
+
+    /// Returns column `index` with at least `len` values, and the number of values already computed, evicting
+    /// the least recently used columns to make room if needed.
+    pub fn get_data(&mut self, index: usize, len: usize) -> (&mut [f32], usize) {
+        let cached = self.columns[index].len();
+
+        if cached > 0 {
+            self.unlink(index);
+        }
+
+        let start = if len > cached {
+            let more = len - cached;
+
+            while self.available < more {
+                let old = self.next[self.columns.len()];
+
+                self.unlink(old);
+                self.available += self.columns[old].len();
+                self.columns[old] = Vec::new();
+            }
+
+            self.columns[index].resize(len, 0.0);
+            self.available -= more;
+            cached
+        } else {
+            len
+        };
+
+        if !self.columns[index].is_empty() {
+            self.link(index);
+        }
+
+        (&mut self.columns[index], start)
+    }
+
+    /// Returns column `index`, which must have been computed with [`Cache::get_data`] before.
+    pub fn column(&self, index: usize) -> &[f32] {
+        &self.columns[index]
+    }
+
+    /// Swaps the columns and rows `i` and `j`, dropping columns computed for only one of them.
+    pub fn swap_index(&mut self, i: usize, j: usize) {
+        if i == j {
+            return;
+        }
+
+        for k in [i, j] {
+            if !self.columns[k].is_empty() {
+                self.unlink(k);
+            }
+        }
+
+        self.columns.swap(i, j);
+
+        for k in [i, j] {
+            if !self.columns[k].is_empty() {
+                self.link(k);
+            }
+        }
+
+        let (i, j) = (i.min(j), i.max(j));
+        let sentinel = self.columns.len();
+        let mut k = self.next[sentinel];
+
+        while k != sentinel {
+            let next = self.next[k];
+            let len = self.columns[k].len();
+
+            if len > j {
+                self.columns[k].swap(i, j);
+            } else if len > i {
+                self.unlink(k);
+                self.available += len;
+                self.columns[k] = Vec::new();
+            }
+
+            k = next;
+        }
+    }
+}
+
+/// The matrix `Q` of the dual problem, `Q_ij = y_i y_j K(x_i, x_j)` for classification.
+pub trait QMatrix {
+    /// Returns the first `len` values of column `i`.
+    fn get_q(&mut self, i: usize, len: usize) -> &[f32];
+
+    /// Returns the first `len` values of the distinct columns `i` and `j`.
+    fn get_q_pair(&mut self, i: usize, j: usize, len: usize) -> (&[f32], &[f32]);
+
+    /// Returns the diagonal of `Q`.
+    fn qd(&self) -> &[f64];
+
+    fn swap_index(&mut self, i: usize, j: usize);
+}
+
+/// `Q` of C-SVC, ν-SVC and, with all `y_i = 1`, one-class SVMs.
+pub struct SvcQ<'a> {
+    y: Vec<i8>,
+    kernel: Kernel<'a>,
+    cache: Cache,
+    qd: Vec<f64>,
+}
+
+impl<'a> SvcQ<'a> {
+    pub fn new(x: &[&'a SparseVector<f64>], y: &[i8], parameters: Parameters, cache_bytes: usize) -> Self {
+        let kernel = Kernel::new(x, parameters);
+        let qd = (0..x.len()).map(|i| kernel.evaluate(i, i)).collect();
+
+        Self { y: y.to_vec(), kernel, cache: Cache::new(x.len(), cache_bytes), qd }
+    }
+
+    /// Computes the first `len` values of column `i` into the cache.
+    fn fill(&mut self, i: usize, len: usize) {
+        let (data, start) = self.cache.get_data(i, len);
+
+        for (j, value) in data.iter_mut().enumerate().take(len).skip(start) {
+            *value = (f64::from(self.y[i] * self.y[j]) * self.kernel.evaluate(i, j)) as f32;
+        }
+    }
+}
+
+impl QMatrix for SvcQ<'_> {
+    fn get_q(&mut self, i: usize, len: usize) -> &[f32] {
+        self.fill(i, len);
+        &self.cache.column(i)[..len]
+    }
+
+    fn get_q_pair(&mut self, i: usize, j: usize, len: usize) -> (&[f32], &[f32]) {
+        // The cache holds at least two full columns, so filling `j` never evicts `i`.
+        self.fill(i, len);
+        self.fill(j, len);
+        (&self.cache.column(i)[..len], &self.cache.column(j)[..len])
+    }
+
+    fn qd(&self) -> &[f64] {
+        &self.qd
+    }
+
+    fn swap_index(&mut self, i: usize, j: usize) {
+        self.cache.swap_index(i, j);
+        self.kernel.swap_index(i, j);
+        self.y.swap(i, j);
+        self.qd.swap(i, j);
+    }
+}
+
+/// `Q` of ε-SVR and ν-SVR, whose `2l` variables share the kernel values of the `l` samples.
+pub struct SvrQ<'a> {
+    l: usize,
+    kernel: Kernel<'a>,
+    cache: Cache,
+    sign: Vec<i8>,
+    index: Vec<usize>,
+    qd: Vec<f64>,
+    buffers: [Vec<f32>; 2],
+    next_buffer: usize,
+}
+
+impl<'a> SvrQ<'a> {
+    pub fn new(x: &[&'a SparseVector<f64>], parameters: Parameters, cache_bytes: usize) -> Self {
+        let l = x.len();
+        let kernel = Kernel::new(x, parameters);
+        let qd = (0..2 * l).map(|k| kernel.evaluate(k % l, k % l)).collect();
+
+        Self {
+            l,
+            kernel,
+            cache: Cache::new(l, cache_bytes),
+            sign: (0..2 * l).map(|k| if k < l { 1 } else { -1 }).collect(),
+            index: (0..2 * l).map(|k| k % l).collect(),
+            qd,
+            buffers: [vec![0.0; 2 * l], vec![0.0; 2 * l]],
+            next_buffer: 0,
+        }
+    }
+
+    /// Computes the first `len` values of column `i` into buffer `buffer`.
+    fn fill(&mut self, buffer: usize, i: usize, len: usize) {
+        let real_i = self.index[i];
+        let (data, start) = self.cache.get_data(real_i, self.l);
+
+        for (j, value) in data.iter_mut().enumerate().skip(start) {
+            *value = self.kernel.evaluate(real_i, j) as f32;
+        }
+
+        let sign_i = f32::from(self.sign[i]);
+        let data = self.cache.column(real_i);
+
+        for (j, value) in self.buffers[buffer][..len].iter_mut().enumerate() {
+            *value = sign_i * f32::from(self.sign[j]) * data[self.index[j]];
+        }
+    }
+}
+
+impl QMatrix for SvrQ<'_> {
+    fn get_q(&mut self, i: usize, len: usize) -> &[f32] {
+        let buffer = self.next_buffer;
+        self.next_buffer = 1 - buffer;
+        self.fill(buffer, i, len);
+        &self.buffers[buffer][..len]
+    }
+
+    fn get_q_pair(&mut self, i: usize, j: usize, len: usize) -> (&[f32], &[f32]) {
+        self.fill(0, i, len);
+        self.fill(1, j, len);
+        (&self.buffers[0][..len], &self.buffers[1][..len])
+    }
+
+    fn qd(&self) -> &[f64] {
+        &self.qd
+    }
+
+    fn swap_index(&mut self, i: usize, j: usize) {
+        self.sign.swap(i, j);
+        self.index.swap(i, j);
+        self.qd.swap(i, j);
+    }
+}
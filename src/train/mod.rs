@@ -0,0 +1,515 @@
+mod dataset;
+mod grid;
+mod kernel;
+mod solver;
+mod validation;
+
+pub use self::{
+    dataset::Dataset,
+    grid::{GridPoint, GridSearch, GridSearchResult},
+    validation::{CrossValidation, Folds, Score},
+};
+
+use crate::{
+    errors::Error,
+    parser::{Attribute, Header, ModelFile, SupportVector},
+    sparse::SparseVector,
+    svm::SVMType,
+    train::{
+        kernel::{Parameters, SvcQ, SvrQ},
+        solver::{Formulation, Solver},
+    },
+};
+
+/// Kernel cache size `svm-train` uses by default, 100 MB.
+const DEFAULT_CACHE_BYTES: usize = 100 << 20;
+
+/// Kernel functions an SVM can be trained with, the `kernel_type` of a libSVM model.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum KernelType {
+    /// `u'v`
+    Linear,
+
+    /// `(gamma u'v + coef0)^degree`
+    Poly,
+
+    /// `exp(-gamma |u - v|^2)`
+    #[default]
+    Rbf,
+
+    /// `tanh(gamma u'v + coef0)`
+    Sigmoid,
+}
+
+impl KernelType {
+    /// Returns the name of this kernel as used by the `kernel_type` line of a libSVM model.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Linear => "linear",
+            Self::Poly => "polynomial",
+            Self::Rbf => "rbf",
+            Self::Sigmoid => "sigmoid",
+        }
+    }
+}
+
+/// Training samples and labels of a problem or subproblem, like libSVM's `svm_problem`.
+struct Problem<'a> {
+    x: Vec<&'a SparseVector<f64>>,
+    y: Vec<f64>,
+}
+
+/// Solution of a single problem, like libSVM's `decision_function`.
+struct DecisionFunction {
+    alpha: Vec<f64>,
+    rho: f64,
+}
+
+/// Trains SVMs the same way libSVM's `svm-train` does.
+///
+/// The trainer solves the same dual problems with the same SMO solver, including second order working set selection,
+/// shrinking and a kernel cache, and produces a [`ModelFile`] that can be loaded into any SVM, or written in libSVM's
+/// format with [`ToString`]:
+///
+/// ```
+/// use ffsvm::{Dataset, DenseSVM, Error, FeatureVector, KernelType, Label, Predict, SVMType, Trainer};
+///
+/// # fn main() -> Result<(), Error> {
+/// let data = Dataset::try_from("1 0:1 1:0.9\n1 0:0.8 1:1\n-1 0:-1 1:-0.7\n-1 0:-0.9 1:-1\n")?;
+/// let model = Trainer::new(SVMType::CSvc).with_kernel(KernelType::Linear).with_c(10.0).train(&data)?;
+/// let svm = DenseSVM::try_from(&model)?;
+///
+/// let mut fv = FeatureVector::from(&svm);
+/// fv.features()[0] = 0.7;
+/// fv.features()[1] = 0.6;
+///
+/// svm.predict_value(&mut fv)?;
+///
+/// assert_eq!(fv.label(), Label::Class(1));
+/// # Ok(())
+/// # }
+/// ```
+///
+/// All parameters default to those of `svm-train`, in particular `gamma` defaults to `1 / n` for the largest feature
+/// index `n` of the training data. Per-class weights are not supported.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Trainer {
+    svm_type: SVMType,
+    kernel_type: KernelType,
+    degree: u32,
+    gamma: Option<f64>,
+    coef0: f64,
+    c: f64,
+    nu: f64,
+    p: f64,
+    eps: f64,
+    cache_bytes: usize,
+    shrinking: bool,
+}
+
+impl Default for Trainer {
+    fn default() -> Self {
+        Self::new(SVMType::CSvc)
+    }
+}
+
+impl Trainer {
+    /// Creates a trainer for SVMs of the given type, with an RBF kernel and all other parameters as for `svm-train`.
+    #[must_use]
+    pub const fn new(svm_type: SVMType) -> Self {
+        Self {
+            svm_type,
+            kernel_type: KernelType::Rbf,
+            degree: 3,
+            gamma: None,
+            coef0: 0.0,
+            c: 1.0,
+            nu: 0.5,
+            p: 0.1,
+            eps: 1e-3,
+            cache_bytes: DEFAULT_CACHE_BYTES,
+            shrinking: true,
+        }
+    }
+
+    /// Sets the kernel function, `-t` of `svm-train`.
+    #[must_use]
+    pub const fn with_kernel(mut self, kernel_type: KernelType) -> Self {
+        self.kernel_type = kernel_type;
+        self
+    }
+
+    /// Sets the `degree` of polynomial kernels, `-d` of `svm-train`, `3` by default.
+    #[must_use]
+    pub const fn with_degree(mut self, degree: u32) -> Self {
+        self.degree = degree;
+        self
+    }
+
+    /// Sets `gamma` of polynomial, RBF and sigmoid kernels, `-g` of `svm-train`.
+    #[must_use]
+    pub const fn with_gamma(mut self, gamma: f64) -> Self {
+        self.gamma = Some(gamma);
+        self
+    }
+
+    /// Sets `coef0` of polynomial and sigmoid kernels, `-r` of `svm-train`, `0` by default.
+    #[must_use]
+    pub const fn with_coef0(mut self, coef0: f64) -> Self {
+        self.coef0 = coef0;
+        self
+    }
+
+    /// Sets the cost `C` of C-SVC, ε-SVR and ν-SVR, `-c` of `svm-train`, `1` by default.
+    #[must_use]
+    pub const fn with_c(mut self, c: f64) -> Self {
+        self.c = c;
+        self
+    }
+
+    /// Sets `nu` of ν-SVC, one-class SVMs and ν-SVR, `-n` of `svm-train`, `0.5` by default.
+    #[must_use]
+    pub const fn with_nu(mut self, nu: f64) -> Self {
+        self.nu = nu;
+        self
+    }
+
+    /// Sets the width `p` of the ε-insensitive loss of ε-SVR, `-p` of `svm-train`, `0.1` by default.
+    #[must_use]
+    pub const fn with_epsilon(mut self, p: f64) -> Self {
+        self.p = p;
+        self
+    }
+
+    /// Sets the tolerance of the termination criterion, `-e` of `svm-train`, `0.001` by default.
+    #[must_use]
+    pub const fn with_tolerance(mut self, eps: f64) -> Self {
+        self.eps = eps;
+        self
+    }
+
+    /// Sets the size of the kernel cache in bytes, `-m` of `svm-train`, 100 MB by default.
+    #[must_use]
+    pub const fn with_cache_size(mut self, bytes: usize) -> Self {
+        self.cache_bytes = bytes;
+        self
+    }
+
+    /// Sets whether to use the shrinking heuristics, `-h` of `svm-train`, enabled by default.
+    #[must_use]
+    pub const fn with_shrinking(mut self, shrinking: bool) -> Self {
+        self.shrinking = shrinking;
+        self
+    }
+
+    /// Returns the cost `C`.
+    #[must_use]
+    pub const fn c(&self) -> f64 {
+        self.c
+    }
+
+    /// Returns `gamma`, or `None` if it defaults to `1 / n` for the largest feature index `n` of the training data.
+    #[must_use]
+    pub const fn gamma(&self) -> Option<f64> {
+        self.gamma
+    }
+
+    /// Returns the `degree` of polynomial kernels.
+    #[must_use]
+    pub const fn degree(&self) -> u32 {
+        self.degree
+    }
+
+    /// Returns `coef0` of polynomial and sigmoid kernels.
+    #[must_use]
+    pub const fn coef0(&self) -> f64 {
+        self.coef0
+    }
+
+    /// Returns `nu`.
+    #[must_use]
+    pub const fn nu(&self) -> f64 {
+        self.nu
+    }
+
+    /// Trains a model on `data`.
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`Error::InvalidParameter`] if a parameter is out of range, or `nu` is infeasible for the data of a
+    /// ν-SVC, and with [`Error::InvalidTrainingData`] if `data` is empty or classification data has a single class.
+    pub fn train(&self, data: &Dataset) -> Result<ModelFile<'static>, Error> {
+        self.check()?;
+
+        if data.is_empty() {
+            return Err(Error::InvalidTrainingData("no samples"));
+        }
+
+        let parameters = self.parameters(data);
+        let x = data.samples().iter().collect::<Vec<_>>();
+
+        let (rho, label, nr_sv, vectors) = match self.svm_type {
+            SVMType::CSvc | SVMType::NuSvc => self.train_classification(&x, data.labels(), parameters)?,
+            SVMType::ESvr | SVMType::NuSvr | SVMType::OneClass => {
+                let problem = Problem { x, y: data.labels().to_vec() };
+                let f = self.solve(&problem, parameters, (self.c, self.c));
+
+                let vectors = problem
+                    .x
+                    .iter()
+                    .zip(&f.alpha)
+                    .filter(|(_, alpha)| alpha.abs() > 0.0)
+                    .map(|(x, alpha)| support_vector(x, vec![*alpha]))
+                    .collect();
+
+                (vec![f.rho], Vec::new(), Vec::new(), vectors)
+            }
+        };
+
+        let Parameters { kernel_type, degree, gamma, coef0 } = parameters;
+        let header = Header {
+            svm_type: self.svm_type.as_str(),
+            kernel_type: kernel_type.as_str(),
+            gamma: (kernel_type != KernelType::Linear).then_some(gamma as f32),
+            coef0: matches!(kernel_type, KernelType::Poly | KernelType::Sigmoid).then_some(coef0 as f32),
+            degree: (kernel_type == KernelType::Poly).then_some(degree),
+            nr_class: label.len().max(2) as u32,
+            total_sv: vectors.len() as u32,
+            rho,
+            label,
+            prob_a: None,
+            prob_b: None,
+            nr_sv,
+        };
+
+        Ok(ModelFile::new(header, vectors))
+    }
+
+    /// Checks all parameters relevant for the SVM type, like libSVM's `svm_check_parameter`.
+    fn check(&self) -> Result<(), Error> {
+        let uses_c = matches!(self.svm_type, SVMType::CSvc | SVMType::ESvr | SVMType::NuSvr);
+        let uses_nu = matches!(self.svm_type, SVMType::NuSvc | SVMType::OneClass | SVMType::NuSvr);
+
+        if self.gamma.is_some_and(|gamma| gamma < 0.0) {
+            Err(Error::InvalidParameter("gamma"))
+        } else if self.cache_bytes == 0 {
+            Err(Error::InvalidParameter("cache_size"))
+        } else if self.eps <= 0.0 {
+            Err(Error::InvalidParameter("eps"))
+        } else if uses_c && self.c <= 0.0 {
+            Err(Error::InvalidParameter("C"))
+        } else if uses_nu && (self.nu <= 0.0 || self.nu > 1.0) {
+            Err(Error::InvalidParameter("nu"))
+        } else if self.svm_type == SVMType::ESvr && self.p < 0.0 {
+            Err(Error::InvalidParameter("p"))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Returns the kernel parameters, with `gamma` defaulting to `1 / n` for the largest feature index `n`.
+    fn parameters(&self, data: &Dataset) -> Parameters {
+        let gamma = self.gamma.unwrap_or_else(|| match data.max_index() {
+            0 => 0.0,
+            n => 1.0 / n as f64,
+        });
+
+        Parameters { kernel_type: self.kernel_type, degree: self.degree, gamma, coef0: self.coef0 }
+    }
+
+    /// Trains one-vs-one classifiers for all pairs of classes, returning `rho`, the labels, the number of support
+    /// vectors per class and the support vectors, like libSVM's `svm_train`.
+    #[allow(clippy::type_complexity)]
+    fn train_classification(&self, x: &[&SparseVector<f64>], y: &[f64], parameters: Parameters) -> Result<(Vec<f64>, Vec<i32>, Vec<u32>, Vec<SupportVector>), Error> {
+        let (labels, members) = group_classes(y);
+        let nr_class = labels.len();
+
+        if nr_class < 2 {
+            return Err(Error::InvalidTrainingData("a single class"));
+        }
+
+        if self.svm_type == SVMType::NuSvc {
+            for i in 0..nr_class {
+                for j in i + 1..nr_class {
+                    let (n1, n2) = (members[i].len() as f64, members[j].len() as f64);
+
+                    if self.nu * (n1 + n2) / 2.0 > n1.min(n2) {
+                        return Err(Error::InvalidParameter("nu"));
+                    }
+                }
+            }
+        }
+
+        // Solve all pairs, remembering which samples of each class became support vectors.
+        let mut nonzero = members.iter().map(|x| vec![false; x.len()]).collect::<Vec<_>>();
+        let mut functions = Vec::with_capacity(nr_class * (nr_class - 1) / 2);
+
+        for i in 0..nr_class {
+            for j in i + 1..nr_class {
+                let (ci, cj) = (members[i].len(), members[j].len());
+                let problem = Problem {
+                    x: members[i].iter().chain(&members[j]).map(|k| x[*k]).collect(),
+                    y: [vec![1.0; ci], vec![-1.0; cj]].concat(),
+                };
+
+                let f = self.solve(&problem, parameters, (self.c, self.c));
+
+                for (nonzero, alpha) in nonzero[i].iter_mut().zip(&f.alpha[..ci]) {
+                    *nonzero |= alpha.abs() > 0.0;
+                }
+
+                for (nonzero, alpha) in nonzero[j].iter_mut().zip(&f.alpha[ci..]) {
+                    *nonzero |= alpha.abs() > 0.0;
+                }
+
+                functions.push(f);
+            }
+        }
+
+        // Coefficients of a support vector of class `i` are those of its classifiers against all other classes, in
+        // the order of these classes.
+        let mut vectors = Vec::new();
+        let mut nr_sv = Vec::with_capacity(nr_class);
+
+        for i in 0..nr_class {
+            let mut count = 0;
+
+            for (k, sample) in members[i].iter().enumerate().filter(|(k, _)| nonzero[i][*k]) {
+                let coefs = (0..nr_class)
+                    .filter(|j| *j != i)
+                    .map(|j| {
+                        if i < j {
+                            functions[pair_index(nr_class, i, j)].alpha[k]
+                        } else {
+                            functions[pair_index(nr_class, j, i)].alpha[members[j].len() + k]
+                        }
+                    })
+                    .collect();
+
+                vectors.push(support_vector(x[*sample], coefs));
+                count += 1;
+            }
+
+            nr_sv.push(count);
+        }
+
+        let rho = functions.iter().map(|f| f.rho).collect();
+
+        Ok((rho, labels, nr_sv, vectors))
+    }
+
+    /// Solves a single problem for the SVM type, with the upper bounds `(C_p, C_n)` of positive and negative
+    /// samples of C-SVC, like libSVM's `svm_train_one`.
+    fn solve(&self, problem: &Problem<'_>, parameters: Parameters, (cp, cn): (f64, f64)) -> DecisionFunction {
+        let l = problem.x.len();
+        let signs = || problem.y.iter().map(|y| if *y > 0.0 { 1 } else { -1 }).collect::<Vec<i8>>();
+
+        let (alpha, rho) = match self.svm_type {
+            SVMType::CSvc => {
+                let y = signs();
+                let q = SvcQ::new(&problem.x, &y, parameters, self.cache_bytes);
+                let (alpha, si) = Solver::new(q, Formulation::Standard, vec![-1.0; l], y.clone(), vec![0.0; l], (cp, cn)).solve(self.eps, self.shrinking);
+
+                (alpha.iter().zip(&y).map(|(alpha, y)| alpha * f64::from(*y)).collect(), si.rho)
+            }
+            SVMType::NuSvc => {
+                let y = signs();
+                let mut sums = [self.nu * l as f64 / 2.0; 2];
+                let alpha = y
+                    .iter()
+                    .map(|y| {
+                        let sum = &mut sums[usize::from(*y != 1)];
+                        let alpha = sum.min(1.0);
+                        *sum -= alpha;
+                        alpha
+                    })
+                    .collect();
+
+                let q = SvcQ::new(&problem.x, &y, parameters, self.cache_bytes);
+                let (alpha, si) = Solver::new(q, Formulation::Nu, vec![0.0; l], y.clone(), alpha, (1.0, 1.0)).solve(self.eps, self.shrinking);
+
+                (alpha.iter().zip(&y).map(|(alpha, y)| alpha * f64::from(*y) / si.r).collect(), si.rho / si.r)
+            }
+            SVMType::OneClass => {
+                // The first `nu * l` variables start at their upper bound.
+                let n = self.nu * l as f64;
+                let alpha = (0..l).map(|i| (n - i as f64).clamp(0.0, 1.0)).collect();
+                let y = vec![1; l];
+
+                let q = SvcQ::new(&problem.x, &y, parameters, self.cache_bytes);
+                let (alpha, si) = Solver::new(q, Formulation::Standard, vec![0.0; l], y, alpha, (1.0, 1.0)).solve(self.eps, self.shrinking);
+
+                (alpha, si.rho)
+            }
+            SVMType::ESvr => {
+                let p = problem.y.iter().map(|y| self.p - y).chain(problem.y.iter().map(|y| self.p + y)).collect();
+                let y = [vec![1; l], vec![-1; l]].concat();
+
+                let q = SvrQ::new(&problem.x, parameters, self.cache_bytes);
+                let (alpha, si) = Solver::new(q, Formulation::Standard, p, y, vec![0.0; 2 * l], (self.c, self.c)).solve(self.eps, self.shrinking);
+
+                (alpha[..l].iter().zip(&alpha[l..]).map(|(a, b)| a - b).collect(), si.rho)
+            }
+            SVMType::NuSvr => {
+                let mut sum = self.c * self.nu * l as f64 / 2.0;
+                let alpha = (0..l)
+                    .map(|_| {
+                        let alpha = sum.min(self.c);
+                        sum -= alpha;
+                        alpha
+                    })
+                    .collect::<Vec<_>>();
+
+                let p = problem.y.iter().map(|y| -y).chain(problem.y.iter().copied()).collect();
+                let y = [vec![1; l], vec![-1; l]].concat();
+
+                let q = SvrQ::new(&problem.x, parameters, self.cache_bytes);
+                let (alpha, si) = Solver::new(q, Formulation::Nu, p, y, alpha.repeat(2), (self.c, self.c)).solve(self.eps, self.shrinking);
+
+                (alpha[..l].iter().zip(&alpha[l..]).map(|(a, b)| a - b).collect(), si.rho)
+            }
+        };
+
+        DecisionFunction { alpha, rho }
+    }
+}
+
+/// Groups samples by their label truncated to an integer, returning the labels in the order of their first
+/// occurrence and the samples of each class, like libSVM's `svm_group_classes`.
+fn group_classes(y: &[f64]) -> (Vec<i32>, Vec<Vec<usize>>) {
+    let mut labels = Vec::new();
+    let mut members = Vec::<Vec<usize>>::new();
+
+    for (i, y) in y.iter().enumerate() {
+        let label = *y as i32;
+
+        if let Some(class) = labels.iter().position(|x| *x == label) {
+            members[class].push(i);
+        } else {
+            labels.push(label);
+            members.push(vec![i]);
+        }
+    }
+
+    // For two classes `-1` and `+1`, libSVM makes `+1` the positive class, no matter which comes first.
+    if labels == [-1, 1] {
+        labels.swap(0, 1);
+        members.swap(0, 1);
+    }
+
+    (labels, members)
+}
+
+/// Returns the index of the classifier of classes `i < j` among all pairs, in the order of `rho`.
+const fn pair_index(nr_class: usize, i: usize, j: usize) -> usize {
+    i * (2 * nr_class - i - 1) / 2 + (j - i - 1)
+}
+
+/// Creates a support vector of the model from a training sample.
+fn support_vector(x: &SparseVector<f64>, coefs: Vec<f64>) -> SupportVector {
+    SupportVector {
+        coefs: coefs.into_iter().map(|x| x as f32).collect(),
+        features: x.iter().map(|(index, value)| Attribute { index, value: value as f32 }).collect(),
+    }
+}
@@ -0,0 +1,624 @@
+#![allow(clippy::similar_names)] // Names follow libSVM's `Solver`.
+#![allow(clippy::suboptimal_flops)] // Same formulas as libSVM, for the same results.
+
+use crate::train::kernel::QMatrix;
+
+/// Replaces non-positive quadratic coefficients when selecting and updating working sets.
+const TAU: f64 = 1e-12;
+
+/// The dual problem a [`Solver`] solves.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Formulation {
+    /// `min 0.5 a'Qa + p'a` subject to `y'a = 0` and `0 <= a_i <= C_i`, as for C-SVC, ε-SVR and one-class SVMs.
+    Standard,
+
+    /// Like [`Formulation::Standard`], with the additional constraint `e'a = const`, as for ν-SVC and ν-SVR.
+    Nu,
+}
+
+/// Result of a [`Solver`] besides the `alpha` values.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct SolutionInfo {
+    pub rho: f64,
+
+    /// For [`Formulation::Nu`], the value used to rescale the solution.
+    pub r: f64,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum AlphaStatus {
+    LowerBound,
+    UpperBound,
+    Free,
+}
+
+/// Sequential minimal optimization with second order working set selection (WSS3) and shrinking, a port of
+/// libSVM's `Solver` and `Solver_NU`.
+pub struct Solver<Q> {
+    q: Q,
+
+    /// The diagonal of `Q`, swapped along with the variables.
+    qd: Vec<f64>,
+    formulation: Formulation,
+    l: usize,
+    active_size: usize,
+    y: Vec<i8>,
+    g: Vec<f64>,
+    alpha_status: Vec<AlphaStatus>,
+    alpha: Vec<f64>,
+    eps: f64,
+    cp: f64,
+    cn: f64,
+    p: Vec<f64>,
+    active_set: Vec<usize>,
+
+    /// Gradient contributed by all variables at their upper bound, used to reconstruct shrunk parts of `g`.
+    g_bar: Vec<f64>,
+    unshrink: bool,
+}
+
+impl<Q: QMatrix> Solver<Q> {
+    /// Prepares solving the problem given by `q`, the linear term `p`, the labels `y`, the feasible initial
+    /// `alpha`, and the upper bounds `(C_p, C_n)` of positive and negative variables.
+    pub fn new(q: Q, formulation: Formulation, p: Vec<f64>, y: Vec<i8>, alpha: Vec<f64>, (cp, cn): (f64, f64)) -> Self {
+        let l = alpha.len();
+
+        let mut solver = Self {
+            qd: q.qd().to_vec(),
+            q,
+            formulation,
+            l,
+            active_size: l,
+            y,
+            g: Vec::new(),
+            alpha_status: vec![AlphaStatus::Free; l],
+            alpha,
+            eps: 0.0,
+            cp,
+            cn,
+            p,
+            active_set: (0..l).collect(),
+            g_bar: vec![0.0; l],
+            unshrink: false,
+        };
+
+        for i in 0..l {
+            solver.update_alpha_status(i);
+        }
+
+        solver
+    }
+
+    /// Solves the problem to tolerance `eps`, returning `alpha` in the original order of the variables.
+    pub fn solve(mut self, eps: f64, shrinking: bool) -> (Vec<f64>, SolutionInfo) {
+        let l = self.l;
+        self.eps = eps;
+
+        // Initialize the gradient.
+        self.g = self.p.clone();
+
+        for i in 0..l {
+            if self.is_lower_bound(i) {
+                continue;
+            }
+
+            let (alpha_i, c_i, upper) = (self.alpha[i], self.get_c(i), self.is_upper_bound(i));
+            let q_i = self.q.get_q(i, l);
+
+            for (g, q) in self.g.iter_mut().zip(q_i) {
+                *g += alpha_i * f64::from(*q);
+            }
+
+            if upper {
+                for (g_bar, q) in self.g_bar.iter_mut().zip(q_i) {
+                    *g_bar += c_i * f64::from(*q);
+                }
+            }
+        }
+
+        let max_iter = 10_000_000.max(100 * l);
+        let mut counter = l.min(1000) + 1;
+        let mut iter = 0;
+
+        while iter < max_iter {
+            counter -= 1;
+
+            if counter == 0 {
+                counter = l.min(1000);
+
+                if shrinking {
+                    self.do_shrinking();
+                }
+            }
+
+            let working_set = self.select_working_set().or_else(|| {
+                // Reconstruct the whole gradient and check again with all variables, shrinking again next iteration.
+                self.reconstruct_gradient();
+                self.active_size = l;
+                counter = 1;
+                self.select_working_set()
+            });
+
+            let Some((i, j)) = working_set else {
+                break;
+            };
+
+            iter += 1;
+            self.update(i, j);
+        }
+
+        if iter >= max_iter && self.active_size < l {
+            // Reconstruct the whole gradient to calculate rho over all variables.
+            self.reconstruct_gradient();
+            self.active_size = l;
+        }
+
+        let mut si = SolutionInfo::default();
+        si.rho = self.calculate_rho(&mut si);
+
+        let mut alpha = vec![0.0; l];
+
+        for (i, value) in self.alpha.iter().enumerate() {
+            alpha[self.active_set[i]] = *value;
+        }
+
+        (alpha, si)
+    }
+
+    /// Updates `alpha_i` and `alpha_j` of the working set, and the gradient.
+    fn update(&mut self, i: usize, j: usize) {
+        let (l, active_size) = (self.l, self.active_size);
+        let (c_i, c_j) = (self.get_c(i), self.get_c(j));
+        let (old_alpha_i, old_alpha_j) = (self.alpha[i], self.alpha[j]);
+        let (qd_i, qd_j) = (self.qd[i], self.qd[j]);
+        let alpha = &mut self.alpha;
+
+        let (q_i, q_j) = self.q.get_q_pair(i, j, active_size);
+        let q_ij = f64::from(q_i[j]);
+
+        if self.y[i] == self.y[j] {
+            let quad_coef = qd_i + qd_j - 2.0 * q_ij;
+            let quad_coef = if quad_coef <= 0.0 { TAU } else { quad_coef };
+            let delta = (self.g[i] - self.g[j]) / quad_coef;
+            let sum = alpha[i] + alpha[j];
+
+            alpha[i] -= delta;
+            alpha[j] += delta;
+
+            if sum > c_i {
+                if alpha[i] > c_i {
+                    alpha[i] = c_i;
+                    alpha[j] = sum - c_i;
+                }
+            } else if alpha[j] < 0.0 {
+                alpha[j] = 0.0;
+                alpha[i] = sum;
+            }
+
+            if sum > c_j {
+                if alpha[j] > c_j {
+                    alpha[j] = c_j;
+                    alpha[i] = sum - c_j;
+                }
+            } else if alpha[i] < 0.0 {
+                alpha[i] = 0.0;
+                alpha[j] = sum;
+            }
+        } else {
+            let quad_coef = qd_i + qd_j + 2.0 * q_ij;
+            let quad_coef = if quad_coef <= 0.0 { TAU } else { quad_coef };
+            let delta = (-self.g[i] - self.g[j]) / quad_coef;
+            let diff = alpha[i] - alpha[j];
+
+            alpha[i] += delta;
+            alpha[j] += delta;
+
+            if diff > 0.0 {
+                if alpha[j] < 0.0 {
+                    alpha[j] = 0.0;
+                    alpha[i] = diff;
+                }
+            } else if alpha[i] < 0.0 {
+                alpha[i] = 0.0;
+                alpha[j] = -diff;
+            }
+
+            if diff > c_i - c_j {
+                if alpha[i] > c_i {
+                    alpha[i] = c_i;
+                    alpha[j] = c_i - diff;
+                }
+            } else if alpha[j] > c_j {
+                alpha[j] = c_j;
+                alpha[i] = c_j + diff;
+            }
+        }
+
+        let delta_alpha_i = alpha[i] - old_alpha_i;
+        let delta_alpha_j = alpha[j] - old_alpha_j;
+
+        for k in 0..active_size {
+            self.g[k] += f64::from(q_i[k]) * delta_alpha_i + f64::from(q_j[k]) * delta_alpha_j;
+        }
+
+        // Update the status of both variables, and `g_bar` for those that reached or left their upper bound.
+        for (k, c_k) in [(i, c_i), (j, c_j)] {
+            let was_upper = self.is_upper_bound(k);
+            self.update_alpha_status(k);
+
+            if was_upper != self.is_upper_bound(k) {
+                let sign = if was_upper { -1.0 } else { 1.0 };
+                let q_k = self.q.get_q(k, l);
+
+                for (g_bar, q) in self.g_bar.iter_mut().zip(q_k) {
+                    *g_bar += sign * c_k * f64::from(*q);
+                }
+            }
+        }
+    }
+
+    fn get_c(&self, i: usize) -> f64 {
+        if self.y[i] > 0 {
+            self.cp
+        } else {
+            self.cn
+        }
+    }
+
+    fn update_alpha_status(&mut self, i: usize) {
+        self.alpha_status[i] = if self.alpha[i] >= self.get_c(i) {
+            AlphaStatus::UpperBound
+        } else if self.alpha[i] <= 0.0 {
+            AlphaStatus::LowerBound
+        } else {
+            AlphaStatus::Free
+        };
+    }
+
+    fn is_upper_bound(&self, i: usize) -> bool {
+        self.alpha_status[i] == AlphaStatus::UpperBound
+    }
+
+    fn is_lower_bound(&self, i: usize) -> bool {
+        self.alpha_status[i] == AlphaStatus::LowerBound
+    }
+
+    fn is_free(&self, i: usize) -> bool {
+        self.alpha_status[i] == AlphaStatus::Free
+    }
+
+    fn swap_index(&mut self, i: usize, j: usize) {
+        self.q.swap_index(i, j);
+        self.qd.swap(i, j);
+        self.y.swap(i, j);
+        self.g.swap(i, j);
+        self.alpha_status.swap(i, j);
+        self.alpha.swap(i, j);
+        self.p.swap(i, j);
+        self.active_set.swap(i, j);
+        self.g_bar.swap(i, j);
+    }
+
+    /// Reconstructs the gradient of all inactive variables from `g_bar` and the free variables.
+    fn reconstruct_gradient(&mut self) {
+        let (l, active_size) = (self.l, self.active_size);
+
+        if active_size == l {
+            return;
+        }
+
+        for j in active_size..l {
+            self.g[j] = self.g_bar[j] + self.p[j];
+        }
+
+        let nr_free = (0..active_size).filter(|j| self.is_free(*j)).count();
+
+        if nr_free * l > 2 * active_size * (l - active_size) {
+            for i in active_size..l {
+                let q_i = self.q.get_q(i, active_size);
+
+                for (j, q) in q_i.iter().enumerate() {
+                    if self.alpha_status[j] == AlphaStatus::Free {
+                        self.g[i] += self.alpha[j] * f64::from(*q);
+                    }
+                }
+            }
+        } else {
+            for i in 0..active_size {
+                if !self.is_free(i) {
+                    continue;
+                }
+
+                let alpha_i = self.alpha[i];
+                let q_i = self.q.get_q(i, l);
+
+                for (g, q) in self.g[active_size..].iter_mut().zip(&q_i[active_size..]) {
+                    *g += alpha_i * f64::from(*q);
+                }
+            }
+        }
+    }
+
+    /// Returns the working set `(i, j)`, or `None` if the solution is already optimal.
+    fn select_working_set(&mut self) -> Option<(usize, usize)> {
+        match self.formulation {
+            Formulation::Standard => self.select_working_set_standard(),
+            Formulation::Nu => self.select_working_set_nu(),
+        }
+    }
+
+    /// Returns the decrease of the objective for the working set `(i, j)`, given the gradient difference and the
+    /// quadratic coefficient.
+    fn obj_diff(grad_diff: f64, quad_coef: f64) -> f64 {
+        -(grad_diff * grad_diff) / if quad_coef > 0.0 { quad_coef } else { TAU }
+    }
+
+    /// Selects `i` maximizing `-y_i grad(f)_i` among `I_up`, and `j` minimizing the decrease of the objective
+    /// among `I_low`.
+    fn select_working_set_standard(&mut self) -> Option<(usize, usize)> {
+        let active_size = self.active_size;
+        let mut g_max = f64::NEG_INFINITY;
+        let mut g_max2 = f64::NEG_INFINITY;
+        let mut g_max_idx = None;
+        let mut g_min_idx = None;
+        let mut obj_diff_min = f64::INFINITY;
+
+        for t in 0..active_size {
+            if self.y[t] == 1 {
+                if !self.is_upper_bound(t) && -self.g[t] >= g_max {
+                    g_max = -self.g[t];
+                    g_max_idx = Some(t);
+                }
+            } else if !self.is_lower_bound(t) && self.g[t] >= g_max {
+                g_max = self.g[t];
+                g_max_idx = Some(t);
+            }
+        }
+
+        // Without `i` we have `g_max = -inf`, so no `j` is considered below and its column is never accessed.
+        let i = g_max_idx.unwrap_or_default();
+        let (y_i, qd_i) = (f64::from(self.y[i]), self.qd[i]);
+        let q_i = g_max_idx.map(|i| self.q.get_q(i, active_size)).unwrap_or_default();
+
+        #[allow(clippy::needless_range_loop)] // `q_i` may be empty, see above.
+        for j in 0..active_size {
+            let (grad_diff, sign) = if self.y[j] == 1 {
+                if self.alpha_status[j] == AlphaStatus::LowerBound {
+                    continue;
+                }
+
+                g_max2 = g_max2.max(self.g[j]);
+                (g_max + self.g[j], -1.0)
+            } else {
+                if self.alpha_status[j] == AlphaStatus::UpperBound {
+                    continue;
+                }
+
+                g_max2 = g_max2.max(-self.g[j]);
+                (g_max - self.g[j], 1.0)
+            };
+
+            if grad_diff > 0.0 {
+                let obj_diff = Self::obj_diff(grad_diff, qd_i + self.qd[j] + sign * 2.0 * y_i * f64::from(q_i[j]));
+
+                if obj_diff <= obj_diff_min {
+                    g_min_idx = Some(j);
+                    obj_diff_min = obj_diff;
+                }
+            }
+        }
+
+        match (g_max_idx, g_min_idx) {
+            (Some(i), Some(j)) if g_max + g_max2 >= self.eps => Some((i, j)),
+            _ => None,
+        }
+    }
+
+    /// Like [`Solver::select_working_set_standard`], but `i` and `j` must have the same label.
+    fn select_working_set_nu(&mut self) -> Option<(usize, usize)> {
+        let active_size = self.active_size;
+        let mut g_maxp = f64::NEG_INFINITY;
+        let mut g_maxp2 = f64::NEG_INFINITY;
+        let mut g_maxp_idx = None;
+        let mut g_maxn = f64::NEG_INFINITY;
+        let mut g_maxn2 = f64::NEG_INFINITY;
+        let mut g_maxn_idx = None;
+        let mut g_min_idx = None;
+        let mut obj_diff_min = f64::INFINITY;
+
+        for t in 0..active_size {
+            if self.y[t] == 1 {
+                if !self.is_upper_bound(t) && -self.g[t] >= g_maxp {
+                    g_maxp = -self.g[t];
+                    g_maxp_idx = Some(t);
+                }
+            } else if !self.is_lower_bound(t) && self.g[t] >= g_maxn {
+                g_maxn = self.g[t];
+                g_maxn_idx = Some(t);
+            }
+        }
+
+        // As above, columns are only accessed if the respective index exists.
+        let (ip, in_) = (g_maxp_idx.unwrap_or_default(), g_maxn_idx.unwrap_or_default());
+        let (qd_ip, qd_in) = (self.qd[ip], self.qd[in_]);
+        let (q_ip, q_in): (&[f32], &[f32]) = match (g_maxp_idx, g_maxn_idx) {
+            (Some(ip), Some(in_)) => self.q.get_q_pair(ip, in_, active_size),
+            (Some(ip), None) => (self.q.get_q(ip, active_size), &[]),
+            (None, Some(in_)) => (&[], self.q.get_q(in_, active_size)),
+            (None, None) => (&[], &[]),
+        };
+
+        for j in 0..active_size {
+            let (grad_diff, qd_i, q_i) = if self.y[j] == 1 {
+                if self.alpha_status[j] == AlphaStatus::LowerBound {
+                    continue;
+                }
+
+                g_maxp2 = g_maxp2.max(self.g[j]);
+                (g_maxp + self.g[j], qd_ip, q_ip)
+            } else {
+                if self.alpha_status[j] == AlphaStatus::UpperBound {
+                    continue;
+                }
+
+                g_maxn2 = g_maxn2.max(-self.g[j]);
+                (g_maxn - self.g[j], qd_in, q_in)
+            };
+
+            if grad_diff > 0.0 {
+                let obj_diff = Self::obj_diff(grad_diff, qd_i + self.qd[j] - 2.0 * f64::from(q_i[j]));
+
+                if obj_diff <= obj_diff_min {
+                    g_min_idx = Some(j);
+                    obj_diff_min = obj_diff;
+                }
+            }
+        }
+
+        let j = g_min_idx?;
+
+        if (g_maxp + g_maxp2).max(g_maxn + g_maxn2) < self.eps {
+            return None;
+        }
+
+        let i = if self.y[j] == 1 { g_maxp_idx } else { g_maxn_idx };
+        i.map(|i| (i, j))
+    }
+
+    /// Returns whether variable `i` can be shrunk, given the maximal violations `g_max` as computed by
+    /// [`Solver::do_shrinking`].
+    fn be_shrunk(&self, i: usize, g_max: [f64; 4]) -> bool {
+        if self.is_upper_bound(i) {
+            if self.y[i] == 1 {
+                -self.g[i] > g_max[0]
+            } else {
+                -self.g[i] > g_max[3]
+            }
+        } else if self.is_lower_bound(i) {
+            if self.y[i] == 1 {
+                self.g[i] > g_max[1]
+            } else {
+                self.g[i] > g_max[2]
+            }
+        } else {
+            false
+        }
+    }
+
+    /// Removes variables from the active set that are unlikely to change.
+    fn do_shrinking(&mut self) {
+        let mut g_max = [f64::NEG_INFINITY; 4];
+
+        // Find the maximal violating pair first.
+        for i in 0..self.active_size {
+            let positive = self.y[i] == 1;
+
+            if !self.is_upper_bound(i) {
+                let slot = if positive { 0 } else { 3 };
+                g_max[slot] = g_max[slot].max(-self.g[i]);
+            }
+
+            if !self.is_lower_bound(i) {
+                let slot = if positive { 1 } else { 2 };
+                g_max[slot] = g_max[slot].max(self.g[i]);
+            }
+        }
+
+        let violation = match self.formulation {
+            // The standard formulation doesn't distinguish labels: `up` combines slots 0 and 2, `low` 1 and 3.
+            Formulation::Standard => {
+                g_max = [g_max[0].max(g_max[2]), g_max[1].max(g_max[3]), g_max[0].max(g_max[2]), g_max[1].max(g_max[3])];
+                g_max[0] + g_max[1]
+            }
+            Formulation::Nu => (g_max[0] + g_max[1]).max(g_max[2] + g_max[3]),
+        };
+
+        if !self.unshrink && violation <= self.eps * 10.0 {
+            self.unshrink = true;
+            self.reconstruct_gradient();
+            self.active_size = self.l;
+        }
+
+        let mut i = 0;
+
+        while i < self.active_size {
+            if self.be_shrunk(i, g_max) {
+                self.active_size -= 1;
+
+                while self.active_size > i {
+                    if !self.be_shrunk(self.active_size, g_max) {
+                        self.swap_index(i, self.active_size);
+                        break;
+                    }
+
+                    self.active_size -= 1;
+                }
+            }
+
+            i += 1;
+        }
+    }
+
+    /// Computes the offset `rho`, and for ν also `r`.
+    fn calculate_rho(&self, si: &mut SolutionInfo) -> f64 {
+        /// Averages the gradients of free variables, or uses the middle of the feasible range if there are none.
+        fn average(sum_free: f64, nr_free: usize, ub: f64, lb: f64) -> f64 {
+            if nr_free > 0 {
+                sum_free / nr_free as f64
+            } else {
+                (ub + lb) / 2.0
+            }
+        }
+
+        match self.formulation {
+            Formulation::Standard => {
+                let (mut ub, mut lb, mut sum_free, mut nr_free) = (f64::INFINITY, f64::NEG_INFINITY, 0.0, 0);
+
+                for i in 0..self.active_size {
+                    let y_g = f64::from(self.y[i]) * self.g[i];
+
+                    if self.is_upper_bound(i) {
+                        if self.y[i] == -1 {
+                            ub = ub.min(y_g);
+                        } else {
+                            lb = lb.max(y_g);
+                        }
+                    } else if self.is_lower_bound(i) {
+                        if self.y[i] == 1 {
+                            ub = ub.min(y_g);
+                        } else {
+                            lb = lb.max(y_g);
+                        }
+                    } else {
+                        nr_free += 1;
+                        sum_free += y_g;
+                    }
+                }
+
+                average(sum_free, nr_free, ub, lb)
+            }
+            Formulation::Nu => {
+                // Bounds, sum and count of free gradients for positive and negative variables.
+                let mut bounds = [(f64::INFINITY, f64::NEG_INFINITY, 0.0, 0); 2];
+
+                for i in 0..self.active_size {
+                    let (ub, lb, sum_free, nr_free) = &mut bounds[usize::from(self.y[i] != 1)];
+
+                    if self.is_upper_bound(i) {
+                        *lb = lb.max(self.g[i]);
+                    } else if self.is_lower_bound(i) {
+                        *ub = ub.min(self.g[i]);
+                    } else {
+                        *nr_free += 1;
+                        *sum_free += self.g[i];
+                    }
+                }
+
+                let [r1, r2] = bounds.map(|(ub, lb, sum_free, nr_free)| average(sum_free, nr_free, ub, lb));
+
+                si.r = (r1 + r2) / 2.0;
+                (r1 - r2) / 2.0
+            }
+        }
+    }
+}
@@ -0,0 +1,239 @@
+use crate::{
+    errors::Error,
+    svm::{
+        features::{FeatureVector, Label},
+        predict::Predict,
+        SVMType, SparseSVM,
+    },
+    train::{group_classes, Dataset, Trainer},
+};
+use std::convert::TryFrom;
+
+/// Seed of the sample shuffle if none is set with [`Folds::with_seed`].
+const DEFAULT_SEED: u64 = 0x5EED;
+
+/// How samples are split into folds for cross-validation, see [`Trainer::cross_validate`].
+///
+/// Like `svm-train -v n`, samples are shuffled before they are split. Stratified folds keep the class proportions of
+/// all data in each fold, which `svm-train` does for classification:
+///
+/// ```
+/// use ffsvm::Folds;
+///
+/// let k_fold = Folds::new(10);
+/// let stratified = Folds::stratified(5).with_seed(42);
+/// ```
+///
+/// The shuffle is deterministic, two cross-validations with the same seed use the same folds.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Folds {
+    count: usize,
+    stratified: bool,
+    seed: u64,
+}
+
+impl Folds {
+    /// Splits samples into `count` folds at random.
+    #[must_use]
+    pub const fn new(count: usize) -> Self {
+        Self { count, stratified: false, seed: DEFAULT_SEED }
+    }
+
+    /// Splits samples of each class evenly into `count` folds at random. Regression and one-class data is split like
+    /// with [`Folds::new`].
+    #[must_use]
+    pub const fn stratified(count: usize) -> Self {
+        Self { count, stratified: true, seed: DEFAULT_SEED }
+    }
+
+    /// Sets the seed of the shuffle.
+    #[must_use]
+    pub const fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Returns the number of folds.
+    #[must_use]
+    pub const fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Returns the fold of each sample, like libSVM's `svm_cross_validation`. With at least as many folds as samples,
+    /// each sample is its own fold.
+    fn assign(&self, labels: &[f64], classification: bool) -> Vec<usize> {
+        let count = self.count.min(labels.len());
+        let mut random = SplitMix64(self.seed);
+        let mut fold_of = vec![0; labels.len()];
+
+        let groups = if self.stratified && classification && count < labels.len() {
+            group_classes(labels).1
+        } else {
+            vec![(0..labels.len()).collect()]
+        };
+
+        for mut members in groups {
+            random.shuffle(&mut members);
+
+            for fold in 0..count {
+                for i in &members[fold * members.len() / count..(fold + 1) * members.len() / count] {
+                    fold_of[*i] = fold;
+                }
+            }
+        }
+
+        fold_of
+    }
+}
+
+/// How well a model predicted held-out samples.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Score {
+    /// Fraction of correctly predicted labels of a classifier or one-class SVM, in `[0, 1]`.
+    Accuracy(f64),
+
+    /// Mean squared error of the predicted values of a regression SVM.
+    MeanSquaredError(f64),
+}
+
+impl Score {
+    /// Scores `predictions` of the given SVM type against the true `labels`.
+    fn new(svm_type: SVMType, labels: &[f64], predictions: &[f64]) -> Self {
+        let n = labels.len() as f64;
+
+        match svm_type {
+            SVMType::ESvr | SVMType::NuSvr => Self::MeanSquaredError(labels.iter().zip(predictions).map(|(y, p)| (y - p).powi(2)).sum::<f64>() / n),
+            SVMType::CSvc | SVMType::NuSvc | SVMType::OneClass => {
+                Self::Accuracy(labels.iter().zip(predictions).filter(|(y, p)| **y as i32 == **p as i32).count() as f64 / n)
+            }
+        }
+    }
+
+    /// Returns the accuracy or mean squared error.
+    #[must_use]
+    pub const fn value(self) -> f64 {
+        match self {
+            Self::Accuracy(x) | Self::MeanSquaredError(x) => x,
+        }
+    }
+
+    /// Returns `true` if this is a higher accuracy or lower mean squared error than `other`. Scores of different
+    /// kinds are never better than each other.
+    #[must_use]
+    pub fn is_better_than(self, other: Self) -> bool {
+        match (self, other) {
+            (Self::Accuracy(a), Self::Accuracy(b)) => a > b,
+            (Self::MeanSquaredError(a), Self::MeanSquaredError(b)) => a < b,
+            _ => false,
+        }
+    }
+}
+
+/// Result of [`Trainer::cross_validate`], the prediction of each sample by the model trained without its fold.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CrossValidation {
+    predictions: Vec<f64>,
+    score: Score,
+}
+
+impl CrossValidation {
+    /// Returns the predicted label or value of each sample, in the order of the dataset.
+    #[must_use]
+    pub fn predictions(&self) -> &[f64] {
+        &self.predictions
+    }
+
+    /// Returns the accuracy or mean squared error of all predictions.
+    #[must_use]
+    pub const fn score(&self) -> Score {
+        self.score
+    }
+}
+
+impl Trainer {
+    /// Cross-validates the trainer's parameters on `data`, like `svm-train -v n` does.
+    ///
+    /// For each fold, a model is trained on all other folds and predicts the samples of that fold. As for
+    /// `svm-train`, the default `gamma` is computed from all of `data`, not from the samples of each model:
+    ///
+    /// ```
+    /// use ffsvm::{Dataset, Error, Folds, KernelType, Score, Trainer};
+    ///
+    /// # fn main() -> Result<(), Error> {
+    /// let data = Dataset::try_from("1 0:1\n1 0:0.8\n1 0:0.9\n-1 0:-1\n-1 0:-0.7\n-1 0:-0.9\n")?;
+    /// let result = Trainer::default().with_kernel(KernelType::Linear).cross_validate(&data, Folds::stratified(3))?;
+    ///
+    /// assert_eq!(result.score(), Score::Accuracy(1.0));
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`Error::InvalidParameter`] if there are fewer than two folds, and like [`Trainer::train`] for
+    /// `data` or the data of a fold, e.g., if a fold has samples of a single class only.
+    pub fn cross_validate(&self, data: &Dataset, folds: Folds) -> Result<CrossValidation, Error> {
+        self.check()?;
+
+        if folds.count < 2 {
+            return Err(Error::InvalidParameter("folds"));
+        } else if data.is_empty() {
+            return Err(Error::InvalidTrainingData("no samples"));
+        }
+
+        let trainer = self.clone().with_gamma(self.parameters(data).gamma);
+        let fold_of = folds.assign(data.labels(), matches!(self.svm_type, SVMType::CSvc | SVMType::NuSvc));
+        let mut predictions = vec![0.0; data.len()];
+
+        for fold in 0..folds.count.min(data.len()) {
+            let training = data
+                .labels()
+                .iter()
+                .zip(data.samples())
+                .zip(&fold_of)
+                .filter(|(_, x)| **x != fold)
+                .map(|((label, sample), _)| (*label, sample.clone()))
+                .collect::<Dataset>();
+
+            let svm = SparseSVM::try_from(&trainer.train(&training)?)?;
+            let mut problem = FeatureVector::from(&svm);
+
+            for i in (0..data.len()).filter(|i| fold_of[*i] == fold) {
+                problem.clear();
+                problem.features().extend(data.samples()[i].iter().map(|(index, value)| (index as usize, value as f32)));
+
+                svm.predict_value(&mut problem)?;
+
+                predictions[i] = match problem.label() {
+                    Label::Class(x) => f64::from(x),
+                    _ => problem.raw_value(),
+                };
+            }
+        }
+
+        let score = Score::new(self.svm_type, data.labels(), &predictions);
+
+        Ok(CrossValidation { predictions, score })
+    }
+}
+
+/// Small, fast pseudo random number generator, only used to shuffle samples.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    const fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Shuffles `values` with the Fisher-Yates shuffle.
+    fn shuffle(&mut self, values: &mut [usize]) {
+        for i in (1..values.len()).rev() {
+            values.swap(i, (self.next() % (i as u64 + 1)) as usize);
+        }
+    }
+}
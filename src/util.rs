@@ -39,6 +39,91 @@ pub fn sigmoid_predict(decision_value: f64, a: f64, b: f64) -> f64 {
     }
 }
 
+/// Fits the parameters `(A, B)` of [`sigmoid_predict`] to decision values of samples of the positive (`true`) and
+/// negative class, using Newton's method with backtracking. As implemented in `libsvm`.
+#[allow(clippy::suboptimal_flops, clippy::while_float)] // Same formulas and line search as `libsvm`.
+pub fn sigmoid_train(decision_values: &[f64], labels: &[bool]) -> (f64, f64) {
+    const MAX_ITER: usize = 100;
+    const MIN_STEP: f64 = 1e-10;
+    const SIGMA: f64 = 1e-12;
+    const EPS: f64 = 1e-5;
+
+    let prior1 = labels.iter().filter(|x| **x).count() as f64;
+    let prior0 = labels.len() as f64 - prior1;
+
+    // Targets are regularized so that the fit doesn't diverge for separable data.
+    let hi_target = (prior1 + 1.0) / (prior1 + 2.0);
+    let lo_target = 1.0 / (prior0 + 2.0);
+    let targets = labels.iter().map(|x| if *x { hi_target } else { lo_target }).collect::<Vec<_>>();
+
+    // Negative log likelihood of parameters `a` and `b`.
+    let objective = |a: f64, b: f64| -> f64 {
+        decision_values
+            .iter()
+            .zip(&targets)
+            .map(|(f, t)| {
+                let fapb = f * a + b;
+
+                if fapb >= 0.0 {
+                    t * fapb + (-fapb).exp().ln_1p()
+                } else {
+                    (t - 1.0) * fapb + fapb.exp().ln_1p()
+                }
+            })
+            .sum()
+    };
+
+    let mut a = 0.0;
+    let mut b = ((prior0 + 1.0) / (prior1 + 1.0)).ln();
+    let mut value = objective(a, b);
+
+    for _ in 0..MAX_ITER {
+        // Gradient and Hessian, with `SIGMA` added to the diagonal so that it's positive definite.
+        let (mut h11, mut h22, mut h21, mut g1, mut g2) = (SIGMA, SIGMA, 0.0, 0.0, 0.0);
+
+        for (f, t) in decision_values.iter().zip(&targets) {
+            let p = sigmoid_predict(*f, a, b);
+            let d2 = p * (1.0 - p);
+
+            h11 += f * f * d2;
+            h22 += d2;
+            h21 += f * d2;
+            g1 += f * (t - p);
+            g2 += t - p;
+        }
+
+        if g1.abs() < EPS && g2.abs() < EPS {
+            break;
+        }
+
+        let det = h11 * h22 - h21.powi(2);
+        let da = -(h22 * g1 - h21 * g2) / det;
+        let db = -(-h21 * g1 + h11 * g2) / det;
+        let gd = g1 * da + g2 * db;
+
+        // Line search, halving the step until the objective decreases sufficiently.
+        let mut step = 1.0;
+
+        while step >= MIN_STEP {
+            let (new_a, new_b) = (a + step * da, b + step * db);
+            let new_value = objective(new_a, new_b);
+
+            if new_value < value + 0.0001 * step * gd {
+                (a, b, value) = (new_a, new_b, new_value);
+                break;
+            }
+
+            step /= 2.0;
+        }
+
+        if step < MIN_STEP {
+            break;
+        }
+    }
+
+    (a, b)
+}
+
 /// As implemented in `libsvm`.
 pub fn powi(base: f64, times: u32) -> f64 {
     let mut tmp = base;
@@ -48,7 +133,7 @@ pub fn powi(base: f64, times: u32) -> f64 {
     while t > 0 {
         if t % 2 == 1 {
             ret *= tmp;
-        };
+        }
 
         tmp = tmp * tmp;
         t /= 2;
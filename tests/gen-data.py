@@ -1,5 +1,6 @@
 #!/usr/bin/python
 
+import math
 import random
 
 CLASSES = 8
@@ -49,3 +50,24 @@ with open('data_sparse/problem.in', "w") as f:
                 f.write(rest)
 
             f.write("\n")
+
+
+# Precomputed kernel rows of the dense problem, using an RBF kernel with `svm-train`'s default gamma.
+with open('data_dense/problem.in') as f:
+    samples = []
+
+    for line in f:
+        tokens = line.split()
+        samples.append((tokens[0], [float(x.split(":")[1]) for x in tokens[1:]]))
+
+GAMMA = 1 / (DENSE_ATTRIBUTES - 1)
+
+with open('data_precomputed/problem.in', "w") as f:
+    for i, (label, x) in enumerate(samples):
+        f.write(label + " 0:" + str(i + 1) + " ")
+
+        for j, (_, y) in enumerate(samples):
+            k = math.exp(-GAMMA * sum((a - b) ** 2 for a, b in zip(x, y)))
+            f.write(str(j + 1) + ":" + str(k) + " ")
+
+        f.write("\n")
@@ -7,7 +7,7 @@ svm_train = "/usr/local/bin/svm-train"
 svm_predict = "/usr/local/bin/svm-predict"
 problemfile = "problem.in"
 
-svm_types = {"csvm": "0", "nusvm": "1", "e_svr": "3", "nu_svr": "4"}
+svm_types = {"csvm": "0", "nusvm": "1", "one_class": "2", "e_svr": "3", "nu_svr": "4"}
 kernel_types = {"linear": "0", "poly": "1", "rbf": "2", "sigmoid": "3"}
 probabilities = {"_prob": "1", "": "0"}
 
@@ -20,6 +20,10 @@ def produce_models(path):
     for svm_type in svm_types.keys():
         for kernel_type in kernel_types.keys():
             for probablity in probabilities.keys():
+                # We don't support one-class probability estimates (yet).
+                if svm_type == "one_class" and probablity:
+                    continue
+
                 s = svm_types[svm_type]
                 t = kernel_types[kernel_type]
                 b = probabilities[probablity]
@@ -34,5 +38,22 @@ def produce_models(path):
                     [svm_predict, "-b", b, f"{path}/{problemfile}", modelfile, predictionfile])
 
 
+def produce_precomputed_models(path):
+    for svm_type in ["csvm", "nusvm", "e_svr", "nu_svr"]:
+        for probablity in probabilities.keys():
+            s = svm_types[svm_type]
+            b = probabilities[probablity]
+
+            modelfile = f"{path}/m_{svm_type}_precomputed{probablity}.libsvm"
+            predictionfile = f"{modelfile}-predicted"
+
+            subprocess.run([svm_train, "-s", s, "-t",
+                            "4", "-b", b, f"{path}/{problemfile}", modelfile])
+
+            subprocess.run(
+                [svm_predict, "-b", b, f"{path}/{problemfile}", modelfile, predictionfile])
+
+
 produce_models("data_sparse")
 produce_models("data_dense")
+produce_precomputed_models("data_precomputed")
@@ -0,0 +1,121 @@
+use ffsvm::{DenseSVM, Error, FeatureVector, Label, Predict};
+use simd_aligned::{arch::f32x8, MatSimd, Rows};
+use std::{fs, path::PathBuf};
+
+/// Returns the content of all models in the given test data folder.
+fn models(folder: &str) -> Vec<(PathBuf, String)> {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests").join(folder);
+    let mut models = fs::read_dir(path)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|x| x == "libsvm"))
+        .map(|path| {
+            let model = fs::read_to_string(&path).unwrap();
+            (path, model)
+        })
+        .collect::<Vec<_>>();
+
+    models.sort();
+    models
+}
+
+/// Produces `samples` rows of features in a range similar to our test data, deliberately not a multiple of the batch size.
+fn features(svm: &DenseSVM, samples: usize) -> MatSimd<f32x8, Rows> {
+    let mut features = MatSimd::with_dimension(samples, svm.attributes());
+    let mut flat = features.flat_mut();
+
+    for i in 0..samples {
+        for j in 0..svm.attributes() {
+            flat[(i, j)] = 0.5 + ((i * 31 + j * 17) % 23) as f32 / 23.0;
+        }
+    }
+
+    features
+}
+
+/// Predicts all samples one by one, returning their labels and probabilities.
+fn predict_one_by_one(svm: &DenseSVM, features: &MatSimd<f32x8, Rows>, probability: bool) -> Result<Vec<(Label, Vec<f64>)>, Error> {
+    let mut results = Vec::new();
+
+    for i in 0..features.dimension().0 {
+        let mut problem = FeatureVector::from(svm);
+        problem.features().copy_from_slice(&features.row_as_flat(i)[..svm.attributes()]);
+
+        if probability {
+            svm.predict_probability(&mut problem)?;
+        } else {
+            svm.predict_value(&mut problem)?;
+        }
+
+        results.push((problem.label(), problem.probabilities().to_vec()));
+    }
+
+    Ok(results)
+}
+
+#[cfg(test)]
+mod svm_batch {
+    use super::{features, models, predict_one_by_one};
+    use ffsvm::{DenseSVM, Error, Label, SAMPLE_MODEL};
+    use simd_aligned::{arch::f64x4, MatSimd, Rows};
+    use std::convert::TryFrom;
+
+    #[test]
+    fn batch_matches_one_by_one() -> Result<(), Error> {
+        let mut models = models("data_dense");
+        models.push(("model_large".into(), include_str!("data_misc/model_large.libsvm").to_owned()));
+
+        for (path, model) in models {
+            let svm = DenseSVM::try_from(model.as_str())?;
+            let features = features(&svm, 37);
+
+            let mut labels = vec![Label::None; 37];
+            svm.predict_values_batch(&features, &mut labels)?;
+
+            let expected = predict_one_by_one(&svm, &features, false)?;
+            assert_eq!(labels, expected.iter().map(|(label, _)| *label).collect::<Vec<_>>(), "{path:?}");
+
+            if !model.contains("probA") {
+                continue;
+            }
+
+            let mut probabilities = MatSimd::<f64x4, Rows>::with_dimension(37, svm.classes());
+            svm.predict_probabilities_batch(&features, &mut labels, &mut probabilities)?;
+
+            for (i, (label, probability)) in predict_one_by_one(&svm, &features, true)?.iter().enumerate() {
+                assert_eq!(labels[i], *label, "{path:?}");
+                assert_eq!(&probabilities.row_as_flat(i)[..svm.classes()], probability.as_slice(), "{path:?}");
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn empty_batch() -> Result<(), Error> {
+        let svm = DenseSVM::try_from(SAMPLE_MODEL)?;
+
+        svm.predict_values_batch(&features(&svm, 0), &mut [])?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn dimension_mismatch() -> Result<(), Error> {
+        let svm = DenseSVM::try_from(SAMPLE_MODEL)?;
+        let mut labels = vec![Label::None; 10];
+
+        let result = svm.predict_values_batch(&features(&svm, 9), &mut labels);
+        assert!(matches!(result, Err(Error::BatchDimensionMismatch { expected: (10, _), found: (9, _) })));
+
+        let wrong_attributes = MatSimd::with_dimension(10, svm.attributes() + 1);
+        let result = svm.predict_values_batch(&wrong_attributes, &mut labels);
+        assert!(matches!(result, Err(Error::BatchDimensionMismatch { .. })));
+
+        let mut probabilities = MatSimd::<f64x4, Rows>::with_dimension(10, svm.classes() + 1);
+        let result = svm.predict_probabilities_batch(&features(&svm, 10), &mut labels, &mut probabilities);
+        assert!(matches!(result, Err(Error::BatchDimensionMismatch { .. })));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,126 @@
+use ffsvm::{DenseSVM, Error, ModelFile};
+use std::{convert::TryFrom, fs, path::PathBuf};
+
+/// Returns the content of all models in the given test data folder.
+fn models(folder: &str) -> Vec<(PathBuf, String)> {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests").join(folder);
+    let mut models = fs::read_dir(path)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|x| x == "libsvm"))
+        .map(|path| {
+            let model = fs::read_to_string(&path).unwrap();
+            (path, model)
+        })
+        .collect::<Vec<_>>();
+
+    models.sort();
+    models
+}
+
+/// Saves the SVM in the binary format, loads it again and makes sure nothing changed.
+fn round_trip(svm: &DenseSVM) -> Result<DenseSVM, Error> {
+    let bytes = svm.to_bytes();
+    let reloaded = DenseSVM::try_from(bytes.as_slice())?;
+
+    assert_eq!(ModelFile::from(svm).to_string(), ModelFile::from(&reloaded).to_string());
+    assert_eq!(bytes, reloaded.to_bytes());
+
+    Ok(reloaded)
+}
+
+#[cfg(test)]
+mod svm_binary {
+    use super::{models, round_trip};
+    use ffsvm::{DenseSVM, Error, FeatureVector, Predict, SAMPLE_MODEL};
+    use std::convert::TryFrom;
+
+    #[test]
+    fn dense_models_round_trip() -> Result<(), Error> {
+        for (path, model) in models("data_dense") {
+            let svm = DenseSVM::try_from(model.as_str())?;
+            let reloaded = round_trip(&svm)?;
+
+            let mut problem = FeatureVector::from(&svm);
+            let mut problem_reloaded = FeatureVector::from(&reloaded);
+            let features = [1.287_784_9, 0.986_031_7, 1.486_247_2, 1.128_083, 0.891_030_55, 1.164_363_4, 0.928_599_1, 1.140_762_9];
+
+            problem.features().clone_from_slice(&features);
+            problem_reloaded.features().clone_from_slice(&features);
+
+            svm.predict_probability(&mut problem).or_else(|_| svm.predict_value(&mut problem))?;
+            reloaded.predict_probability(&mut problem_reloaded).or_else(|_| reloaded.predict_value(&mut problem_reloaded))?;
+
+            assert_eq!(problem.label(), problem_reloaded.label(), "{path:?}");
+            assert_eq!(problem.raw_value(), problem_reloaded.raw_value(), "{path:?}");
+            assert_eq!(problem.probabilities(), problem_reloaded.probabilities(), "{path:?}");
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn large_model_round_trip() -> Result<(), Error> {
+        let svm = DenseSVM::try_from(include_str!("data_misc/model_large.libsvm"))?;
+        let reloaded = round_trip(&svm)?;
+
+        assert_eq!(svm.attributes(), reloaded.attributes());
+        assert_eq!(svm.classes(), reloaded.classes());
+
+        Ok(())
+    }
+
+    #[test]
+    fn text_is_not_binary() {
+        let result = DenseSVM::try_from(SAMPLE_MODEL.as_bytes());
+
+        assert!(matches!(result, Err(Error::BinaryInvalidMagic)));
+    }
+
+    #[test]
+    fn truncated() -> Result<(), Error> {
+        let bytes = DenseSVM::try_from(SAMPLE_MODEL)?.to_bytes();
+
+        assert!(matches!(DenseSVM::try_from(&bytes[..0]), Err(Error::BinaryInvalidMagic)));
+        assert!(matches!(DenseSVM::try_from(&bytes[..20]), Err(Error::BinaryTruncated { expected: 64, found: 20 })));
+
+        for length in [64, 100, bytes.len() - 1] {
+            match DenseSVM::try_from(&bytes[..length]) {
+                Err(Error::BinaryTruncated { expected, found }) => {
+                    assert_eq!(expected, bytes.len());
+                    assert_eq!(found, length);
+                }
+                _ => panic!("truncating to {length} bytes must fail"),
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn corrupted() -> Result<(), Error> {
+        let bytes = DenseSVM::try_from(SAMPLE_MODEL)?.to_bytes();
+
+        let mut payload = bytes.clone();
+        payload[bytes.len() - 100] ^= 0x10;
+        assert!(matches!(DenseSVM::try_from(payload.as_slice()), Err(Error::BinaryChecksumMismatch { .. })));
+
+        let mut magic = bytes.clone();
+        magic[0] = b'X';
+        assert!(matches!(DenseSVM::try_from(magic.as_slice()), Err(Error::BinaryInvalidMagic)));
+
+        let mut version = bytes.clone();
+        version[8] = 99;
+        assert!(matches!(DenseSVM::try_from(version.as_slice()), Err(Error::BinaryUnsupportedVersion(99))));
+
+        let mut svm_type = bytes.clone();
+        svm_type[12] = 5;
+        assert!(matches!(DenseSVM::try_from(svm_type.as_slice()), Err(Error::BinaryInvalidHeader("svm_type"))));
+
+        let mut trailing = bytes;
+        trailing.push(0);
+        assert!(matches!(DenseSVM::try_from(trailing.as_slice()), Err(Error::BinaryInvalidHeader("payload_length"))));
+
+        Ok(())
+    }
+}
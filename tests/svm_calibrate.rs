@@ -0,0 +1,130 @@
+#[cfg(test)]
+mod svm_calibrate {
+    use ffsvm::{DataReader, DenseSVM, Error, FeatureVector, Label, ModelFile, Predict, SparseSVM, Svm};
+    use std::convert::TryFrom;
+
+    const DENSE_PROBLEMS: &str = include_str!("data_dense/problem.in");
+    const SPARSE_PROBLEMS: &str = include_str!("data_sparse/problem.in");
+
+    /// Checks the gradient of the sigmoid fit of each class pair vanishes, i.e., the fit converged.
+    fn assert_fitted<T>(model: &ModelFile<'_>, samples: &[(f64, FeatureVector<T>)]) {
+        let a = model.header().prob_a.as_ref().unwrap();
+        let b = model.header().prob_b.as_ref().unwrap();
+        let num_classes = model.header().nr_class as usize;
+        let mut pair = 0;
+
+        for i in 0..num_classes {
+            for j in i + 1..num_classes {
+                let pair_samples = samples
+                    .iter()
+                    .filter(|(label, _)| *label as usize == i || *label as usize == j)
+                    .map(|(label, problem)| (problem.decision_value(i, j), *label as usize == i))
+                    .collect::<Vec<_>>();
+
+                let num_positive = pair_samples.iter().filter(|(_, positive)| *positive).count() as f64;
+                let num_negative = pair_samples.len() as f64 - num_positive;
+                let (mut g1, mut g2) = (0.0_f64, 0.0_f64);
+
+                for (f, positive) in pair_samples {
+                    let target = if positive { (num_positive + 1.0) / (num_positive + 2.0) } else { 1.0 / (num_negative + 2.0) };
+                    let p = 1.0 / (1.0 + a[pair].mul_add(f, b[pair]).exp());
+                    g1 += f * (target - p);
+                    g2 += target - p;
+                }
+
+                assert!(g1.abs() < 1e-4 && g2.abs() < 1e-4, "pair ({i}, {j}) not fitted, gradient ({g1}, {g2})");
+                pair += 1;
+            }
+        }
+    }
+
+    #[test]
+    fn dense() -> Result<(), Error> {
+        let mut svm = DenseSVM::try_from(include_str!("data_dense/m_csvm_rbf.libsvm"))?;
+        let mut samples = DataReader::new(DENSE_PROBLEMS.as_bytes(), &svm).collect::<Result<Vec<_>, _>>()?;
+
+        assert!(matches!(svm.predict_probability(&mut samples[0].1), Err(Error::NoProbabilities)));
+
+        svm.calibrate_probabilities(&mut samples)?;
+        assert_fitted(&ModelFile::from(&svm), &samples);
+
+        for (label, problem) in &mut samples {
+            svm.predict_probability(problem)?;
+
+            assert_eq!(problem.label(), Label::Class(*label as i32));
+            assert!((problem.probabilities().iter().sum::<f64>() - 1.0).abs() < 1e-6);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn sparse_round_trip() -> Result<(), Error> {
+        let mut svm = SparseSVM::try_from(include_str!("data_sparse/m_nusvm_linear.libsvm"))?;
+        let mut samples = DataReader::new(SPARSE_PROBLEMS.as_bytes(), &svm).collect::<Result<Vec<_>, _>>()?;
+
+        svm.calibrate_probabilities(&mut samples)?;
+
+        let model = ModelFile::from(&svm);
+        assert_fitted(&model, &samples);
+
+        let reloaded = SparseSVM::try_from(model.to_string().as_str())?;
+        let (_, problem) = &mut samples[9];
+        let mut copy = problem.clone();
+
+        svm.predict_probability(problem)?;
+        reloaded.predict_probability(&mut copy)?;
+
+        assert_eq!(problem.label(), copy.label());
+        assert!(problem.probabilities().iter().zip(copy.probabilities()).all(|(a, b)| (a - b).abs() < 1e-6));
+
+        Ok(())
+    }
+
+    #[test]
+    fn unified() -> Result<(), Error> {
+        let mut svm = Svm::try_from(include_str!("data_dense/m_csvm_linear.libsvm"))?;
+        let mut samples = DataReader::new(DENSE_PROBLEMS.as_bytes(), &svm).collect::<Result<Vec<_>, _>>()?;
+
+        svm.calibrate_probabilities(&mut samples)?;
+        assert_fitted(&ModelFile::from(&svm), &samples);
+
+        svm.predict_probability(&mut samples[31].1)?;
+        assert_eq!(samples[31].1.label(), Label::Class(7));
+
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_samples() -> Result<(), Error> {
+        let mut svm = DenseSVM::try_from(include_str!("data_dense/m_csvm_rbf.libsvm"))?;
+        let mut samples = DataReader::new(DENSE_PROBLEMS.as_bytes(), &svm).collect::<Result<Vec<_>, _>>()?;
+
+        assert!(matches!(svm.calibrate_probabilities(&mut []), Err(Error::InvalidCalibrationData("no samples"))));
+
+        samples[3].0 = 9.0;
+        assert!(matches!(svm.calibrate_probabilities(&mut samples), Err(Error::InvalidCalibrationData("labels not in the model"))));
+
+        // All classes need samples, otherwise their probabilities against other classes would be fixed at `0.5`.
+        let mut samples = DataReader::new(DENSE_PROBLEMS.as_bytes(), &svm).collect::<Result<Vec<_>, _>>()?;
+        samples.retain(|(label, _)| *label != 5.0);
+        assert!(matches!(svm.calibrate_probabilities(&mut samples), Err(Error::InvalidCalibrationData("a class without samples"))));
+
+        // Perfect regression predictions give a `sigma` of `0`, which is no distribution.
+        let mut svr = DenseSVM::try_from(include_str!("data_dense/m_e_svr_linear.libsvm"))?;
+        let mut samples = DataReader::new(DENSE_PROBLEMS.as_bytes(), &svr).collect::<Result<Vec<_>, _>>()?;
+
+        for (label, problem) in &mut samples {
+            svr.predict_value(problem)?;
+            *label = problem.raw_value();
+        }
+
+        assert!(matches!(svr.calibrate_probabilities(&mut samples), Err(Error::InvalidCalibrationData("no prediction errors"))));
+
+        let mut one_class = DenseSVM::try_from(include_str!("data_dense/m_one_class_rbf.libsvm"))?;
+        let mut samples = DataReader::new(DENSE_PROBLEMS.as_bytes(), &one_class).collect::<Result<Vec<_>, _>>()?;
+        assert!(matches!(one_class.calibrate_probabilities(&mut samples), Err(Error::InvalidCalibrationData("a one-class SVM"))));
+
+        Ok(())
+    }
+}
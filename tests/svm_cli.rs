@@ -0,0 +1,194 @@
+#![cfg(feature = "cli")]
+
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+/// Returns all models in the given test data folder.
+fn models(folder: &str) -> Vec<PathBuf> {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests").join(folder);
+    let mut models = fs::read_dir(path)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|x| x == "libsvm"))
+        .collect::<Vec<_>>();
+
+    models.sort();
+    models
+}
+
+/// Runs `ffsvm-predict` with the given options, returning its output file and what it printed.
+fn predict(options: &[&str], test_file: &Path, model_file: &Path) -> (String, String) {
+    let name = format!("ffsvm-predict-{}-{}", std::process::id(), model_file.file_name().unwrap().to_string_lossy());
+    let output_file = env::temp_dir().join(name);
+
+    let result = Command::new(env!("CARGO_BIN_EXE_ffsvm-predict"))
+        .args(options)
+        .arg(test_file)
+        .arg(model_file)
+        .arg(&output_file)
+        .output()
+        .unwrap();
+
+    assert!(result.status.success(), "{model_file:?}: {}", String::from_utf8_lossy(&result.stderr));
+
+    let output = fs::read_to_string(&output_file).unwrap();
+    fs::remove_file(&output_file).unwrap();
+
+    (output, String::from_utf8(result.stdout).unwrap())
+}
+
+/// Checks that two `svm-predict` output files agree, allowing for our `f32` precision.
+fn assert_similar(ours: &str, libsvm: &str, path: &Path) {
+    assert_eq!(ours.lines().count(), libsvm.lines().count(), "{path:?}");
+
+    for (ours, libsvm) in ours.lines().zip(libsvm.lines()) {
+        if libsvm.starts_with("labels") {
+            assert_eq!(ours, libsvm, "{path:?}");
+            continue;
+        }
+
+        let ours = ours.split_whitespace().map(|x| x.parse::<f64>().unwrap()).collect::<Vec<_>>();
+        let libsvm = libsvm.split_whitespace().map(|x| x.parse::<f64>().unwrap()).collect::<Vec<_>>();
+
+        assert_eq!(ours.len(), libsvm.len(), "{path:?}");
+
+        for (a, b) in ours.iter().zip(&libsvm) {
+            assert!((a - b).abs() <= 0.001 * a.abs().max(b.abs()).max(1.0), "{a} != {b} in {path:?}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod svm_cli {
+    use super::{assert_similar, models, predict};
+    use std::{fs, path::Path, process::Command};
+
+    fn matches_libsvm(folder: &str, options: &[&str]) {
+        let test_file = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join(folder).join("problem.in");
+
+        for model in models(folder) {
+            let name = model.file_name().unwrap().to_string_lossy().to_string();
+            let probability = name.contains("_prob") && !name.contains("one_class");
+            let options = [options, if probability { &["-b", "1"] } else { &[] }].concat();
+
+            let (output, stdout) = predict(&options, &test_file, &model);
+            let expected = fs::read_to_string(model.with_file_name(format!("{name}-predicted"))).unwrap();
+
+            assert_similar(&output, &expected, &model);
+
+            if name.contains("svr") {
+                assert!(stdout.contains("Mean squared error = "), "{stdout}");
+                assert!(stdout.contains("Squared correlation coefficient = "), "{stdout}");
+            } else {
+                assert!(stdout.contains("Accuracy = "), "{stdout}");
+            }
+        }
+    }
+
+    #[test]
+    fn dense() {
+        matches_libsvm("data_dense", &[]);
+    }
+
+    #[test]
+    fn sparse() {
+        matches_libsvm("data_sparse", &["-d", "sparse"]);
+    }
+
+    #[test]
+    fn precomputed() {
+        matches_libsvm("data_precomputed", &[]);
+    }
+
+    #[test]
+    fn accuracy() {
+        let folder = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("data_dense");
+        let (output, stdout) = predict(&[], &folder.join("problem.in"), &folder.join("m_csvm_rbf.libsvm"));
+        let targets = fs::read_to_string(folder.join("problem.in")).unwrap();
+        let correct = output.lines().zip(targets.lines()).filter(|(a, b)| b.split_whitespace().next() == Some(a)).count();
+
+        assert!(stdout.contains(&format!("({correct}/32) (classification)")), "{stdout}");
+        assert!(predict(&["-q"], &folder.join("problem.in"), &folder.join("m_csvm_rbf.libsvm")).1.is_empty());
+    }
+
+    #[test]
+    fn features_unused_by_model() {
+        let folder = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("data_dense");
+        let test_file = std::env::temp_dir().join(format!("ffsvm-predict-unused-{}.in", std::process::id()));
+        let problems = fs::read_to_string(folder.join("problem.in")).unwrap();
+
+        // No support vector has attribute 20, so a dense SVM can't hold it.
+        fs::write(&test_file, problems.lines().map(|x| format!("{x} 20:0.5\n")).collect::<String>()).unwrap();
+
+        for model in ["m_csvm_rbf.libsvm", "m_e_svr_linear.libsvm"] {
+            let (dense, _) = predict(&[], &test_file, &folder.join(model));
+            let (sparse, _) = predict(&["-d", "sparse"], &test_file, &folder.join(model));
+
+            assert_eq!(dense.lines().count(), 32);
+            assert_eq!(dense, sparse);
+        }
+
+        fs::remove_file(&test_file).unwrap();
+    }
+
+    #[test]
+    fn precomputed_training_instances_without_support_vector() {
+        // Rows hold kernel values of all 4 training instances, only the first 2 are support vectors.
+        let folder = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("data_misc");
+        let (output, stdout) = predict(&[], &folder.join("problem_precomputed_partial.in"), &folder.join("model_precomputed_partial.libsvm"));
+
+        assert_eq!(output, "1\n-1\n1\n-1\n");
+        assert!(stdout.contains("(4/4) (classification)"), "{stdout}");
+    }
+
+    #[test]
+    fn invalid_arguments() {
+        for args in [&["-b"][..], &["-d", "diagonal", "a", "b", "c"], &["-x", "a", "b", "c"], &["a", "b"]] {
+            let result = Command::new(env!("CARGO_BIN_EXE_ffsvm-predict")).args(args).output().unwrap();
+
+            assert!(!result.status.success(), "{args:?}");
+            assert!(String::from_utf8_lossy(&result.stderr).starts_with("Usage: ffsvm-predict"), "{args:?}");
+        }
+    }
+
+    #[test]
+    fn inspect() {
+        let model = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("data_sparse").join("m_csvm_rbf.libsvm");
+        let result = Command::new(env!("CARGO_BIN_EXE_ffsvm-inspect")).arg(&model).output().unwrap();
+        let stdout = String::from_utf8(result.stdout).unwrap();
+
+        assert!(result.status.success());
+        assert!(stdout.contains("total_sv        32\n"), "{stdout}");
+        assert!(stdout.contains("recommended     SparseSVM\n"), "{stdout}");
+
+        let missing = Command::new(env!("CARGO_BIN_EXE_ffsvm-inspect")).output().unwrap();
+        assert!(!missing.status.success());
+
+        let huge = std::env::temp_dir().join(format!("ffsvm-inspect-huge-{}.libsvm", std::process::id()));
+        fs::write(&huge, include_str!("data_dense/m_csvm_rbf.libsvm").replacen("7:0.0001 \n", "7:0.0001 4000000000:1\n", 1)).unwrap();
+
+        let result = Command::new(env!("CARGO_BIN_EXE_ffsvm-inspect")).arg(&huge).output().unwrap();
+        fs::remove_file(&huge).unwrap();
+
+        assert!(!result.status.success());
+        assert!(String::from_utf8_lossy(&result.stderr).contains("at most"), "{result:?}");
+    }
+
+    #[test]
+    fn no_probabilities() {
+        let folder = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("data_dense");
+        let result = Command::new(env!("CARGO_BIN_EXE_ffsvm-predict"))
+            .args(["-b", "1"])
+            .arg(folder.join("problem.in"))
+            .arg(folder.join("m_csvm_rbf.libsvm"))
+            .arg(std::env::temp_dir().join("ffsvm-predict-no-probabilities"))
+            .output()
+            .unwrap();
+
+        assert!(!result.status.success());
+        assert!(String::from_utf8_lossy(&result.stderr).contains("does not support probability estimates"));
+    }
+}
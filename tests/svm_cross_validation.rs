@@ -0,0 +1,118 @@
+#![cfg(feature = "train")]
+
+#[cfg(test)]
+mod svm_cross_validation {
+    use ffsvm::{Dataset, Error, Folds, GridSearch, KernelType, SVMType, Score, Trainer};
+    use std::convert::TryFrom;
+
+    const PROBLEMS: &str = include_str!("data_dense/problem.in");
+
+    #[test]
+    fn stratified_classification() -> Result<(), Error> {
+        let data = Dataset::try_from(PROBLEMS)?;
+        let result = Trainer::default().with_c(8.0).cross_validate(&data, Folds::stratified(4))?;
+
+        assert_eq!(result.predictions().len(), data.len());
+        assert!(result.predictions().iter().all(|x| (0.0..8.0).contains(x) && x.fract() == 0.0));
+
+        let correct = result.predictions().iter().zip(data.labels()).filter(|(p, y)| p == y).count();
+        assert_eq!(result.score(), Score::Accuracy(correct as f64 / 32.0));
+
+        // The same seed gives the same folds, and thus the same predictions.
+        assert_eq!(Trainer::default().with_c(8.0).cross_validate(&data, Folds::stratified(4))?, result);
+
+        Ok(())
+    }
+
+    #[test]
+    fn k_fold_and_leave_one_out() -> Result<(), Error> {
+        let data = Dataset::try_from("1 0:1\n1 0:0.8\n1 0:0.5\n1 0:0.9\n-1 0:-1\n-1 0:-0.7\n-1 0:-0.2\n-1 0:-0.9\n")?;
+        let trainer = Trainer::default().with_kernel(KernelType::Linear);
+
+        assert_eq!(trainer.cross_validate(&data, Folds::stratified(2).with_seed(7))?.score(), Score::Accuracy(1.0));
+        assert_eq!(trainer.cross_validate(&data, Folds::new(100))?.score(), Score::Accuracy(1.0));
+
+        Ok(())
+    }
+
+    #[test]
+    fn regression() -> Result<(), Error> {
+        let data = Dataset::try_from(PROBLEMS)?;
+        let result = Trainer::new(SVMType::ESvr).cross_validate(&data, Folds::new(5))?;
+
+        let mse = result.predictions().iter().zip(data.labels()).map(|(p, y)| (p - y).powi(2)).sum::<f64>() / 32.0;
+        assert!(matches!(result.score(), Score::MeanSquaredError(x) if (x - mse).abs() < 1e-12));
+
+        // Stratification only applies to classification.
+        assert_eq!(Trainer::new(SVMType::ESvr).cross_validate(&data, Folds::stratified(5))?, result);
+
+        Ok(())
+    }
+
+    #[test]
+    fn invalid() -> Result<(), Error> {
+        let data = Dataset::try_from(PROBLEMS)?;
+
+        assert!(matches!(Trainer::default().cross_validate(&data, Folds::new(1)), Err(Error::InvalidParameter("folds"))));
+        assert!(matches!(Trainer::default().with_c(-1.0).cross_validate(&data, Folds::new(5)), Err(Error::InvalidParameter("C"))));
+        assert!(matches!(Trainer::default().cross_validate(&Dataset::new(), Folds::new(5)), Err(Error::InvalidTrainingData("no samples"))));
+
+        Ok(())
+    }
+
+    #[test]
+    fn scores() {
+        assert!(Score::Accuracy(0.9).is_better_than(Score::Accuracy(0.8)));
+        assert!(Score::MeanSquaredError(0.1).is_better_than(Score::MeanSquaredError(0.2)));
+        assert!(!Score::Accuracy(0.9).is_better_than(Score::MeanSquaredError(0.2)));
+        assert_eq!(Score::MeanSquaredError(0.5).value(), 0.5);
+    }
+
+    #[test]
+    fn grid_search() -> Result<(), Error> {
+        let data = Dataset::try_from(PROBLEMS)?;
+        let result = GridSearch::new(Trainer::default()).with_log2_c(-5.0, 15.0, 4.0).with_log2_gamma(3.0, -15.0, -6.0).run(&data)?;
+
+        assert_eq!(result.points().len(), 6 * 4);
+        assert_eq!(result.points()[0].trainer().c(), 2.0_f64.powi(-5));
+        assert_eq!(result.points()[0].trainer().gamma(), Some(8.0));
+        assert_eq!(result.points()[23].trainer().c(), 2.0_f64.powi(15));
+        assert_eq!(result.points()[23].trainer().gamma(), Some(2.0_f64.powi(-15)));
+
+        let best = result.best();
+        assert!(result.points().iter().all(|x| !x.score().is_better_than(best.score())));
+        assert_eq!(best.trainer().cross_validate(&data, Folds::stratified(5))?.score(), best.score());
+
+        Ok(())
+    }
+
+    #[test]
+    #[should_panic(expected = "Step must lead from begin to end.")]
+    fn grid_search_step_away_from_end() {
+        let _ = GridSearch::new(Trainer::default()).with_log2_c(-5.0, 15.0, -2.0);
+    }
+
+    #[test]
+    fn grid_search_single_value() {
+        let search = GridSearch::new(Trainer::default()).with_log2_gamma(1.0, 1.0, -2.0);
+        assert_eq!(search, GridSearch::new(Trainer::default()).with_gamma([2.0]));
+    }
+
+    #[test]
+    fn grid_search_other_parameters() -> Result<(), Error> {
+        let data = Dataset::try_from(PROBLEMS)?;
+        let trainer = Trainer::new(SVMType::NuSvr).with_kernel(KernelType::Poly);
+        let result = GridSearch::new(trainer).with_degree([2, 3]).with_coef0([0.0, 1.0]).with_nu([0.25, 0.5, 0.75]).with_folds(Folds::new(4)).run(&data)?;
+
+        assert_eq!(result.points().len(), 2 * 2 * 3);
+        assert!(result.points().iter().all(|x| matches!(x.score(), Score::MeanSquaredError(_))));
+        assert_eq!((result.points()[5].trainer().degree(), result.points()[5].trainer().coef0(), result.points()[5].trainer().nu()), (2, 1.0, 0.75));
+
+        // Infeasible `nu` of a ν-SVC fails the search.
+        let unbalanced = Dataset::try_from("1 0:1\n1 0:0.5\n-1 0:-1\n-1 0:-0.5\n-1 0:-0.25\n-1 0:-0.75\n-1 0:-0.1\n-1 0:-0.6\n")?;
+        let search = GridSearch::new(Trainer::new(SVMType::NuSvc)).with_nu([0.1, 0.9]).with_folds(Folds::stratified(2));
+        assert!(matches!(search.run(&unbalanced), Err(Error::InvalidParameter("nu"))));
+
+        Ok(())
+    }
+}
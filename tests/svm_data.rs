@@ -0,0 +1,160 @@
+#[cfg(test)]
+mod svm_data {
+    use ffsvm::{DataReader, DenseSVM, Error, FeatureVector, Indexing, Label, ParseContext, PrecomputedSVM, Predict, SparseSVM};
+    use std::convert::TryFrom;
+
+    /// Returns the labels of a `-predicted` file.
+    fn predicted(file: &str) -> Vec<Label> {
+        file.lines()
+            .filter(|x| !x.starts_with("labels"))
+            .map(|x| Label::Class(x.split_whitespace().next().unwrap().parse().unwrap()))
+            .collect()
+    }
+
+    #[test]
+    fn dense_matches_libsvm() -> Result<(), Error> {
+        let svm = DenseSVM::try_from(include_str!("data_dense/m_csvm_rbf.libsvm"))?;
+        let mut labels = Vec::new();
+
+        for sample in DataReader::new(include_str!("data_dense/problem.in").as_bytes(), &svm) {
+            let (_, mut problem) = sample?;
+            svm.predict_value(&mut problem)?;
+            labels.push(problem.label());
+        }
+
+        assert_eq!(labels, predicted(include_str!("data_dense/m_csvm_rbf.libsvm-predicted")));
+
+        Ok(())
+    }
+
+    #[test]
+    fn sparse_matches_libsvm() -> Result<(), Error> {
+        let svm = SparseSVM::try_from(include_str!("data_sparse/m_csvm_rbf.libsvm"))?;
+        let mut labels = Vec::new();
+
+        for sample in DataReader::new(include_str!("data_sparse/problem.in").as_bytes(), &svm) {
+            let (_, mut problem) = sample?;
+            svm.predict_value(&mut problem)?;
+            labels.push(problem.label());
+        }
+
+        assert_eq!(labels, predicted(include_str!("data_sparse/m_csvm_rbf.libsvm-predicted")));
+
+        Ok(())
+    }
+
+    #[test]
+    fn precomputed_matches_libsvm() -> Result<(), Error> {
+        let svm = PrecomputedSVM::try_from(include_str!("data_precomputed/m_csvm_precomputed.libsvm"))?;
+        let mut labels = Vec::new();
+
+        for sample in DataReader::new(include_str!("data_precomputed/problem.in").as_bytes(), &svm) {
+            let (_, mut problem) = sample?;
+            svm.predict_value(&mut problem)?;
+            labels.push(problem.label());
+        }
+
+        assert_eq!(labels, predicted(include_str!("data_precomputed/m_csvm_precomputed.libsvm-predicted")));
+
+        Ok(())
+    }
+
+    #[test]
+    fn comments_and_qid() -> Result<(), Error> {
+        let svm = DenseSVM::try_from(include_str!("data_dense/m_csvm_rbf.libsvm"))?;
+        let data = "# header comment\n\n3 qid:7 1:0.5 7:2 # trailing comment\r\n-1.5\n";
+        let samples = DataReader::new(data.as_bytes(), &svm).collect::<Result<Vec<_>, _>>()?;
+
+        assert_eq!(samples.len(), 2);
+        assert_eq!(samples[0].0, 3.0);
+        assert_eq!(samples[1].0, -1.5);
+
+        let mut expected = FeatureVector::from(&svm);
+        expected.features()[1] = 0.5;
+        expected.features()[7] = 2.0;
+
+        let (_, mut first) = samples[0].clone();
+        assert_eq!(first.features(), expected.features());
+
+        let (_, mut second) = samples[1].clone();
+        assert!(second.features().iter().all(|x| *x == 0.0));
+
+        Ok(())
+    }
+
+    #[test]
+    fn one_based() -> Result<(), Error> {
+        let svm = DenseSVM::try_from(include_str!("data_dense/m_csvm_rbf.libsvm"))?;
+        let data = "1 1:0.5 8:2\n";
+        let (_, mut problem) = DataReader::new(data.as_bytes(), &svm).with_indexing(Indexing::OneBased).next().unwrap()?;
+
+        assert_eq!(problem.features(), &[0.5, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 2.0]);
+
+        let mut zero_based = DataReader::new(data.as_bytes(), &svm);
+        assert!(matches!(zero_based.next(), Some(Err(Error::FeatureIndexOutOfRange { line: 1, index: 8, num_attributes: 8 }))));
+
+        Ok(())
+    }
+
+    #[test]
+    fn sparse_features() -> Result<(), Error> {
+        let svm = SparseSVM::try_from(include_str!("data_sparse/m_csvm_rbf.libsvm"))?;
+        let (label, mut problem) = DataReader::new("2 3:0.5 100:1.5".as_bytes(), &svm).next().unwrap()?;
+
+        assert_eq!(label, 2.0);
+        assert_eq!(problem.features().iter().collect::<Vec<_>>(), vec![(3, 0.5), (100, 1.5)]);
+
+        let beyond_u32 = DataReader::new("2 3:0.5 5000000000:1".as_bytes(), &svm).next();
+        assert!(matches!(beyond_u32, Some(Err(Error::FeatureIndexOutOfRange { line: 1, index: 5_000_000_000, .. }))));
+
+        Ok(())
+    }
+
+    #[test]
+    fn precomputed_training_instances_without_support_vector() -> Result<(), Error> {
+        // Only instances 1 and 2 of 4 are support vectors, but rows hold kernel values of all of them.
+        let svm = PrecomputedSVM::try_from(include_str!("data_misc/model_precomputed_partial.libsvm"))?;
+        let mut samples = DataReader::new(include_str!("data_misc/problem_precomputed_partial.in").as_bytes(), &svm).collect::<Result<Vec<_>, _>>()?;
+        let mut labels = Vec::new();
+
+        assert_eq!(samples[0].1.features(), &[1.0, 1.0, 0.2]);
+
+        for (_, problem) in &mut samples {
+            svm.predict_value(problem)?;
+            labels.push(problem.label());
+        }
+
+        assert_eq!(labels, vec![Label::Class(1), Label::Class(-1), Label::Class(1), Label::Class(-1)]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn errors() -> Result<(), Error> {
+        let svm = DenseSVM::try_from(include_str!("data_dense/m_csvm_rbf.libsvm"))?;
+
+        for (data, line, column, token) in [
+            ("a 0:1\n", 1, 1, "a"),
+            ("1 0:1\n\n1 2:1 1:1\n", 3, 7, "1"),
+            ("1 0:1 0:2\n", 1, 7, "0"),
+            ("# comment\n1 0:x\n", 2, 5, "x"),
+            ("1 3\n", 1, 3, "3"),
+            ("1 -1:0.5\n", 1, 3, "-1"),
+        ] {
+            match DataReader::new(data.as_bytes(), &svm).find_map(Result::err) {
+                Some(Error::ParsingToken { line: l, column: c, token: t, context: ParseContext::DataFile }) => {
+                    assert_eq!((l, c, t.as_str()), (line, column, token), "{data:?}");
+                }
+                x => panic!("Expected error for {data:?}, got {x:?}"),
+            }
+        }
+
+        let one_based = DataReader::new("1 0:1\n".as_bytes(), &svm).with_indexing(Indexing::OneBased).next();
+        assert!(matches!(one_based, Some(Err(Error::ParsingToken { line: 1, column: 3, .. }))));
+
+        let invalid_utf8 = DataReader::new(&[b'1', b' ', 0xff, b'\n'][..], &svm).next();
+        assert!(matches!(invalid_utf8, Some(Err(Error::Io(_)))));
+
+        Ok(())
+    }
+}
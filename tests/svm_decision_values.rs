@@ -0,0 +1,120 @@
+#[cfg(test)]
+mod svm_decision_values {
+    use ffsvm::{DenseSVM, Error, FeatureVector, Label, Predict, SparseSVM, SAMPLE_MODEL};
+    use std::convert::TryFrom;
+
+    #[test]
+    fn multiclass_values_explain_label() -> Result<(), Error> {
+        let svm = DenseSVM::try_from(include_str!("data_dense/m_csvm_rbf.libsvm"))?;
+
+        let mut problem = FeatureVector::from(&svm);
+        problem.features().clone_from_slice(&[1.287_784_9, 0.986_031_7, 1.486_247_2, 1.128_083, 0.891_030_55, 1.164_363_4, 0.928_599_1, 1.140_762_9]);
+        svm.predict_value(&mut problem)?;
+
+        let decision_values = problem.decision_values().collect::<Vec<_>>();
+        let mut votes = vec![0; svm.classes()];
+        let mut pairs = Vec::new();
+
+        assert_eq!(decision_values.len(), 8 * 7 / 2);
+
+        // Values come in libSVM's order, and each of them is a vote for one of its classes.
+        for i in 0..svm.classes() {
+            for j in i + 1..svm.classes() {
+                let value = problem.decision_value(i, j);
+
+                assert_eq!(problem.decision_value(j, i), -value);
+                votes[if value > 0.0 { i } else { j }] += 1;
+                pairs.push((svm.class_label_for_index(i).unwrap(), svm.class_label_for_index(j).unwrap(), value));
+            }
+        }
+
+        assert_eq!(decision_values, pairs);
+        assert_eq!(problem.votes(), votes.as_slice());
+
+        let winner = votes.iter().enumerate().rev().max_by_key(|(_, votes)| **votes).unwrap().0;
+        assert_eq!(problem.label(), Label::Class(svm.class_label_for_index(winner).unwrap()));
+
+        Ok(())
+    }
+
+    #[test]
+    fn binary_value_matches_labels() -> Result<(), Error> {
+        let svm = DenseSVM::try_from(SAMPLE_MODEL)?;
+
+        let mut problem = FeatureVector::from(&svm);
+        let features = problem.features();
+        features[0] = 0.558_38;
+        features[1] = -0.157_895;
+        features[2] = 0.581_292;
+        features[3] = -0.221_184;
+
+        svm.predict_value(&mut problem)?;
+
+        let decision_values = problem.decision_values().collect::<Vec<_>>();
+
+        assert_eq!(problem.label(), Label::Class(42));
+        assert_eq!(decision_values.len(), 1);
+        assert_eq!((decision_values[0].0, decision_values[0].1), (21, 42));
+        assert!(decision_values[0].2 < 0.0);
+        assert_eq!(problem.votes(), &[0, 1]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn kernel_values() -> Result<(), Error> {
+        let svm = SparseSVM::try_from(include_str!("data_sparse/m_csvm_rbf.libsvm"))?;
+
+        let mut problem = FeatureVector::from(&svm);
+        problem.features()[3] = 0.930_907_6;
+        problem.features()[4] = 1.264_398_9;
+
+        assert_eq!(problem.kernel_values(0), None);
+        svm.predict_value(&mut problem)?;
+
+        let mut total_sv = 0;
+
+        for class in 0..svm.classes() {
+            let kernel_values = problem.kernel_values(class).unwrap();
+
+            assert!(!kernel_values.is_empty());
+            assert!(kernel_values.iter().all(|x| *x > 0.0 && *x <= 1.0));
+            total_sv += kernel_values.len();
+        }
+
+        assert!(include_str!("data_sparse/m_csvm_rbf.libsvm").contains(&format!("total_sv {total_sv}\n")));
+
+        Ok(())
+    }
+
+    #[test]
+    fn no_kernel_values_for_linear_models() -> Result<(), Error> {
+        let classifier = DenseSVM::try_from(include_str!("data_dense/m_csvm_linear.libsvm"))?;
+        let mut problem = FeatureVector::from(&classifier);
+        classifier.predict_value(&mut problem)?;
+
+        assert_eq!(problem.kernel_values(0), None);
+
+        let regression = SparseSVM::try_from(include_str!("data_sparse/m_e_svr_linear.libsvm"))?;
+        let mut problem = FeatureVector::from(&regression);
+        regression.predict_value(&mut problem)?;
+
+        assert_eq!(problem.kernel_values(0), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn regression_has_raw_value_only() -> Result<(), Error> {
+        let svm = DenseSVM::try_from(include_str!("data_dense/m_e_svr_rbf.libsvm"))?;
+
+        let mut problem = FeatureVector::from(&svm);
+        problem.features().clone_from_slice(&[1.287_784_9, 0.986_031_7, 1.486_247_2, 1.128_083, 0.891_030_55, 1.164_363_4, 0.928_599_1, 1.140_762_9]);
+        svm.predict_value(&mut problem)?;
+
+        assert_eq!(problem.decision_values().count(), 0);
+        assert_eq!(problem.label(), Label::Value(problem.raw_value() as f32));
+
+        Ok(())
+    }
+}
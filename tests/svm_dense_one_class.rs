@@ -0,0 +1,48 @@
+fn similar(a: f64, b: f64) -> bool {
+    (a - b).abs() < 0.001 * a.abs().max(b.abs())
+}
+
+macro_rules! test_model {
+    ($name:ident, $file:expr, $libsvm:expr, $decision:expr) => {
+        #[test]
+        fn $name() -> Result<(), Error> {
+            let model = include_str!(concat!("data_dense/", $file));
+            let svm = DenseSVM::try_from(model)?;
+
+            let mut problem_0 = FeatureVector::from(&svm);
+            let features_0 = problem_0.features();
+            features_0.clone_from_slice(&[0.000_1, 0.000_1, 0.000_1, 0.000_1, 0.000_1, 0.000_1, 0.000_1, 0.000_1]);
+
+            let mut problem_7 = FeatureVector::from(&svm);
+            let features_7 = problem_7.features();
+            features_7.clone_from_slice(&[1.287_784_9, 0.986_031_7, 1.486_247_2, 1.128_083, 0.891_030_55, 1.164_363_4, 0.928_599_1, 1.140_762_9]);
+
+            svm.predict_value(&mut problem_0)?;
+            svm.predict_value(&mut problem_7)?;
+
+            assert_eq!(problem_0.label(), Label::Class($libsvm[0]), "predict_value(problem_0)");
+            assert_eq!(problem_7.label(), Label::Class($libsvm[1]), "predict_value(problem_7)");
+
+            assert!(similar(problem_0.raw_value(), $decision[0]), "raw_value(problem_0)");
+            assert!(similar(problem_7.raw_value(), $decision[1]), "raw_value(problem_7)");
+
+            // Without probability estimates `libSVM` falls back to regular prediction.
+            svm.predict_probability(&mut problem_0)?;
+            assert_eq!(problem_0.label(), Label::Class($libsvm[0]), "predict_probability(problem_0)");
+
+            Ok(())
+        }
+    };
+}
+
+#[cfg(test)]
+mod svm_dense_one_class {
+    use super::similar;
+    use ffsvm::{DenseSVM, Error, FeatureVector, Label, Predict};
+    use std::convert::TryFrom;
+
+    test_model!(m_one_class_linear, "m_one_class_linear.libsvm", [-1, 1], [-11.941_286_6, 16.365_564_8]);
+    test_model!(m_one_class_poly, "m_one_class_poly.libsvm", [-1, 1], [-0.057_079_1, 0.657_656_9]);
+    test_model!(m_one_class_rbf, "m_one_class_rbf.libsvm", [-1, -1], [-0.988_229_7, -1.428_681_2]);
+    test_model!(m_one_class_sigmoid, "m_one_class_sigmoid.libsvm", [-1, 1], [-1.687_220_9, 2.138_191_3]);
+}
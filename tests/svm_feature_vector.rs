@@ -0,0 +1,61 @@
+#[cfg(test)]
+mod svm_feature_vector {
+    use ffsvm::{DenseSVM, Error, FeatureVector, Predict, SparseSVM, SAMPLE_MODEL};
+    use std::convert::TryFrom;
+
+    const RBF: &str = include_str!("data_dense/m_csvm_rbf.libsvm");
+
+    #[test]
+    fn same_shape_can_be_shared() -> Result<(), Error> {
+        let svm = DenseSVM::try_from(RBF)?;
+        let other = DenseSVM::try_from(include_str!("data_dense/m_csvm_rbf_prob.libsvm"))?;
+
+        let mut problem = FeatureVector::from(&svm);
+        other.predict_value(&mut problem)?;
+        other.predict_probability(&mut problem)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn different_attributes() -> Result<(), Error> {
+        let svm = DenseSVM::try_from(RBF)?;
+        let mut problem = FeatureVector::from(&DenseSVM::try_from(SAMPLE_MODEL)?);
+
+        assert!(matches!(svm.predict_value(&mut problem), Err(Error::FeatureVectorMismatch("num_attributes"))));
+
+        Ok(())
+    }
+
+    #[test]
+    fn different_support_vectors() -> Result<(), Error> {
+        let svm = DenseSVM::try_from(include_str!("data_dense/m_csvm_rbf_prob.libsvm"))?;
+        let mut problem = FeatureVector::from(&DenseSVM::try_from(include_str!("data_dense/m_csvm_poly_prob.libsvm"))?);
+
+        assert!(matches!(svm.predict_value(&mut problem), Err(Error::FeatureVectorMismatch("total_sv"))));
+        assert!(matches!(svm.predict_probability(&mut problem), Err(Error::FeatureVectorMismatch("total_sv"))));
+
+        Ok(())
+    }
+
+    #[test]
+    fn different_classes() -> Result<(), Error> {
+        let svm = DenseSVM::try_from(RBF)?;
+        let relabeled = DenseSVM::try_from(RBF.replace("label 0 1 2 3 4 5 6 7", "label 0 1 2 3 4 5 6 8").as_str())?;
+        let mut problem = FeatureVector::from(&relabeled);
+
+        assert!(matches!(svm.predict_value(&mut problem), Err(Error::FeatureVectorMismatch("classes"))));
+
+        Ok(())
+    }
+
+    #[test]
+    fn sparse() -> Result<(), Error> {
+        let svm = SparseSVM::try_from(include_str!("data_sparse/m_csvm_rbf.libsvm"))?;
+        let mut problem = FeatureVector::from(&SparseSVM::try_from(include_str!("data_sparse/m_e_svr_rbf.libsvm"))?);
+
+        assert!(matches!(svm.predict_value(&mut problem), Err(Error::FeatureVectorMismatch(_))));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,98 @@
+#[cfg(test)]
+mod svm_guarded {
+    use ffsvm::{DenseSVM, Error, FeatureVector, Guarded, InvalidFeaturePolicy, Predict, SparseSVM};
+    use std::convert::TryFrom;
+
+    const FEATURES: [f32; 8] = [1.287_784_9, 0.986_031_7, 1.486_247_2, 1.128_083, 0.891_030_55, 1.164_363_4, 0.928_599_1, 1.140_762_9];
+
+    fn dense(policy: InvalidFeaturePolicy) -> Result<Guarded<DenseSVM>, Error> {
+        Ok(Guarded::new(DenseSVM::try_from(include_str!("data_dense/m_csvm_rbf_prob.libsvm"))?, policy))
+    }
+
+    #[test]
+    fn valid_features_predict_unchanged() -> Result<(), Error> {
+        let svm = dense(InvalidFeaturePolicy::Error)?.with_training_range(vec![(0.0, 2.0); 8]);
+
+        let mut guarded = FeatureVector::from(svm.svm());
+        let mut plain = FeatureVector::from(svm.svm());
+        guarded.features().clone_from_slice(&FEATURES);
+        plain.features().clone_from_slice(&FEATURES);
+
+        svm.predict_probability(&mut guarded)?;
+        svm.svm().predict_probability(&mut plain)?;
+
+        assert_eq!(guarded.label(), plain.label());
+        assert_eq!(guarded.probabilities(), plain.probabilities());
+
+        Ok(())
+    }
+
+    #[test]
+    fn non_finite_features_are_rejected() -> Result<(), Error> {
+        let svm = dense(InvalidFeaturePolicy::Error)?;
+
+        for (index, value) in [(0, f32::NAN), (3, f32::INFINITY), (7, f32::NEG_INFINITY)] {
+            let mut problem = FeatureVector::from(svm.svm());
+            problem.features().clone_from_slice(&FEATURES);
+            problem.features()[index] = value;
+
+            match svm.predict_value(&mut problem) {
+                Err(Error::NonFiniteFeature { index: found, .. }) => assert_eq!(found, index),
+                x => panic!("Expected error for feature {index}, got {x:?}"),
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_features_are_substituted() -> Result<(), Error> {
+        let svm = dense(InvalidFeaturePolicy::Substitute(1.0))?.with_training_range(vec![(0.0, 2.0); 8]);
+
+        let mut guarded = FeatureVector::from(svm.svm());
+        guarded.features().clone_from_slice(&FEATURES);
+        guarded.features()[2] = f32::NAN;
+        guarded.features()[5] = 17.0;
+
+        let mut substituted = FeatureVector::from(svm.svm());
+        substituted.features().clone_from_slice(&FEATURES);
+        substituted.features()[2] = 1.0;
+        substituted.features()[5] = 1.0;
+
+        svm.predict_value(&mut guarded)?;
+        svm.svm().predict_value(&mut substituted)?;
+
+        assert_eq!(guarded.features(), substituted.features());
+        assert_eq!(guarded.label(), substituted.label());
+
+        Ok(())
+    }
+
+    #[test]
+    fn out_of_range_features_are_rejected() -> Result<(), Error> {
+        let svm = dense(InvalidFeaturePolicy::Error)?.with_training_range(vec![(0.0, 2.0), (0.0, 0.5)]);
+
+        let mut problem = FeatureVector::from(svm.svm());
+        problem.features().clone_from_slice(&FEATURES);
+
+        match svm.predict_value(&mut problem) {
+            Err(Error::FeatureOutOfRange { index, min, max, .. }) => assert_eq!((index, min, max), (1, 0.0, 0.5)),
+            x => panic!("Expected range error, got {x:?}"),
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn sparse_features() -> Result<(), Error> {
+        let svm = Guarded::new(SparseSVM::try_from(include_str!("data_sparse/m_csvm_rbf.libsvm"))?, InvalidFeaturePolicy::Error);
+
+        let mut problem = FeatureVector::from(svm.svm());
+        problem.features()[3] = 0.930_907_6;
+        problem.features()[46] = f32::NAN;
+
+        assert!(matches!(svm.predict_value(&mut problem), Err(Error::NonFiniteFeature { index: 46, .. })));
+
+        Ok(())
+    }
+}
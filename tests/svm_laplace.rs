@@ -0,0 +1,75 @@
+#[cfg(test)]
+mod svm_laplace {
+    use ffsvm::{DataReader, DenseSVM, Error, Laplace, ModelFile, Predict, SparseSVM, Svm};
+    use std::convert::TryFrom;
+
+    #[test]
+    fn sigma_from_model() -> Result<(), Error> {
+        let svr = DenseSVM::try_from(include_str!("data_dense/m_e_svr_rbf_prob.libsvm"))?;
+        let sparse = SparseSVM::try_from(include_str!("data_sparse/m_e_svr_rbf_prob.libsvm"))?;
+
+        assert!((svr.svr_sigma().unwrap() - 0.320_473).abs() < 1e-9);
+        assert!(sparse.svr_sigma().is_some());
+        assert_eq!(svr.svr_distribution(2.0), Some(Laplace::new(2.0, svr.svr_sigma().unwrap())));
+
+        assert_eq!(DenseSVM::try_from(include_str!("data_dense/m_e_svr_rbf.libsvm"))?.svr_sigma(), None);
+        assert_eq!(DenseSVM::try_from(include_str!("data_dense/m_csvm_rbf_prob.libsvm"))?.svr_sigma(), None);
+        assert_eq!(Svm::try_from(include_str!("data_dense/m_csvm_rbf_prob.libsvm"))?.svr_distribution(1.0), None);
+
+        // A scale that isn't positive gives no distribution.
+        for sigma in ["0", "-0.5"] {
+            let model = include_str!("data_dense/m_e_svr_rbf_prob.libsvm").replacen("probA 0.320473", &format!("probA {sigma}"), 1);
+            let svr = DenseSVM::try_from(model.as_str())?;
+
+            assert_eq!(svr.svr_sigma(), None);
+            assert_eq!(svr.svr_distribution(1.0), None);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn distribution() {
+        let laplace = Laplace::new(-1.0, 2.0);
+        let (low, high) = laplace.interval(0.9).unwrap();
+
+        assert!((laplace.cdf(high) - laplace.cdf(low) - 0.9).abs() < 1e-12);
+        assert!((low + high + 2.0).abs() < 1e-12);
+        assert_eq!(laplace.quantile(0.5), Some(-1.0));
+        assert!((laplace.cdf(laplace.quantile(0.2).unwrap()) - 0.2).abs() < 1e-12);
+        assert!((laplace.cdf(laplace.quantile(0.7).unwrap()) - 0.7).abs() < 1e-12);
+        assert!((laplace.density(-1.0) - 0.25).abs() < 1e-12);
+        assert!((laplace.density(1.0) - laplace.density(-3.0)).abs() < 1e-12);
+        assert_eq!(laplace.interval(0.0), Some((-1.0, -1.0)));
+
+        assert_eq!(laplace.quantile(1.5), None);
+        assert_eq!(laplace.quantile(f64::NAN), None);
+        assert_eq!(laplace.interval(-0.1), None);
+        assert_eq!(laplace.interval(f64::NAN), None);
+    }
+
+    #[test]
+    fn calibrated_sigma() -> Result<(), Error> {
+        let problems = include_str!("data_dense/problem.in");
+        let mut svr = DenseSVM::try_from(include_str!("data_dense/m_nu_svr_poly.libsvm"))?;
+        let mut samples = DataReader::new(problems.as_bytes(), &svr).collect::<Result<Vec<_>, _>>()?;
+
+        svr.calibrate_probabilities(&mut samples)?;
+
+        let errors = samples.iter().map(|(label, problem)| (label - problem.raw_value()).abs()).collect::<Vec<_>>();
+        let mae = errors.iter().sum::<f64>() / errors.len() as f64;
+        let sigma = svr.svr_sigma().unwrap();
+
+        assert!(sigma > 0.0 && sigma <= mae + 1e-12);
+
+        let reloaded = DenseSVM::try_from(ModelFile::from(&svr).to_string().as_str())?;
+        assert!((reloaded.svr_sigma().unwrap() - sigma).abs() < 1e-6 * sigma);
+
+        let (_, problem) = &mut samples[5];
+        svr.predict_value(problem)?;
+        let (low, high) = svr.svr_distribution(problem.raw_value()).unwrap().interval(0.95).unwrap();
+        assert!(low < problem.raw_value() && problem.raw_value() < high);
+
+        Ok(())
+    }
+}
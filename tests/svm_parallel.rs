@@ -0,0 +1,100 @@
+#![cfg(feature = "rayon")]
+
+use std::{fs, path::PathBuf};
+
+/// Returns the content of all models in the given test data folder.
+fn models(folder: &str) -> Vec<(PathBuf, String)> {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests").join(folder);
+    let mut models = fs::read_dir(path)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|x| x == "libsvm"))
+        .map(|path| {
+            let model = fs::read_to_string(&path).unwrap();
+            (path, model)
+        })
+        .collect::<Vec<_>>();
+
+    models.sort();
+    models
+}
+
+#[cfg(test)]
+mod svm_parallel {
+    use super::models;
+    use ffsvm::{DenseSVM, Error, FeatureVector, ParallelPredict, Predict, SparseSVM};
+    use std::convert::TryFrom;
+
+    #[test]
+    fn dense_matches_serial() -> Result<(), Error> {
+        for (path, model) in models("data_dense") {
+            let svm = DenseSVM::try_from(model.as_str())?;
+            let mut problems = (0..37)
+                .map(|i| {
+                    let mut problem = FeatureVector::from(&svm);
+                    problem.features().iter_mut().enumerate().for_each(|(j, x)| *x = 0.5 + ((i * 31 + j * 17) % 23) as f32 / 23.0);
+                    problem
+                })
+                .collect::<Vec<_>>();
+
+            let mut serial = problems.clone();
+            let probability = model.contains("probA");
+
+            if probability {
+                svm.par_predict_probabilities(&mut problems)?;
+                serial.iter_mut().try_for_each(|problem| svm.predict_probability(problem))?;
+            } else {
+                svm.par_predict_values(&mut problems)?;
+                serial.iter_mut().try_for_each(|problem| svm.predict_value(problem))?;
+            }
+
+            for (parallel, serial) in problems.iter().zip(&serial) {
+                assert_eq!(parallel.label(), serial.label(), "{path:?}");
+                assert_eq!(parallel.raw_value().to_bits(), serial.raw_value().to_bits(), "{path:?}");
+                assert_eq!(parallel.probabilities(), serial.probabilities(), "{path:?}");
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn sparse_matches_serial() -> Result<(), Error> {
+        for (path, model) in models("data_sparse") {
+            let svm = SparseSVM::try_from(model.as_str())?;
+            let mut problems = (0..37)
+                .map(|i| {
+                    let mut problem = FeatureVector::from(&svm);
+
+                    for j in (i % 5..128).step_by(7) {
+                        problem.features()[j] = 0.5 + ((i * 31 + j * 17) % 23) as f32 / 23.0;
+                    }
+
+                    problem
+                })
+                .collect::<Vec<_>>();
+
+            let mut serial = problems.clone();
+
+            svm.par_predict_values(&mut problems)?;
+            serial.iter_mut().try_for_each(|problem| svm.predict_value(problem))?;
+
+            for (parallel, serial) in problems.iter().zip(&serial) {
+                assert_eq!(parallel.label(), serial.label(), "{path:?}");
+                assert_eq!(parallel.raw_value().to_bits(), serial.raw_value().to_bits(), "{path:?}");
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn no_probabilities() -> Result<(), Error> {
+        let svm = DenseSVM::try_from(include_str!("data_dense/m_csvm_rbf.libsvm"))?;
+        let mut problems = vec![FeatureVector::from(&svm); 10];
+
+        assert!(matches!(svm.par_predict_probabilities(&mut problems), Err(Error::NoProbabilities)));
+
+        Ok(())
+    }
+}
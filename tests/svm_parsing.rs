@@ -19,3 +19,96 @@ mod svm_performance {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod svm_parsing_errors {
+    use ffsvm::{Error, ModelFile, ParseContext};
+    use std::convert::TryFrom;
+
+    const MODEL: &str = "svm_type c_svc
+kernel_type rbf
+gamma 0.5
+nr_class 2
+total_sv 2
+rho -0.25527
+label 21 42
+probA -1.5
+probB 0.1
+nr_sv 1 1
+SV
+0.5 0:0.0001 1:0.0001
+-0.5 0:1.2974607 1:1.0227317
+";
+
+    /// Parses the model with the given token replaced, returning the error's position, token and context.
+    fn parse_error(from: &str, to: &str) -> (usize, usize, String, ParseContext) {
+        assert!(MODEL.contains(from));
+
+        match ModelFile::try_from(MODEL.replacen(from, to, 1).as_str()) {
+            Err(Error::ParsingToken { line, column, token, context }) => (line, column, token, context),
+            x => panic!("Expected parsing error, got {x:?}"),
+        }
+    }
+
+    #[test]
+    fn valid() -> Result<(), Error> {
+        let model = ModelFile::try_from(MODEL)?;
+
+        assert_eq!(model.header().svm_type, "c_svc");
+        assert_eq!(model.vectors().len(), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn malformed_headers() {
+        assert_eq!(parse_error("gamma 0.5", "gamma 0.x5"), (3, 7, "0.x5".into(), ParseContext::Header("gamma")));
+        assert_eq!(parse_error("nr_class 2", "nr_class -2"), (4, 10, "-2".into(), ParseContext::Header("nr_class")));
+        assert_eq!(parse_error("rho -0.25527", "rho -0.25527 x"), (6, 14, "x".into(), ParseContext::Header("rho")));
+        assert_eq!(parse_error("label 21 42", "label 21 4.2"), (7, 10, "4.2".into(), ParseContext::Header("label")));
+        assert_eq!(parse_error("probA -1.5", "probA -1,5"), (8, 7, "-1,5".into(), ParseContext::Header("probA")));
+        assert_eq!(parse_error("nr_sv 1 1", "nr_sv 1 one"), (10, 9, "one".into(), ParseContext::Header("nr_sv")));
+    }
+
+    #[test]
+    fn missing_header_value() {
+        assert_eq!(parse_error("gamma 0.5", "gamma"), (3, 6, String::new(), ParseContext::Header("gamma")));
+        assert_eq!(parse_error("svm_type c_svc", "svm_type "), (1, 10, String::new(), ParseContext::Header("svm_type")));
+    }
+
+    #[test]
+    fn unknown_header() {
+        assert_eq!(parse_error("gamma 0.5", "gamma 0.5\nfoo 1"), (4, 1, "foo".into(), ParseContext::UnknownHeader));
+    }
+
+    #[test]
+    fn malformed_support_vectors() {
+        assert_eq!(parse_error("1:1.0227317", "1:abc"), (13, 18, "1:abc".into(), ParseContext::SupportVector(1)));
+        assert_eq!(parse_error("1:1.0227317", "x:1.0227317"), (13, 18, "x:1.0227317".into(), ParseContext::SupportVector(1)));
+        assert_eq!(parse_error("0.5 0:0.0001", "0.5e 0:0.0001"), (12, 1, "0.5e".into(), ParseContext::SupportVector(0)));
+    }
+
+    #[test]
+    fn error_messages() {
+        let error = ModelFile::try_from(MODEL.replacen("gamma 0.5", "gamma x", 1).as_str()).unwrap_err();
+
+        assert_eq!(error.to_string(), "invalid token `x` in header `gamma` at line 3, column 7");
+    }
+
+    #[test]
+    fn error_sources() {
+        use std::error::Error as _;
+
+        /// Makes sure our errors work with `?` in functions returning boxed errors.
+        fn parse_float(token: &str) -> Result<f32, Box<dyn std::error::Error>> {
+            Ok(token.parse::<f32>().map_err(Error::from)?)
+        }
+
+        let error = parse_float("abc").unwrap_err();
+        let error = error.downcast_ref::<Error>().unwrap();
+
+        assert!(matches!(error, Error::ParsingFloat(_)));
+        assert_eq!(error.source().unwrap().to_string(), "invalid float literal");
+        assert!(Error::NoProbabilities.source().is_none());
+    }
+}
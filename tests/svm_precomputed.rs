@@ -0,0 +1,84 @@
+use ffsvm::{Label, PrecomputedFeatures};
+
+/// Sets the kernel row of a `label 0:serial 1:k_1 2:k_2 ...` line as used by `svm-train -t 4`.
+fn set_kernel_row(problem: &mut PrecomputedFeatures, line: &str) {
+    let features = problem.features();
+
+    for token in line.split_whitespace().skip(1) {
+        let (index, value) = token.split_once(':').unwrap();
+        let index = index.parse::<usize>().unwrap();
+
+        if index > 0 && index < features.len() {
+            features[index] = value.parse::<f64>().unwrap();
+        }
+    }
+}
+
+fn similar(a: Label, b: Label) -> bool {
+    match (a, b) {
+        (Label::Value(a), Label::Value(b)) => (a - b).abs() < 0.001 * a.abs().max(b.abs()),
+        (a, b) => a == b,
+    }
+}
+
+macro_rules! test_model {
+    ($name:ident, $file:expr, $prob:expr, $label:expr) => {
+        #[test]
+        fn $name() -> Result<(), Error> {
+            let model = include_str!(concat!("data_precomputed/", $file));
+            let problems = include_str!("data_precomputed/problem.in");
+            let predicted = include_str!(concat!("data_precomputed/", $file, "-predicted"));
+
+            let svm = PrecomputedSVM::try_from(model)?;
+            let expected = predicted.lines().filter(|x| !x.starts_with("labels"));
+
+            for (line, expected) in problems.lines().zip(expected) {
+                let mut problem = FeatureVector::from(&svm);
+                set_kernel_row(&mut problem, line);
+
+                if $prob {
+                    svm.predict_probability(&mut problem)?;
+                } else {
+                    svm.predict_value(&mut problem)?;
+                }
+
+                let libsvm = expected.split_whitespace().next().unwrap();
+                assert!(similar(problem.label(), $label(libsvm)), "{:?} != {} for `{}`", problem.label(), libsvm, line);
+            }
+
+            Ok(())
+        }
+    };
+}
+
+#[cfg(test)]
+mod svm_precomputed {
+    use super::{set_kernel_row, similar};
+    use ffsvm::{Error, FeatureVector, Label, PrecomputedSVM, Predict};
+    use std::convert::TryFrom;
+
+    fn class(x: &str) -> Label { Label::Class(x.parse().unwrap()) }
+
+    fn value(x: &str) -> Label { Label::Value(x.parse().unwrap()) }
+
+    test_model!(m_csvm_precomputed_prob, "m_csvm_precomputed_prob.libsvm", true, class);
+    test_model!(m_csvm_precomputed, "m_csvm_precomputed.libsvm", false, class);
+    test_model!(m_nusvm_precomputed_prob, "m_nusvm_precomputed_prob.libsvm", true, class);
+    test_model!(m_nusvm_precomputed, "m_nusvm_precomputed.libsvm", false, class);
+
+    test_model!(m_e_svr_precomputed_prob, "m_e_svr_precomputed_prob.libsvm", true, value);
+    test_model!(m_e_svr_precomputed, "m_e_svr_precomputed.libsvm", false, value);
+    test_model!(m_nu_svr_precomputed_prob, "m_nu_svr_precomputed_prob.libsvm", true, value);
+    test_model!(m_nu_svr_precomputed, "m_nu_svr_precomputed.libsvm", false, value);
+
+    #[test]
+    fn kernel_row_length() -> Result<(), Error> {
+        let svm = PrecomputedSVM::try_from(include_str!("data_precomputed/m_csvm_precomputed.libsvm"))?;
+        let mut problem = FeatureVector::from(&svm);
+
+        assert_eq!(svm.attributes(), 33);
+        assert_eq!(problem.features().len(), 33);
+
+        Ok(())
+    }
+}
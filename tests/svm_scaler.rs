@@ -0,0 +1,106 @@
+#[cfg(test)]
+mod svm_scaler {
+    use ffsvm::{DenseSVM, Error, FeatureVector, ParseContext, Scaler, SparseSVM};
+    use std::convert::TryFrom;
+
+    const RANGE: &str = "y\n0 1\n-2 6\nx\n-1 1\n1 0 10\n2 -10 10\n3 4 8\n";
+
+    #[test]
+    fn parse_and_write() -> Result<(), Error> {
+        let scaler = Scaler::try_from(RANGE)?;
+
+        assert_eq!(scaler.bounds(), (-1.0, 1.0));
+        assert_eq!(scaler.feature_range(0), None);
+        assert_eq!(scaler.feature_range(3), Some((4.0, 8.0)));
+        assert_eq!(scaler.feature_range(4), None);
+        assert_eq!(scaler.to_string(), RANGE);
+        assert_eq!(Scaler::try_from(scaler.to_string().as_str())?, scaler);
+
+        let built = Scaler::new(-1.0, 1.0).with_feature(3, 4.0, 8.0).with_feature(1, 0.0, 10.0).with_feature(2, -10.0, 10.0);
+
+        assert_eq!(built.with_target(0.0, 1.0, -2.0, 6.0), scaler);
+
+        Ok(())
+    }
+
+    #[test]
+    fn targets() -> Result<(), Error> {
+        let scaler = Scaler::try_from(RANGE)?;
+        let unscaled = Scaler::try_from("x\n0 1\n")?;
+
+        assert_eq!(scaler.scale_target(-2.0), 0.0);
+        assert_eq!(scaler.scale_target(4.0), 0.75);
+        assert_eq!(scaler.unscale_target(0.75), 4.0);
+        assert_eq!(unscaled.scale_target(4.0), 4.0);
+        assert_eq!(unscaled.unscale_target(4.0), 4.0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn dense() -> Result<(), Error> {
+        let svm = DenseSVM::try_from(include_str!("data_dense/m_csvm_rbf.libsvm"))?;
+        let scaler = Scaler::try_from(RANGE)?;
+
+        let mut problem = FeatureVector::from(&svm);
+        problem.features().clone_from_slice(&[3.0, 10.0, 5.0, 7.0, 4.0, 0.0, 0.0, 0.0]);
+        scaler.scale(&mut problem);
+
+        assert_eq!(problem.features(), &[0.0, 1.0, 0.5, 0.5, 0.0, 0.0, 0.0, 0.0]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn sparse_drops_zeros() -> Result<(), Error> {
+        let svm = SparseSVM::try_from(include_str!("data_sparse/m_csvm_rbf.libsvm"))?;
+        let scaler = Scaler::try_from("x\n-1 1\n1 0 10\n2 -10 10\n3 4 8\n5 -1 1\n")?;
+
+        let mut problem = FeatureVector::from(&svm);
+        problem.features()[2] = 5.0;
+        problem.features()[3] = 6.0;
+        problem.features()[7] = 3.0;
+        scaler.scale(&mut problem);
+
+        // Unset feature 1 is scaled to its lower bound, set features 3 and 7 and unset feature 5 become 0 and are dropped.
+        assert_eq!(problem.features().iter().collect::<Vec<_>>(), vec![(1, -1.0), (2, 0.5)]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn large_indices() -> Result<(), Error> {
+        let scaler = Scaler::try_from("x\n-1 1\n1 0 10\n4000000000 0 1\n")?;
+
+        assert_eq!(scaler.feature_range(4_000_000_000), Some((0.0, 1.0)));
+        assert_eq!(scaler.to_string(), "x\n-1 1\n1 0 10\n4000000000 0 1\n");
+
+        let svm = SparseSVM::try_from(include_str!("data_sparse/m_csvm_rbf.libsvm"))?;
+        let mut problem = FeatureVector::from(&svm);
+        problem.features()[1] = 10.0;
+        scaler.scale(&mut problem);
+
+        assert_eq!(problem.features().iter().collect::<Vec<_>>(), vec![(1, 1.0), (4_000_000_000, -1.0)]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn errors() {
+        for (range, line, column, token) in [
+            ("y\n0 1\nx\n-1 1\n", 3, 2, ""),
+            ("-1 1\n", 1, 1, "-1"),
+            ("x\n-1\n", 2, 3, ""),
+            ("x\n-1 1\n\n1 0 a\n", 4, 5, "a"),
+            ("x\n-1 1\n1 0 1 2\n", 3, 7, "2"),
+            ("", 1, 1, ""),
+        ] {
+            match Scaler::try_from(range) {
+                Err(Error::ParsingToken { line: l, column: c, token: t, context: ParseContext::RangeFile }) => {
+                    assert_eq!((l, c, t.as_str()), (line, column, token), "{range:?}");
+                }
+                x => panic!("Expected error for {range:?}, got {x:?}"),
+            }
+        }
+    }
+}
@@ -0,0 +1,65 @@
+fn similar(a: f64, b: f64) -> bool {
+    (a - b).abs() < 0.001 * a.abs().max(b.abs())
+}
+
+macro_rules! test_model {
+    ($name:ident, $file:expr, $libsvm:expr, $decision:expr) => {
+        #[test]
+        fn $name() -> Result<(), Error> {
+            let model = include_str!(concat!("data_sparse/", $file));
+            let svm = SparseSVM::try_from(model)?;
+
+            let mut problem_0 = FeatureVector::from(&svm);
+            let features_0 = problem_0.features();
+            features_0[3] = 0.000_1;
+            features_0[4] = 0.000_1;
+            features_0[7] = 0.000_1;
+            features_0[12] = 0.000_1;
+            features_0[18] = 0.000_1;
+            features_0[21] = 0.000_1;
+            features_0[32] = 0.000_1;
+            features_0[34] = 0.000_1;
+            features_0[50] = 0.000_1;
+            features_0[73] = 0.000_1;
+            features_0[123] = 0.000_1;
+            features_0[127] = 0.000_1;
+
+            let mut problem_7 = FeatureVector::from(&svm);
+            let features_7 = problem_7.features();
+            features_7[3] = 0.930_907_6;
+            features_7[4] = 1.264_398_9;
+            features_7[6] = 1.417_500_6;
+            features_7[32] = 1.090_475_8;
+            features_7[46] = 1.475_075;
+            features_7[54] = 0.902_898_55;
+            features_7[74] = 1.504_974_4;
+            features_7[92] = 0.908_902_6;
+            features_7[95] = 1.274_937_5;
+            features_7[98] = 1.234_927_2;
+            features_7[110] = 1.500_999;
+
+            svm.predict_value(&mut problem_0)?;
+            svm.predict_value(&mut problem_7)?;
+
+            assert_eq!(problem_0.label(), Label::Class($libsvm[0]), "predict_value(problem_0)");
+            assert_eq!(problem_7.label(), Label::Class($libsvm[1]), "predict_value(problem_7)");
+
+            assert!(similar(problem_0.raw_value(), $decision[0]), "raw_value(problem_0)");
+            assert!(similar(problem_7.raw_value(), $decision[1]), "raw_value(problem_7)");
+
+            Ok(())
+        }
+    };
+}
+
+#[cfg(test)]
+mod svm_sparse_one_class {
+    use super::similar;
+    use ffsvm::{Error, FeatureVector, Label, Predict, SparseSVM};
+    use std::convert::TryFrom;
+
+    test_model!(m_one_class_linear, "m_one_class_linear.libsvm", [-1, 1], [-2.732_374_3, 2.334_317_4]);
+    test_model!(m_one_class_poly, "m_one_class_poly.libsvm", [-1, -1], [-9.312_82e-6, -5.033_743e-6]);
+    test_model!(m_one_class_rbf, "m_one_class_rbf.libsvm", [1, -1], [0.202_060_0, -1.242_650_8]);
+    test_model!(m_one_class_sigmoid, "m_one_class_sigmoid.libsvm", [-1, 1], [-0.021_532_8, 0.018_449_6]);
+}
@@ -0,0 +1,95 @@
+#[cfg(test)]
+mod svm_sparse_vector {
+    use ffsvm::{DataReader, Error, FeatureVector, Predict, SparseSVM, SparseVector};
+    use std::{collections::HashMap, convert::TryFrom};
+
+    const MODEL: &str = include_str!("data_sparse/m_csvm_rbf.libsvm");
+    const PROBLEMS: &str = include_str!("data_sparse/problem.in");
+
+    #[test]
+    fn random_order() {
+        let mut vector = SparseVector::new();
+        vector[5] = 5.0;
+        vector[1] = 1.0;
+        vector[3] = 3.0;
+        vector[1] = 1.5;
+
+        assert_eq!(vector.iter().collect::<Vec<_>>(), vec![(1, 1.5), (3, 3.0), (5, 5.0)]);
+        assert_eq!(vector[3], 3.0);
+    }
+
+    #[test]
+    fn get_and_remove() {
+        let mut vector = [(2, 2.0), (4, 4.0)].into_iter().collect::<SparseVector<f32>>();
+
+        assert_eq!(vector.get(2), Some(&2.0));
+        assert_eq!(vector.get(3), None);
+        assert_eq!(vector.get(9), None);
+        assert_eq!(vector.remove(3), None);
+        assert_eq!(vector.remove(2), Some(2.0));
+        assert_eq!(vector.iter().collect::<Vec<_>>(), vec![(4, 4.0)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Index out of bounds.")]
+    fn index_missing() {
+        let vector = [(2, 2.0)].into_iter().collect::<SparseVector<f32>>();
+        let _ = vector[1];
+    }
+
+    #[test]
+    #[cfg(target_pointer_width = "64")]
+    fn index_beyond_u32() {
+        let beyond = u32::MAX as usize + 1;
+        let mut vector = [(0, 0.5), (3, 3.0)].into_iter().collect::<SparseVector<f32>>();
+
+        assert_eq!(vector.get(beyond), None);
+        assert_eq!(vector.get(beyond + 3), None);
+        assert_eq!(vector.remove(beyond), None);
+        assert_eq!(vector.iter().collect::<Vec<_>>(), vec![(0, 0.5), (3, 3.0)]);
+    }
+
+    #[test]
+    #[cfg(target_pointer_width = "64")]
+    #[should_panic(expected = "Index out of bounds.")]
+    fn set_beyond_u32() {
+        let mut vector = [(0, 0.5)].into_iter().collect::<SparseVector<f32>>();
+        vector[u32::MAX as usize + 1] = 1.0;
+    }
+
+    #[test]
+    fn extend() {
+        let mut vector = [(8, 8.0), (2, 2.0)].into_iter().collect::<SparseVector<f32>>();
+        vector.extend([(6, 6.0), (2, -2.0), (0, 0.5), (6, -6.0)]);
+
+        assert_eq!(vector.iter().collect::<Vec<_>>(), vec![(0, 0.5), (2, -2.0), (6, -6.0), (8, 8.0)]);
+    }
+
+    #[test]
+    fn predicts_like_ordered() -> Result<(), Error> {
+        let svm = SparseSVM::try_from(MODEL)?;
+
+        for sample in DataReader::new(PROBLEMS.as_bytes(), &svm) {
+            let (_, mut expected) = sample?;
+            let features = expected.features().iter().map(|(index, value)| (index as usize, value)).collect::<HashMap<_, _>>();
+
+            let mut reversed = FeatureVector::from(&svm);
+            let mut collected = FeatureVector::from(&svm);
+
+            for (index, value) in expected.features().iter().collect::<Vec<_>>().into_iter().rev() {
+                reversed.features()[index as usize] = value;
+            }
+
+            collected.features().extend(features);
+
+            svm.predict_value(&mut expected)?;
+            svm.predict_value(&mut reversed)?;
+            svm.predict_value(&mut collected)?;
+
+            assert_eq!(reversed.label(), expected.label());
+            assert_eq!(collected.label(), expected.label());
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,109 @@
+#[cfg(test)]
+mod svm_stats {
+    use ffsvm::{DenseSVM, Error, ModelFile, ModelStats, PrecomputedSVM, Representation, SparseSVM, ValueStats};
+    use std::convert::TryFrom;
+
+    const MODEL: &str = "svm_type c_svc
+kernel_type linear
+nr_class 2
+total_sv 3
+rho 0.5
+label 1 -1
+nr_sv 2 1
+SV
+0.5 0:1 1:0 2:3
+1 0:2 1:0 2:0
+-1.5 0:0 1:0 2:6
+";
+
+    #[test]
+    fn small_model() -> Result<(), Error> {
+        let stats = ModelStats::try_from(&ModelFile::try_from(MODEL)?)?;
+
+        assert_eq!(stats.svm_type, "c_svc");
+        assert_eq!(stats.kernel_type, "linear");
+        assert_eq!(stats.total_sv, 3);
+        assert_eq!(stats.classes, vec![(1, 2), (-1, 1)]);
+        assert_eq!(stats.num_attributes, 3);
+        assert!((stats.sparsity - 5.0 / 9.0).abs() < 1e-12);
+        assert_eq!(
+            stats.features,
+            vec![ValueStats { min: 0.0, max: 2.0, mean: 1.0 }, ValueStats { min: 0.0, max: 0.0, mean: 0.0 }, ValueStats { min: 0.0, max: 6.0, mean: 3.0 }]
+        );
+        assert_eq!(stats.coefficients, ValueStats { min: 0.5, max: 1.5, mean: 1.0 });
+        assert_eq!(stats.dense_bytes, 3 * 8 * 4 + 2 * 4 * 8);
+        assert!(stats.sparse_bytes > 9 * 8);
+        assert_eq!(stats.recommended(), Representation::Dense);
+
+        Ok(())
+    }
+
+    #[test]
+    fn omitted_attributes() -> Result<(), Error> {
+        let model = MODEL.replace("0:0 1:0 2:6", "2:6").replace("0:2 1:0 2:0", "0:2");
+        let stats = ModelStats::try_from(&ModelFile::try_from(model.as_str())?)?;
+
+        assert_eq!(stats.features[2], ValueStats { min: 0.0, max: 6.0, mean: 3.0 });
+        assert!((stats.sparsity - 5.0 / 9.0).abs() < 1e-12);
+        assert_eq!(stats.recommended(), Representation::Dense);
+
+        // Attributes stored in all support vectors don't count as `0`.
+        let model = MODEL.replace("0:1 1:0 2:3", "0:-1 2:3").replace("0:2 1:0 2:0", "0:-2").replace("0:0 1:0 2:6", "0:-3 2:6");
+        let stats = ModelStats::try_from(&ModelFile::try_from(model.as_str())?)?;
+
+        assert_eq!(stats.features[0], ValueStats { min: -3.0, max: -1.0, mean: -2.0 });
+        assert_eq!(stats.features[1], ValueStats { min: 0.0, max: 0.0, mean: 0.0 });
+        assert_eq!(stats.features[2], ValueStats { min: 0.0, max: 6.0, mean: 3.0 });
+
+        Ok(())
+    }
+
+    #[test]
+    fn too_many_attributes() -> Result<(), Error> {
+        let model = MODEL.replace("0:0 1:0 2:6", "4000000000:1");
+
+        assert!(matches!(ModelStats::try_from(&ModelFile::try_from(model.as_str())?), Err(Error::TooManyAttributes { found: 4_000_000_001, .. })));
+
+        Ok(())
+    }
+
+    #[test]
+    fn same_for_svms() -> Result<(), Error> {
+        let dense = include_str!("data_dense/m_csvm_rbf_prob.libsvm");
+        let sparse = include_str!("data_sparse/m_e_svr_rbf.libsvm");
+        let precomputed = include_str!("data_precomputed/m_csvm_precomputed.libsvm");
+
+        assert_eq!(ModelStats::try_from(&ModelFile::try_from(dense)?)?, ModelStats::try_from(&DenseSVM::try_from(dense)?)?);
+        assert_eq!(ModelStats::try_from(&ModelFile::try_from(sparse)?)?, ModelStats::try_from(&SparseSVM::try_from(sparse)?)?);
+        assert_eq!(ModelStats::try_from(&ModelFile::try_from(precomputed)?)?, ModelStats::try_from(&PrecomputedSVM::try_from(precomputed)?)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn recommendations() -> Result<(), Error> {
+        let dense = ModelStats::try_from(&ModelFile::try_from(include_str!("data_dense/m_csvm_rbf.libsvm"))?)?;
+        let sparse = ModelStats::try_from(&ModelFile::try_from(include_str!("data_sparse/m_csvm_rbf.libsvm"))?)?;
+        let precomputed = ModelStats::try_from(&ModelFile::try_from(include_str!("data_precomputed/m_csvm_precomputed.libsvm"))?)?;
+
+        assert_eq!(dense.recommended(), Representation::Dense);
+        assert_eq!(sparse.recommended(), Representation::Sparse);
+        assert_eq!(precomputed.recommended(), Representation::Precomputed);
+
+        assert_eq!(dense.classes.iter().map(|(_, n)| n).sum::<usize>(), dense.total_sv);
+        assert!(sparse.sparse_bytes < sparse.dense_bytes);
+        assert!(dense.sparse_bytes > dense.dense_bytes);
+
+        Ok(())
+    }
+
+    #[test]
+    fn regression_has_no_classes() -> Result<(), Error> {
+        let stats = ModelStats::try_from(&ModelFile::try_from(include_str!("data_dense/m_e_svr_rbf.libsvm"))?)?;
+
+        assert!(stats.classes.is_empty());
+        assert!(stats.to_string().contains("svm_type        epsilon_svr\n"));
+
+        Ok(())
+    }
+}
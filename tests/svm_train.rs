@@ -0,0 +1,153 @@
+#![cfg(feature = "train")]
+
+use ffsvm::{Label, ModelFile};
+
+/// Returns `true` if `a` and `b` agree to the precision libSVM writes them with.
+fn close(a: f64, b: f64) -> bool {
+    (a - b).abs() <= 1e-3 * a.abs().max(b.abs()).max(1e-1)
+}
+
+/// Checks that a trained model has the same support vectors, coefficients and `rho` as one of `svm-train`.
+fn assert_same_model(trained: &ModelFile<'_>, reference: &ModelFile<'_>) {
+    let (header, expected) = (trained.header(), reference.header());
+
+    assert_eq!(header.svm_type, expected.svm_type);
+    assert_eq!(header.kernel_type, expected.kernel_type);
+    assert_eq!(header.nr_class, expected.nr_class);
+    assert_eq!(header.total_sv, expected.total_sv);
+    assert_eq!(header.label, expected.label);
+    assert_eq!(header.nr_sv, expected.nr_sv);
+    assert!(close(f64::from(header.gamma.unwrap_or_default()), f64::from(expected.gamma.unwrap_or_default())));
+    assert!(header.rho.iter().zip(&expected.rho).all(|(a, b)| close(*a, *b)), "rho {:?} != {:?}", header.rho, expected.rho);
+
+    for (sv, expected) in trained.vectors().iter().zip(reference.vectors()) {
+        assert_eq!(sv.features.len(), expected.features.len());
+        assert!(sv.features.iter().zip(&expected.features).all(|(a, b)| a.index == b.index && close(f64::from(a.value), f64::from(b.value))));
+        assert!(sv.coefs.iter().zip(&expected.coefs).all(|(a, b)| close(f64::from(*a), f64::from(*b))), "{:?} != {:?}", sv.coefs, expected.coefs);
+    }
+}
+
+/// Returns `true` if a prediction matches the line of a `-predicted` file.
+fn same_prediction(label: Label, predicted: &str) -> bool {
+    match label {
+        Label::Class(x) => predicted.parse() == Ok(x),
+        Label::Value(x) => close(f64::from(x), predicted.parse().unwrap()),
+        Label::None => false,
+    }
+}
+
+macro_rules! test_training {
+    ($name:ident, $svm:ident, $folder:expr, $file:expr, $svm_type:expr, $kernel:expr) => {
+        #[test]
+        fn $name() -> Result<(), Error> {
+            let problems = include_str!(concat!($folder, "/problem.in"));
+            let reference = ModelFile::try_from(include_str!(concat!($folder, "/", $file)))?;
+            let predicted = include_str!(concat!($folder, "/", $file, "-predicted"));
+
+            let model = Trainer::new($svm_type).with_kernel($kernel).train(&Dataset::try_from(problems)?)?;
+            assert_same_model(&model, &reference);
+
+            let svm = $svm::try_from(&model)?;
+
+            for (sample, expected) in DataReader::new(problems.as_bytes(), &svm).zip(predicted.lines()) {
+                let (_, mut problem) = sample?;
+                svm.predict_value(&mut problem)?;
+
+                assert!(same_prediction(problem.label(), expected), "{:?} != {}", problem.label(), expected);
+            }
+
+            Ok(())
+        }
+    };
+}
+
+#[cfg(test)]
+mod svm_train {
+    use super::{assert_same_model, same_prediction};
+    use ffsvm::{DataReader, Dataset, DenseSVM, Error, KernelType, ModelFile, Predict, SVMType, SparseSVM, SparseVector, Trainer};
+    use std::convert::TryFrom;
+
+    test_training!(dense_csvm_linear, DenseSVM, "data_dense", "m_csvm_linear.libsvm", SVMType::CSvc, KernelType::Linear);
+    test_training!(dense_csvm_poly, DenseSVM, "data_dense", "m_csvm_poly.libsvm", SVMType::CSvc, KernelType::Poly);
+    test_training!(dense_csvm_rbf, DenseSVM, "data_dense", "m_csvm_rbf.libsvm", SVMType::CSvc, KernelType::Rbf);
+    test_training!(dense_csvm_sigmoid, DenseSVM, "data_dense", "m_csvm_sigmoid.libsvm", SVMType::CSvc, KernelType::Sigmoid);
+
+    test_training!(dense_nusvm_linear, DenseSVM, "data_dense", "m_nusvm_linear.libsvm", SVMType::NuSvc, KernelType::Linear);
+    test_training!(dense_nusvm_poly, DenseSVM, "data_dense", "m_nusvm_poly.libsvm", SVMType::NuSvc, KernelType::Poly);
+    test_training!(dense_nusvm_rbf, DenseSVM, "data_dense", "m_nusvm_rbf.libsvm", SVMType::NuSvc, KernelType::Rbf);
+    test_training!(dense_nusvm_sigmoid, DenseSVM, "data_dense", "m_nusvm_sigmoid.libsvm", SVMType::NuSvc, KernelType::Sigmoid);
+
+    test_training!(dense_one_class_linear, DenseSVM, "data_dense", "m_one_class_linear.libsvm", SVMType::OneClass, KernelType::Linear);
+    test_training!(dense_one_class_rbf, DenseSVM, "data_dense", "m_one_class_rbf.libsvm", SVMType::OneClass, KernelType::Rbf);
+
+    test_training!(dense_e_svr_linear, DenseSVM, "data_dense", "m_e_svr_linear.libsvm", SVMType::ESvr, KernelType::Linear);
+    test_training!(dense_e_svr_rbf, DenseSVM, "data_dense", "m_e_svr_rbf.libsvm", SVMType::ESvr, KernelType::Rbf);
+    test_training!(dense_nu_svr_poly, DenseSVM, "data_dense", "m_nu_svr_poly.libsvm", SVMType::NuSvr, KernelType::Poly);
+    test_training!(dense_nu_svr_sigmoid, DenseSVM, "data_dense", "m_nu_svr_sigmoid.libsvm", SVMType::NuSvr, KernelType::Sigmoid);
+
+    test_training!(sparse_csvm_rbf, SparseSVM, "data_sparse", "m_csvm_rbf.libsvm", SVMType::CSvc, KernelType::Rbf);
+    test_training!(sparse_nusvm_poly, SparseSVM, "data_sparse", "m_nusvm_poly.libsvm", SVMType::NuSvc, KernelType::Poly);
+    test_training!(sparse_one_class_sigmoid, SparseSVM, "data_sparse", "m_one_class_sigmoid.libsvm", SVMType::OneClass, KernelType::Sigmoid);
+    test_training!(sparse_e_svr_linear, SparseSVM, "data_sparse", "m_e_svr_linear.libsvm", SVMType::ESvr, KernelType::Linear);
+    test_training!(sparse_nu_svr_rbf, SparseSVM, "data_sparse", "m_nu_svr_rbf.libsvm", SVMType::NuSvr, KernelType::Rbf);
+
+    #[test]
+    fn tiny_cache_and_no_shrinking() -> Result<(), Error> {
+        let data = Dataset::try_from(include_str!("data_dense/problem.in"))?;
+        let reference = ModelFile::try_from(include_str!("data_dense/m_csvm_rbf.libsvm"))?;
+
+        assert_same_model(&Trainer::default().with_cache_size(1).train(&data)?, &reference);
+        assert_same_model(&Trainer::default().with_shrinking(false).train(&data)?, &reference);
+
+        Ok(())
+    }
+
+    #[test]
+    fn writes_libsvm_format() -> Result<(), Error> {
+        let data = Dataset::try_from(include_str!("data_sparse/problem.in"))?;
+        let model = Trainer::new(SVMType::NuSvc).train(&data)?;
+        let text = model.to_string();
+
+        assert!(text.starts_with("svm_type nu_svc\nkernel_type rbf\n"));
+        assert_same_model(&ModelFile::try_from(text.as_str())?, &model);
+
+        Ok(())
+    }
+
+    #[test]
+    fn plus_one_is_first_class() -> Result<(), Error> {
+        let data = Dataset::try_from("-1 0:-1\n1 0:1\n-1 0:-0.5\n1 0:0.5\n")?;
+        let model = Trainer::default().train(&data)?;
+
+        assert_eq!(model.header().label, vec![1, -1]);
+        assert_eq!(model.header().nr_sv, vec![2, 2]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_parameters() -> Result<(), Error> {
+        let data = Dataset::try_from(include_str!("data_dense/problem.in"))?;
+
+        assert!(matches!(Trainer::default().with_c(0.0).train(&data), Err(Error::InvalidParameter("C"))));
+        assert!(matches!(Trainer::default().with_gamma(-1.0).train(&data), Err(Error::InvalidParameter("gamma"))));
+        assert!(matches!(Trainer::default().with_tolerance(0.0).train(&data), Err(Error::InvalidParameter("eps"))));
+        assert!(matches!(Trainer::new(SVMType::ESvr).with_epsilon(-0.1).train(&data), Err(Error::InvalidParameter("p"))));
+        assert!(matches!(Trainer::new(SVMType::OneClass).with_nu(1.5).train(&data), Err(Error::InvalidParameter("nu"))));
+
+        // A class of one sample can't have `nu * 4 / 2 = 1.6` support vectors.
+        let unbalanced = Dataset::try_from("1 0:1\n-1 0:-1\n-1 0:-0.5\n-1 0:-0.25\n")?;
+        assert!(matches!(Trainer::new(SVMType::NuSvc).with_nu(0.8).train(&unbalanced), Err(Error::InvalidParameter("nu"))));
+        assert!(Trainer::new(SVMType::NuSvc).with_nu(0.5).train(&unbalanced).is_ok());
+
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_data() {
+        let single_class = [(1.0, [(0, 1.0)].into_iter().collect::<SparseVector<f64>>())].into_iter().collect::<Dataset>();
+
+        assert!(matches!(Trainer::default().train(&Dataset::new()), Err(Error::InvalidTrainingData("no samples"))));
+        assert!(matches!(Trainer::default().train(&single_class), Err(Error::InvalidTrainingData("a single class"))));
+    }
+}
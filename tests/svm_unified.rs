@@ -0,0 +1,144 @@
+#[cfg(test)]
+mod svm_unified {
+    use ffsvm::{
+        BackendPolicy, DataReader, DenseSVM, Error, FeatureVector, Guarded, InvalidFeaturePolicy, ModelFile, Predict, Representation, Scaler, SparseSVM, Svm,
+    };
+    use std::convert::TryFrom;
+
+    const DENSE: &str = include_str!("data_dense/m_csvm_rbf_prob.libsvm");
+    const SPARSE: &str = include_str!("data_sparse/m_csvm_rbf.libsvm");
+
+    #[test]
+    fn automatic_choice() -> Result<(), Error> {
+        assert_eq!(Svm::try_from(DENSE)?.representation(), Representation::Dense);
+        assert_eq!(Svm::try_from(SPARSE)?.representation(), Representation::Sparse);
+
+        // Models omitting a few attributes are still dense.
+        let gaps = include_str!("data_dense/m_csvm_rbf.libsvm").replace(" 7:", " 9:");
+        assert_eq!(Svm::try_from(gaps.as_str())?.representation(), Representation::Dense);
+
+        assert!(matches!(Svm::try_from(include_str!("data_precomputed/m_csvm_precomputed.libsvm")), Err(Error::UnsupportedKernel(_))));
+
+        Ok(())
+    }
+
+    #[test]
+    fn automatic_choice_of_broken_models() -> Result<(), Error> {
+        let model = "svm_type c_svc\nkernel_type linear\nnr_class 2\ntotal_sv 2\nrho 0.1\nlabel 1 2\nnr_sv 1 1\nSV\n1 0:1 4000000000:1\n-1 1:1\n";
+
+        // A huge attribute index can't be loaded densely, but doesn't stop the model from being inspected.
+        assert_eq!(Svm::try_from(model)?.representation(), Representation::Sparse);
+
+        // Broken models fail before being inspected.
+        assert!(matches!(Svm::try_from(model.replace("total_sv 2", "total_sv 3").as_str()), Err(Error::InconsistentSupportVectorCount { .. })));
+
+        Ok(())
+    }
+
+    #[test]
+    fn policy() -> Result<(), Error> {
+        let model = ModelFile::try_from(DENSE)?;
+
+        assert_eq!(Svm::with_policy(&model, BackendPolicy::Sparse)?.representation(), Representation::Sparse);
+        assert_eq!(Svm::with_policy(&model, BackendPolicy::Dense)?.representation(), Representation::Dense);
+        assert_eq!(Svm::with_policy(&ModelFile::try_from(SPARSE)?, BackendPolicy::Dense)?.representation(), Representation::Dense);
+
+        Ok(())
+    }
+
+    #[test]
+    fn predicts_like_backends() -> Result<(), Error> {
+        let model = ModelFile::try_from(DENSE)?;
+        let dense = DenseSVM::try_from(DENSE)?;
+        let features = [1.287_784_9, 0.986_031_7, 1.486_247_2, 1.128_083, 0.891_030_55, 1.164_363_4, 0.928_599_1, 1.140_762_9];
+
+        let mut expected = FeatureVector::from(&dense);
+        expected.features().clone_from_slice(&features);
+        dense.predict_probability(&mut expected)?;
+
+        for policy in [BackendPolicy::Dense, BackendPolicy::Sparse] {
+            let svm = Svm::with_policy(&model, policy)?;
+            let mut problem = FeatureVector::from(&svm);
+
+            for (index, value) in features.iter().enumerate() {
+                problem.set(index, *value);
+            }
+
+            svm.predict_probability(&mut problem)?;
+
+            assert_eq!(problem.label(), expected.label(), "{policy:?}");
+            assert_eq!(problem.get(2), features[2]);
+
+            for (a, b) in problem.probabilities().iter().zip(expected.probabilities()) {
+                assert!((a - b).abs() < 1e-6, "{policy:?}");
+            }
+
+            // Feature vectors can be reused.
+            problem.clear();
+            assert_eq!(problem.get(2), 0.0);
+            svm.predict_value(&mut problem)?;
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn sparse_matches_sparse_svm() -> Result<(), Error> {
+        let svm = Svm::try_from(SPARSE)?;
+        let sparse = SparseSVM::try_from(SPARSE)?;
+
+        let unified = DataReader::new(include_str!("data_sparse/problem.in").as_bytes(), &svm);
+        let expected = DataReader::new(include_str!("data_sparse/problem.in").as_bytes(), &sparse);
+
+        for (a, b) in unified.zip(expected) {
+            let ((_, mut a), (_, mut b)) = (a?, b?);
+
+            svm.predict_value(&mut a)?;
+            sparse.predict_value(&mut b)?;
+
+            assert_eq!(a.label(), b.label());
+            assert_eq!(a.decision_values().collect::<Vec<_>>(), b.decision_values().collect::<Vec<_>>());
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn representation_mismatch() -> Result<(), Error> {
+        let model = ModelFile::try_from(DENSE)?;
+        let dense = Svm::with_policy(&model, BackendPolicy::Dense)?;
+        let sparse = Svm::with_policy(&model, BackendPolicy::Sparse)?;
+
+        let mut problem = FeatureVector::from(&sparse);
+        problem.set(1, 0.5);
+
+        assert!(matches!(dense.predict_value(&mut problem), Err(Error::FeatureVectorMismatch("representation"))));
+
+        // The feature vector is left intact.
+        assert_eq!(problem.representation(), Representation::Sparse);
+        assert_eq!(problem.get(1), 0.5);
+        sparse.predict_value(&mut problem)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn scaled_and_guarded() -> Result<(), Error> {
+        let svm = Guarded::new(Svm::try_from(SPARSE)?, InvalidFeaturePolicy::Error);
+        let scaler = Scaler::new(0.0, 1.0).with_feature(3, 0.0, 2.0);
+
+        let mut problem = FeatureVector::from(svm.svm());
+        problem.set(3, 1.0);
+        problem.set(7, f32::NAN);
+
+        scaler.scale(&mut problem);
+        assert_eq!(problem.get(3), 0.5);
+        assert_eq!(problem.get(7), 0.0);
+
+        problem.clear();
+        problem.set(7, f32::NAN);
+        assert!(matches!(svm.predict_value(&mut problem), Err(Error::NonFiniteFeature { index: 7, .. })));
+
+        Ok(())
+    }
+}
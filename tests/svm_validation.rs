@@ -0,0 +1,225 @@
+const VALID: &str = "svm_type c_svc
+kernel_type linear
+nr_class 2
+total_sv 2
+rho -0.25527
+label 21 42
+nr_sv 1 1
+SV
+0 0:0.0001 1:0.0001 2:0.0001 3:0.0001
+-1 0:1.2974607 1:1.0227317 2:1.2545854
+";
+
+/// Returns the valid model with the given line replaced.
+fn broken(line: &str, replacement: &str) -> String {
+    assert!(VALID.contains(line));
+    VALID.replacen(line, replacement, 1)
+}
+
+#[cfg(test)]
+mod svm_validation {
+    use super::{broken, VALID};
+    use ffsvm::{AttributePolicy, DenseSVM, Error, FeatureVector, ModelFile, PrecomputedSVM, Predict, SparseSVM};
+    use std::convert::TryFrom;
+
+    #[test]
+    fn valid() -> Result<(), Error> {
+        DenseSVM::try_from(VALID)?;
+        SparseSVM::try_from(VALID)?;
+        Ok(())
+    }
+
+    #[test]
+    fn unsupported_svm_type() {
+        let model = broken("svm_type c_svc", "svm_type magic_svc");
+        assert!(matches!(DenseSVM::try_from(model.as_str()), Err(Error::UnsupportedSvmType(x)) if x == "magic_svc"));
+    }
+
+    #[test]
+    fn unsupported_kernel() {
+        let model = broken("kernel_type linear", "kernel_type magic");
+        assert!(matches!(DenseSVM::try_from(model.as_str()), Err(Error::UnsupportedKernel(x)) if x == "magic"));
+        assert!(matches!(SparseSVM::try_from(model.as_str()), Err(Error::UnsupportedKernel(x)) if x == "magic"));
+    }
+
+    #[test]
+    fn precomputed_kernel_mismatch() {
+        let model = broken("kernel_type linear", "kernel_type precomputed");
+        assert!(matches!(DenseSVM::try_from(model.as_str()), Err(Error::UnsupportedKernel(x)) if x == "precomputed"));
+        assert!(matches!(PrecomputedSVM::try_from(VALID), Err(Error::UnsupportedKernel(x)) if x == "linear"));
+    }
+
+    #[test]
+    fn no_support_vectors() {
+        let model = VALID.split("SV\n").next().unwrap().replace("total_sv 2", "total_sv 0").replace("nr_sv 1 1", "nr_sv 0 0");
+        assert!(matches!(DenseSVM::try_from(model.as_str()), Err(Error::NoSupportVectors)));
+        assert!(matches!(SparseSVM::try_from(model.as_str()), Err(Error::NoSupportVectors)));
+    }
+
+    #[test]
+    fn total_sv_mismatch() {
+        let model = broken("total_sv 2", "total_sv 3");
+        assert!(matches!(DenseSVM::try_from(model.as_str()), Err(Error::InconsistentSupportVectorCount { total_sv: 3, found: 2 })));
+    }
+
+    #[test]
+    fn nr_sv_mismatch() {
+        let model = broken("nr_sv 1 1", "nr_sv 1 2");
+        assert!(matches!(SparseSVM::try_from(model.as_str()), Err(Error::InconsistentSupportVectorCount { total_sv: 2, found: 3 })));
+    }
+
+    #[test]
+    fn invalid_class_count() {
+        let model = broken("nr_class 2", "nr_class 1");
+        assert!(matches!(DenseSVM::try_from(model.as_str()), Err(Error::InvalidClassCount(1))));
+    }
+
+    #[test]
+    fn rho_length_mismatch() {
+        let model = broken("rho -0.25527", "rho -0.25527 0.1");
+        assert!(matches!(DenseSVM::try_from(model.as_str()), Err(Error::RhoLengthMismatch { expected: 1, found: 2 })));
+    }
+
+    #[test]
+    fn label_length_mismatch() {
+        let model = broken("label 21 42", "label 21");
+        assert!(matches!(DenseSVM::try_from(model.as_str()), Err(Error::HeaderLengthMismatch { key: "label", expected: 2, found: 1 })));
+    }
+
+    #[test]
+    fn nr_sv_length_mismatch() {
+        let model = broken("nr_sv 1 1", "nr_sv 2");
+        assert!(matches!(DenseSVM::try_from(model.as_str()), Err(Error::HeaderLengthMismatch { key: "nr_sv", expected: 2, found: 1 })));
+    }
+
+    #[test]
+    fn probability_length_mismatch() {
+        let model = broken("nr_sv 1 1", "probA 0.1 0.2\nprobB 0.1\nnr_sv 1 1");
+        assert!(matches!(DenseSVM::try_from(model.as_str()), Err(Error::HeaderLengthMismatch { key: "probA", expected: 1, found: 2 })));
+
+        let model = broken("nr_sv 1 1", "probA 0.1\nprobB 0.1 0.2\nnr_sv 1 1");
+        assert!(matches!(DenseSVM::try_from(model.as_str()), Err(Error::HeaderLengthMismatch { key: "probB", expected: 1, found: 2 })));
+    }
+
+    #[test]
+    fn probability_half_missing() {
+        let model = broken("nr_sv 1 1", "probA 0.1\nnr_sv 1 1");
+        assert!(matches!(DenseSVM::try_from(model.as_str()), Err(Error::HeaderLengthMismatch { key: "probB", expected: 1, found: 0 })));
+        assert!(matches!(SparseSVM::try_from(model.as_str()), Err(Error::HeaderLengthMismatch { key: "probB", expected: 1, found: 0 })));
+
+        let model = broken("nr_sv 1 1", "probB 0.1\nnr_sv 1 1");
+        assert!(matches!(DenseSVM::try_from(model.as_str()), Err(Error::HeaderLengthMismatch { key: "probA", expected: 1, found: 0 })));
+    }
+
+    #[test]
+    fn coefficient_count_mismatch() {
+        let model = broken("-1 0:1.2974607", "-1 0.5 0:1.2974607");
+        assert!(matches!(
+            DenseSVM::try_from(model.as_str()),
+            Err(Error::CoefficientCountMismatch { sv_index: 1, expected: 1, found: 2 })
+        ));
+    }
+
+    #[test]
+    fn dense_attribute_count_mismatch() -> Result<(), Error> {
+        let model = broken("2:1.2545854", "2:1.2545854 3:0.1 4:0.1");
+        let model = ModelFile::try_from(model.as_str())?;
+
+        assert_eq!(DenseSVM::try_from(&model)?.attributes(), 5);
+        assert!(matches!(
+            DenseSVM::with_attribute_policy(&model, AttributePolicy::Fixed(4)),
+            Err(Error::AttributeCountMismatch { sv_index: 1, expected: 4, found: 5 })
+        ));
+        assert!(matches!(
+            DenseSVM::with_attribute_policy(&model, AttributePolicy::Strict),
+            Err(Error::AttributeCountMismatch { sv_index: 1, expected: 4, found: 5 })
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn dense_omitted_attributes() -> Result<(), Error> {
+        let explicit = broken("1:1.0227317", "1:0");
+        let omitted = broken("1:1.0227317 ", "");
+
+        let model = ModelFile::try_from(omitted.as_str())?;
+        let svm = DenseSVM::try_from(&model)?;
+        let expected = DenseSVM::try_from(explicit.as_str())?;
+
+        let mut fv = FeatureVector::from(&svm);
+        let mut expected_fv = FeatureVector::from(&expected);
+        fv.features()[..4].copy_from_slice(&[0.5, 0.25, -0.5, 1.0]);
+        expected_fv.features()[..4].copy_from_slice(&[0.5, 0.25, -0.5, 1.0]);
+
+        svm.predict_value(&mut fv)?;
+        expected.predict_value(&mut expected_fv)?;
+
+        assert_eq!(svm.attributes(), 4);
+        assert_eq!(fv.label(), expected_fv.label());
+        assert_eq!(DenseSVM::with_attribute_policy(&model, AttributePolicy::Fixed(6))?.attributes(), 6);
+        assert!(matches!(
+            DenseSVM::with_attribute_policy(&model, AttributePolicy::Strict),
+            Err(Error::AttributesUnordered { index: 2, last_index: 0, .. })
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn dense_attributes_unordered() {
+        let model = broken("1:1.0227317 2:1.2545854", "2:1.2545854 1:1.0227317");
+        assert!(matches!(DenseSVM::try_from(model.as_str()), Err(Error::AttributesUnordered { index: 1, last_index: 2, .. })));
+    }
+
+    #[test]
+    fn dense_attributes_unordered_beyond_last() {
+        // The first attribute is beyond the number of attributes inferred from the last ones.
+        let model = broken("0 0:0.0001 1:0.0001 2:0.0001 3:0.0001", "0 0:0.0001 1:0.0001").replace("0:1.2974607 1:1.0227317 2:1.2545854", "9:1 1:1");
+        assert!(matches!(DenseSVM::try_from(model.as_str()), Err(Error::AttributesUnordered { index: 1, last_index: 9, .. })));
+
+        let model = model.replace("9:1 1:1", "1:1 9:1 2:1");
+        assert!(matches!(DenseSVM::try_from(model.as_str()), Err(Error::AttributesUnordered { index: 2, last_index: 9, .. })));
+    }
+
+    #[test]
+    fn dense_too_many_attributes() -> Result<(), Error> {
+        let model = broken("2:1.2545854", "2:1.2545854 4000000000:1");
+        assert!(matches!(DenseSVM::try_from(model.as_str()), Err(Error::TooManyAttributes { found: 4_000_000_001, .. })));
+        assert!(SparseSVM::try_from(model.as_str()).is_ok());
+
+        // Choosing the number of attributes explicitly lifts the limit.
+        let model = broken("2:1.2545854", "2:1.2545854 10000000:1");
+        assert!(matches!(DenseSVM::try_from(model.as_str()), Err(Error::TooManyAttributes { limit: 8_388_608, found: 10_000_001 })));
+        DenseSVM::with_attribute_policy(&ModelFile::try_from(model.as_str())?, AttributePolicy::Fixed(10_000_001))?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn sparse_attributes_unordered() {
+        let model = broken("1:1.0227317 2:1.2545854", "2:1.2545854 1:1.0227317");
+        assert!(matches!(SparseSVM::try_from(model.as_str()), Err(Error::AttributesUnordered { index: 1, last_index: 2, .. })));
+    }
+
+    #[test]
+    fn precomputed_invalid_serial() {
+        let model = "svm_type c_svc\nkernel_type precomputed\nnr_class 2\ntotal_sv 2\nrho 0.1\nlabel 1 2\nnr_sv 1 1\nSV\n1 0:1\n-1 0:2.5\n";
+        assert!(matches!(PrecomputedSVM::try_from(model), Err(Error::InvalidSerialNumber { sv_index: 1 })));
+
+        let model = model.replace("0:2.5", "1:2");
+        assert!(matches!(PrecomputedSVM::try_from(model.as_str()), Err(Error::InvalidSerialNumber { sv_index: 1 })));
+
+        let model = model.replace("1:2", "0:2");
+        assert!(PrecomputedSVM::try_from(model.as_str()).is_ok());
+
+        // Serial numbers that can't be read exactly, or would need huge kernel rows.
+        for serial in ["16777217", "3000000000"] {
+            let model = model.replace("0:2", &format!("0:{serial}"));
+            assert!(matches!(PrecomputedSVM::try_from(model.as_str()), Err(Error::InvalidSerialNumber { sv_index: 1 })));
+        }
+
+        let model = model.replace("0:2", "0:2000000");
+        assert!(matches!(PrecomputedSVM::try_from(model.as_str()), Err(Error::TooManyAttributes { limit: 1_048_576, found: 2_000_001 })));
+    }
+}
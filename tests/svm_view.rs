@@ -0,0 +1,107 @@
+use std::{fs, path::PathBuf};
+
+/// Returns the content of all models in the given test data folder.
+fn models(folder: &str) -> Vec<(PathBuf, String)> {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests").join(folder);
+    let mut models = fs::read_dir(path)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|x| x == "libsvm"))
+        .map(|path| {
+            let model = fs::read_to_string(&path).unwrap();
+            (path, model)
+        })
+        .collect::<Vec<_>>();
+
+    models.sort();
+    models
+}
+
+/// Copies `bytes` into `buffer` so that they start `misalignment` bytes past a 32 byte boundary.
+fn aligned(buffer: &mut Vec<u8>, bytes: &[u8], misalignment: usize) -> std::ops::Range<usize> {
+    buffer.resize(bytes.len() + 64, 0);
+
+    let start = buffer.as_ptr().align_offset(32) + misalignment;
+    buffer[start..start + bytes.len()].copy_from_slice(bytes);
+    start..start + bytes.len()
+}
+
+#[cfg(test)]
+mod svm_view {
+    use super::{aligned, models};
+    use ffsvm::{DenseFeatures, DenseSVM, DenseSVMView, Error, Predict, SAMPLE_MODEL};
+    use std::convert::TryFrom;
+
+    #[test]
+    fn predicts_like_dense_svm() -> Result<(), Error> {
+        for (path, model) in models("data_dense") {
+            let svm = DenseSVM::try_from(model.as_str())?;
+            let bytes = svm.to_bytes();
+
+            let mut buffer = Vec::new();
+            let range = aligned(&mut buffer, &bytes, 0);
+            let view = DenseSVMView::try_from(&buffer[range])?;
+
+            assert_eq!(svm.attributes(), view.attributes(), "{path:?}");
+            assert_eq!(svm.classes(), view.classes(), "{path:?}");
+
+            for features in [[0.000_1; 8], [1.287_784_9, 0.986_031_7, 1.486_247_2, 1.128_083, 0.891_030_55, 1.164_363_4, 0.928_599_1, 1.140_762_9]] {
+                let mut problem = DenseFeatures::from(&svm);
+                let mut problem_view = DenseFeatures::from(&view);
+
+                problem.features().clone_from_slice(&features);
+                problem_view.features().clone_from_slice(&features);
+
+                svm.predict_probability(&mut problem).or_else(|_| svm.predict_value(&mut problem))?;
+                view.predict_probability(&mut problem_view).or_else(|_| view.predict_value(&mut problem_view))?;
+
+                assert_eq!(problem.label(), problem_view.label(), "{path:?}");
+                assert_eq!(problem.raw_value(), problem_view.raw_value(), "{path:?}");
+                assert_eq!(problem.probabilities(), problem_view.probabilities(), "{path:?}");
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn class_operations() -> Result<(), Error> {
+        let bytes = DenseSVM::try_from(SAMPLE_MODEL)?.to_bytes();
+
+        let mut buffer = Vec::new();
+        let range = aligned(&mut buffer, &bytes, 0);
+        let view = DenseSVMView::try_from(&buffer[range])?;
+
+        assert_eq!(None, view.class_index_for_label(0));
+        assert_eq!(Some(1), view.class_index_for_label(42));
+        assert_eq!(Some(42), view.class_label_for_index(1));
+
+        Ok(())
+    }
+
+    #[test]
+    fn unaligned() -> Result<(), Error> {
+        let bytes = DenseSVM::try_from(SAMPLE_MODEL)?.to_bytes();
+
+        for misalignment in [1, 4, 8, 16] {
+            let mut buffer = Vec::new();
+            let range = aligned(&mut buffer, &bytes, misalignment);
+
+            assert!(matches!(DenseSVMView::try_from(&buffer[range]), Err(Error::BinaryUnaligned { alignment: 32 })));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn truncated() -> Result<(), Error> {
+        let bytes = DenseSVM::try_from(SAMPLE_MODEL)?.to_bytes();
+
+        let mut buffer = Vec::new();
+        let range = aligned(&mut buffer, &bytes, 0);
+
+        assert!(matches!(DenseSVMView::try_from(&buffer[range.start..range.end - 32]), Err(Error::BinaryTruncated { .. })));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,121 @@
+use ffsvm::{Error, ModelFile};
+use std::{convert::TryFrom, fs, path::PathBuf};
+
+/// Returns the content of all models in the given test data folder.
+fn models(folder: &str) -> Vec<(PathBuf, String)> {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests").join(folder);
+    let mut models = fs::read_dir(path)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|x| x == "libsvm"))
+        .map(|path| {
+            let model = fs::read_to_string(&path).unwrap();
+            (path, model)
+        })
+        .collect::<Vec<_>>();
+
+    models.sort();
+    models
+}
+
+/// Writes the model as text, and makes sure the text parses back into the same model.
+fn written(model: &ModelFile<'_>) -> Result<String, Error> {
+    let text = model.to_string();
+    let reparsed = ModelFile::try_from(text.as_str())?;
+
+    assert_eq!(reparsed.to_string(), text);
+
+    Ok(text)
+}
+
+#[cfg(test)]
+mod svm_writing {
+    use super::{models, written};
+    use ffsvm::{DenseSVM, Error, FeatureVector, ModelFile, Predict, PrecomputedSVM, SparseSVM};
+    use std::convert::TryFrom;
+
+    #[test]
+    fn dense_models_round_trip() -> Result<(), Error> {
+        for (path, model) in models("data_dense") {
+            let raw_model = ModelFile::try_from(model.as_str())?;
+            let svm = DenseSVM::try_from(&raw_model)?;
+
+            let text = written(&ModelFile::from(&svm))?;
+            assert_eq!(text, written(&raw_model)?, "{path:?}");
+
+            let reloaded = DenseSVM::try_from(text.as_str())?;
+
+            let mut problem = FeatureVector::from(&svm);
+            let mut problem_reloaded = FeatureVector::from(&reloaded);
+            let features = [1.287_784_9, 0.986_031_7, 1.486_247_2, 1.128_083, 0.891_030_55, 1.164_363_4, 0.928_599_1, 1.140_762_9];
+
+            problem.features().clone_from_slice(&features);
+            problem_reloaded.features().clone_from_slice(&features);
+
+            svm.predict_probability(&mut problem).or_else(|_| svm.predict_value(&mut problem))?;
+            reloaded.predict_probability(&mut problem_reloaded).or_else(|_| reloaded.predict_value(&mut problem_reloaded))?;
+
+            assert_eq!(problem.label(), problem_reloaded.label(), "{path:?}");
+            assert_eq!(problem.raw_value(), problem_reloaded.raw_value(), "{path:?}");
+            assert_eq!(problem.probabilities(), problem_reloaded.probabilities(), "{path:?}");
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn sparse_models_round_trip() -> Result<(), Error> {
+        for (path, model) in models("data_sparse") {
+            let raw_model = ModelFile::try_from(model.as_str())?;
+            let svm = SparseSVM::try_from(&raw_model)?;
+
+            let text = written(&ModelFile::from(&svm))?;
+            assert_eq!(text, written(&raw_model)?, "{path:?}");
+
+            let reloaded = SparseSVM::try_from(text.as_str())?;
+
+            let mut problem = FeatureVector::from(&svm);
+            let mut problem_reloaded = FeatureVector::from(&reloaded);
+
+            for (index, value) in [(3, 0.930_907_6), (4, 1.264_398_9), (6, 1.417_500_6), (32, 1.090_475_8), (46, 1.475_075), (110, 1.500_999)] {
+                problem.features()[index] = value;
+                problem_reloaded.features()[index] = value;
+            }
+
+            svm.predict_probability(&mut problem).or_else(|_| svm.predict_value(&mut problem))?;
+            reloaded.predict_probability(&mut problem_reloaded).or_else(|_| reloaded.predict_value(&mut problem_reloaded))?;
+
+            assert_eq!(problem.label(), problem_reloaded.label(), "{path:?}");
+            assert_eq!(problem.raw_value(), problem_reloaded.raw_value(), "{path:?}");
+            assert_eq!(problem.probabilities(), problem_reloaded.probabilities(), "{path:?}");
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn precomputed_models_round_trip() -> Result<(), Error> {
+        for (path, model) in models("data_precomputed") {
+            let raw_model = ModelFile::try_from(model.as_str())?;
+            let svm = PrecomputedSVM::try_from(&raw_model)?;
+
+            let text = written(&ModelFile::from(&svm))?;
+            assert_eq!(text, written(&raw_model)?, "{path:?}");
+
+            let reloaded = PrecomputedSVM::try_from(text.as_str())?;
+            assert_eq!(svm.attributes(), reloaded.attributes(), "{path:?}");
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn writes_libsvm_format() -> Result<(), Error> {
+        let model = "svm_type epsilon_svr\nkernel_type polynomial\ndegree 3\ngamma 0.5\ncoef0 0\nnr_class 2\ntotal_sv 2\nrho -0.25\nprobA 0.125\nSV\n1 0:0.5 1:1 \n-1 0:1 1:0.25 \n";
+        let svm = DenseSVM::try_from(model)?;
+
+        assert_eq!(ModelFile::from(&svm).to_string(), model);
+
+        Ok(())
+    }
+}